@@ -7,10 +7,44 @@ pub const HIGHER_POOL_SEED: &str = "higher_pool";
 #[constant]
 pub const BET_SEED: &str = "prediction_bet";
 #[constant]
-pub const MARKET_LOCK_PERIOD: u64 = 576000; //more than two days
+pub const USER_POSITION_SEED: &str = "user_position";
 #[constant]
-pub const USDC_MINT: &str = "4zMMC9srt5Ri5X14GAgXhaHii3GnPAEERYPJgZJDncDU";
+pub const POSITION_SEED: &str = "position";
 #[constant]
-pub const INITIAL_USDC_POOL_AMOUNT: u64 = 1000000;
+pub const CONFIG_SEED: &str = "config";
+#[constant]
+pub const ALLOWED_MINT_SEED: &str = "allowed_mint";
+#[constant]
+pub const TREASURY_SEED: &str = "treasury";
+#[constant]
+pub const TREASURY_VAULT_SEED: &str = "treasury_vault";
+#[constant]
+pub const PRICE_HISTORY_SEED: &str = "price_history";
+#[constant]
+pub const BOND_VAULT_SEED: &str = "bond_vault";
+#[constant]
+pub const OUTCOME_POOL_SEED: &str = "outcome_pool";
+#[constant]
+pub const HIGHER_MINT_SEED: &str = "higher_mint";
+#[constant]
+pub const LOWER_MINT_SEED: &str = "lower_mint";
+#[constant]
+pub const SET_VAULT_SEED: &str = "set_vault";
 #[constant]
 pub const ODDS_FIXED_POINT_MULTIPLIER: u64 = 1_000_000;
+#[constant]
+pub const BPS_DENOMINATOR: u64 = 10_000;
+#[constant]
+pub const MAX_FEE_BPS: u16 = 1000; //each fee is capped at 10% of the amount it is taken from
+#[constant]
+pub const MAX_SECONDARY_FEEDS: u8 = 4; //a market is resolved from at most five oracle feeds
+#[constant]
+pub const MAX_TARGET_EXPONENT: u32 = 18; //10^18 still leaves headroom for a u64 price
+#[constant]
+pub const MAX_PRICE_SAMPLES: u8 = 32; //ring buffer size of a twap market's PriceHistory
+#[constant]
+pub const MIN_TWAP_SAMPLES: u8 = 3;
+#[constant]
+pub const MAX_COMMITTEE_SIZE: u8 = 7;
+#[constant]
+pub const MAX_OUTCOMES: u8 = 16;
@@ -4,7 +4,7 @@ use anchor_lang::prelude::*;
 pub enum MarketError {
     #[msg("Pyth Solana Feed ID is expected to have 66 characters")]
     IncorrectFeedIDLength,
-    #[msg("Market Duration Can not be less than 1200 slots")]
+    #[msg("Market Duration Can not be less than the configured minimum")]
     ShortMarketDuration,
     #[msg("Only the account creator can change account state")]
     UnauthorizedUser,
@@ -12,11 +12,13 @@ pub enum MarketError {
     BetIsClaimed,
     #[msg("Market key must match bet market key")]
     BetMarketMismatch,
+    //deprecated, no longer returned since the MarketStatus lifecycle replaced it. Kept so the
+    //error codes after it keep the numbers clients already match on
     #[msg("Invalid market stage. Must perform instruction at correct time period")]
     InvalidMarketInitialization,
     #[msg("Market betting Duration is not over yet")]
     MarketDurationNotOver,
-    #[msg("576000 slots must pass after Market betting period before closing market")]
+    #[msg("The configured lock period must pass after Market betting period before closing market")]
     MarketLockPeriodNotOver,
     #[msg("Bet Can only be placed during the market duration")]
     MarketDurationOver,
@@ -32,4 +34,148 @@ pub enum MarketError {
     InvalidFeedId,
     #[msg("Overflow occured when adjusting the oracle price data")]
     PriceAdjustmentOverflow,
+    #[msg("Overflow occured when computing the bet payout")]
+    PayoutOverflow,
+    #[msg("Market must be resolved before bets can be claimed")]
+    MarketNotResolved,
+    #[msg("Resolution window must be between 1 second and the configured maximum")]
+    InvalidResolutionWindow,
+    #[msg("Price update must be fully verified to resolve a market")]
+    UnverifiedPriceUpdate,
+    #[msg("Price update was published before the market expired")]
+    PriceUpdateTooEarly,
+    #[msg("Price update was published after the market resolution window")]
+    PriceUpdateTooLate,
+    #[msg("Overflow occured when computing the market expiry")]
+    MarketDurationOverflow,
+    #[msg("Market is not open for betting")]
+    MarketNotOpen,
+    #[msg("Market pools are already initialized")]
+    MarketAlreadyOpen,
+    #[msg("Market is already locked")]
+    MarketAlreadyLocked,
+    #[msg("Market is already resolved or voided")]
+    MarketAlreadyResolved,
+    #[msg("Market must be settled before it can be closed")]
+    MarketNotSettled,
+    #[msg("Market is already closed")]
+    MarketAlreadyClosed,
+    #[msg("Market must be voided before bets can be refunded")]
+    MarketNotVoided,
+    #[msg("Invalid market status transition")]
+    InvalidStatusTransition,
+    #[msg("Position has no stake to claim")]
+    EmptyPosition,
+    #[msg("Fee can not be higher than 1000 basis points")]
+    FeeTooHigh,
+    #[msg("Creator fees must be withdrawn before closing the market")]
+    UnwithdrawnCreatorFees,
+    #[msg("There are no fees to withdraw")]
+    NoFeesToWithdraw,
+    #[msg("Config values are out of range")]
+    InvalidConfig,
+    #[msg("There is no pending admin to accept")]
+    NoPendingAdmin,
+    #[msg("Minimum bet must be positive and not above the maximum bet")]
+    InvalidBetLimits,
+    #[msg("Bet amount is below the market minimum")]
+    BetTooSmall,
+    #[msg("Bet amount is above the market maximum")]
+    BetTooLarge,
+    #[msg("Mints with transfer fee, transfer hook or permanent delegate extensions are not supported")]
+    UnsupportedMintExtension,
+    #[msg("The void grace period after the market duration is not over yet")]
+    VoidGracePeriodNotOver,
+    #[msg("Maximum confidence must be between 1 and 9999 basis points")]
+    InvalidConfidenceLimit,
+    #[msg("Oracle confidence interval is wider than the market allows")]
+    ConfidenceTooWide,
+    #[msg("Oracle confidence interval straddles the target, outcome is undetermined")]
+    OutcomeUndetermined,
+    #[msg("Oracle price must be positive")]
+    NonPositivePrice,
+    #[msg("Target exponent is out of the supported range")]
+    InvalidTargetExponent,
+    #[msg("Oracle account does not belong to the market's oracle source or feed")]
+    InvalidOracleAccount,
+    #[msg("Too many secondary oracle feeds")]
+    TooManyOracleFeeds,
+    #[msg("Oracle feeds must be distinct")]
+    DuplicateOracleFeed,
+    #[msg("Oracle quorum must be between 1 and the number of feeds")]
+    InvalidOracleQuorum,
+    #[msg("An account must be passed for every secondary oracle feed")]
+    MissingOracleFeeds,
+    #[msg("Not enough fresh oracle prices within the confidence limit to resolve")]
+    OracleQuorumNotMet,
+    #[msg("Market does not resolve in this mode")]
+    WrongResolutionMode,
+    #[msg("Twap window must fit the market duration with a second per sample and twap markets can only use one feed")]
+    InvalidTwapConfig,
+    #[msg("Price sample was published too soon after the last recorded sample")]
+    StalePriceSample,
+    #[msg("Not enough price samples were recorded to cover the twap window")]
+    InsufficientPriceSamples,
+    #[msg("Committee markets need 1 to 7 distinct members and a threshold no larger than the committee")]
+    InvalidCommittee,
+    #[msg("Signer is not a member of the market's committee")]
+    NotCommitteeMember,
+    #[msg("No outcome has been proposed for this market")]
+    NoProposedOutcome,
+    #[msg("Outcome does not match the proposed outcome")]
+    ProposedOutcomeMismatch,
+    #[msg("Committee member already backed an outcome")]
+    AlreadyApproved,
+    #[msg("Optimistic markets need a positive bond and challenge period and no feeds or committee")]
+    InvalidOptimisticConfig,
+    #[msg("An outcome has already been proposed for this market")]
+    OutcomeAlreadyProposed,
+    #[msg("The challenge window of the proposal is closed")]
+    ChallengeWindowClosed,
+    #[msg("The challenge window of the proposal is still open")]
+    ChallengeWindowOpen,
+    #[msg("The proposed outcome has already been disputed")]
+    AlreadyDisputed,
+    #[msg("The proposed outcome is not disputed")]
+    MarketNotDisputed,
+    #[msg("Account does not match the proposer or disputer owed the bonds")]
+    WrongBondRecipient,
+    #[msg("Instruction does not apply to this kind of market")]
+    WrongMarketKind,
+    #[msg("Categorical markets need 2 to 16 outcomes with ascending bounds, spot resolution and one feed")]
+    InvalidOutcomeBounds,
+    #[msg("Outcome index is out of range for this market")]
+    InvalidOutcomeIndex,
+    #[msg("Account is not the expected outcome pool of this market")]
+    InvalidOutcomePool,
+    #[msg("Instruction is not supported by the pricing mode of this market")]
+    WrongPricingMode,
+    #[msg("AMM reserves are empty")]
+    EmptyReserves,
+    #[msg("Fewer shares out than the minimum requested")]
+    SlippageExceeded,
+    #[msg("Fixed-point input is out of range")]
+    FixedPointOutOfRange,
+    #[msg("Outcome tokens of the losing direction pay nothing")]
+    LosingOutcomeToken,
+    #[msg("Outcome tokens can only be redeemed once the market is resolved or voided")]
+    MarketNotRedeemable,
+    #[msg("The complete set vault holds less collateral than requested")]
+    InsufficientSetCollateral,
+    #[msg("The complete set vault of a market with outcome tokens is missing")]
+    MissingSetVault,
+    #[msg("Price update is not the first one published at or after the market expiry")]
+    PriceUpdateNotFirstAfterExpiry,
+    #[msg("Overflow occured when counting the open bets of the market")]
+    OpenBetsOverflow,
+    #[msg("Overflow occured when accruing fees")]
+    FeeOverflow,
+    #[msg("Switchboard feeds do not report when their result was published and can not settle a spot market")]
+    UnsupportedOracleSource,
+    #[msg("Pass either a position, or a bet with its user position")]
+    InvalidBetAccounts,
+    #[msg("The bond vault and bond recipients of a proposed or disputed market are missing")]
+    MissingBondAccounts,
+    #[msg("Scalar markets need a lower bound below the upper bound, outcome tokens and a spot or twap price")]
+    InvalidScalarBounds,
 }
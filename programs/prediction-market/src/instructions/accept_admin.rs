@@ -0,0 +1,30 @@
+use anchor_lang::prelude::*;
+
+use crate::constants::*;
+use crate::states::ProgramConfig;
+use crate::MarketError;
+
+pub fn _accept_admin(
+    ctx: Context<AcceptAdmin>,
+) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+    let pending_admin = config.pending_admin.ok_or(MarketError::NoPendingAdmin)?;
+    require_keys_eq!(ctx.accounts.new_admin.key(), pending_admin, MarketError::UnauthorizedUser);
+
+    config.admin = pending_admin;
+    config.pending_admin = None;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct AcceptAdmin<'info> {
+    #[account(
+        mut,
+        seeds = [CONFIG_SEED.as_bytes()],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, ProgramConfig>,
+
+    pub new_admin: Signer<'info>,
+}
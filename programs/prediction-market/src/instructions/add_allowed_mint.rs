@@ -0,0 +1,54 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::Mint;
+
+use crate::constants::*;
+use crate::states::{AllowedMint, ProgramConfig};
+use crate::MarketError;
+use crate::utils::validate_mint_extensions;
+
+pub fn _add_allowed_mint(
+    ctx: Context<AddAllowedMint>,
+    min_bet: u64,
+    max_bet: u64,
+    seed_liquidity_amount: u64,
+) -> Result<()> {
+    require!(min_bet > 0 && min_bet <= max_bet, MarketError::InvalidBetLimits);
+    validate_mint_extensions(&ctx.accounts.mint.to_account_info())?;
+
+    let allowed_mint = &mut ctx.accounts.allowed_mint;
+    allowed_mint.mint = ctx.accounts.mint.key();
+    allowed_mint.min_bet = min_bet;
+    allowed_mint.max_bet = max_bet;
+    allowed_mint.seed_liquidity_amount = seed_liquidity_amount;
+    allowed_mint.bump = ctx.bumps.allowed_mint;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct AddAllowedMint<'info> {
+    #[account(
+        seeds = [CONFIG_SEED.as_bytes()],
+        bump = config.bump,
+        has_one = admin @ MarketError::UnauthorizedUser,
+    )]
+    pub config: Account<'info, ProgramConfig>,
+
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + AllowedMint::INIT_SPACE,
+        seeds = [
+            ALLOWED_MINT_SEED.as_bytes(),
+            mint.key().as_ref(),
+        ],
+        bump
+    )]
+    pub allowed_mint: Account<'info, AllowedMint>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
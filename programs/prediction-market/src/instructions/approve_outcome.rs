@@ -0,0 +1,65 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::TokenAccount;
+
+use crate::constants::*;
+use crate::instructions::resolve_if_approved;
+use crate::states::*;
+use crate::MarketError;
+use crate::utils::hash_to_bytes;
+
+
+pub fn _approve_outcome(
+    ctx: Context<ApproveOutcome>,
+    outcome: Outcome,
+) -> Result<()> {
+    let market = &mut ctx.accounts.market;
+    let clock = Clock::get()?;
+
+    require!(market.resolution_mode == ResolutionMode::Committee, MarketError::WrongResolutionMode);
+    market.require_status(MarketStatus::Locked, clock.unix_timestamp)?;
+    let member = market.committee_index(&ctx.accounts.approver.key())?;
+
+    require!(market.approval_count(outcome) > 0, MarketError::NoProposedOutcome);
+    market.record_approval(member, outcome)?;
+
+    resolve_if_approved(market, outcome, &ctx.accounts.higher_pool, &ctx.accounts.lower_pool, &clock)
+}
+
+#[derive(Accounts)]
+pub struct ApproveOutcome<'info> {
+    #[account(
+        mut,
+        seeds = [
+            market.creator.key().as_ref(), 
+            &hash_to_bytes(&market.feed_id),
+            &market.target_price.to_le_bytes(), 
+            &market.market_duration.to_le_bytes(),
+        ],
+        bump = market.bump,
+    )]
+    pub market: Account<'info, Market>,
+
+    #[account(
+        token::mint = market.mint, 
+        token::authority = market,
+        seeds = [
+            HIGHER_POOL_SEED.as_bytes(),
+            market.key().as_ref(),
+        ],
+        bump = market.higher_pool_bump,
+    )]
+    pub higher_pool: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        token::mint = market.mint, 
+        token::authority = market,
+        seeds = [
+            LOWER_POOL_SEED.as_bytes(),
+            market.key().as_ref()
+        ],
+        bump = market.lower_pool_bump,
+    )]
+    pub lower_pool: InterfaceAccount<'info, TokenAccount>,
+
+    pub approver: Signer<'info>,
+}
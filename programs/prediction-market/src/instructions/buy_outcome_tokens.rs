@@ -0,0 +1,211 @@
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token_interface::*;
+
+use crate::constants::*;
+use crate::instructions::buy_from_market_maker;
+use crate::math::fee_amount;
+use crate::states::*;
+use crate::MarketError;
+use crate::utils::hash_to_bytes;
+
+pub fn _buy_outcome_tokens(
+    ctx: Context<BuyOutcomeTokens>,
+    bet_amount: u64,
+    bet_direction: Direction,
+    min_shares_out: u64,
+) -> Result<()> {
+    let market = &mut ctx.accounts.market;
+
+    let clock = Clock::get()?;
+    market.require_status(MarketStatus::Open, clock.unix_timestamp)?;
+    require!(market.outcome_tokens, MarketError::WrongMarketKind);
+    require_gte!(bet_amount, market.min_bet, MarketError::BetTooSmall);
+    require_gte!(market.max_bet, bet_amount, MarketError::BetTooLarge);
+
+    //a token does not remember what was paid for it, so fees are taken from the stake up front
+    let protocol_fee = fee_amount(bet_amount, market.protocol_fee_bps)?;
+    let creator_fee = fee_amount(bet_amount, market.creator_fee_bps)?;
+    let fees = protocol_fee.checked_add(creator_fee).ok_or(MarketError::FeeOverflow)?;
+    let stake = bet_amount.checked_sub(fees).ok_or(MarketError::PayoutOverflow)?;
+
+    let shares_out = buy_from_market_maker(market, stake, &bet_direction)?;
+    require_gte!(shares_out, min_shares_out, MarketError::SlippageExceeded);
+    market.outstanding_outcome_tokens = market.outstanding_outcome_tokens
+        .checked_add(shares_out)
+        .ok_or(MarketError::PayoutOverflow)?;
+    let token_cost = market.token_cost_mut(&bet_direction);
+    *token_cost = token_cost.checked_add(stake).ok_or(MarketError::PayoutOverflow)?;
+
+    let bet_pool: AccountInfo = match bet_direction {
+        Direction::Higher => ctx.accounts.higher_pool.to_account_info(),
+        Direction::Lower => ctx.accounts.lower_pool.to_account_info()
+    };
+
+    transfer_checked(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            TransferChecked {
+                from: ctx.accounts.user_ata.to_account_info(),
+                mint: ctx.accounts.mint.to_account_info(),
+                to: bet_pool,
+                authority: ctx.accounts.user.to_account_info(),
+            },
+        ),
+        stake,
+        ctx.accounts.mint.decimals,
+    )?;
+
+    if fees > 0 {
+        transfer_checked(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                TransferChecked {
+                    from: ctx.accounts.user_ata.to_account_info(),
+                    mint: ctx.accounts.mint.to_account_info(),
+                    to: ctx.accounts.treasury_vault.to_account_info(),
+                    authority: ctx.accounts.user.to_account_info(),
+                },
+            ),
+            fees,
+            ctx.accounts.mint.decimals,
+        )?;
+    }
+
+    ctx.accounts.treasury.protocol_fees = ctx.accounts.treasury.protocol_fees
+        .checked_add(protocol_fee)
+        .ok_or(MarketError::FeeOverflow)?;
+    market.creator_fees = market.creator_fees
+        .checked_add(creator_fee)
+        .ok_or(MarketError::FeeOverflow)?;
+
+    let market = &ctx.accounts.market;
+    mint_to(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            MintTo {
+                mint: ctx.accounts.outcome_mint.to_account_info(),
+                to: ctx.accounts.user_outcome_ata.to_account_info(),
+                authority: market.to_account_info(),
+            },
+            &[&[
+                market.creator.as_ref(),
+                &hash_to_bytes(&market.feed_id),
+                &market.target_price.to_le_bytes(),
+                &market.market_duration.to_le_bytes(),
+                &[market.bump],
+            ]],
+        ),
+        shares_out,
+    )?;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(bet_amount: u64, bet_direction: Direction)]
+pub struct BuyOutcomeTokens<'info> {
+    #[account(
+        mut,
+        seeds = [
+            market.creator.key().as_ref(),
+            &hash_to_bytes(&market.feed_id),
+            &market.target_price.to_le_bytes(),
+            &market.market_duration.to_le_bytes(),
+        ],
+        bump = market.bump,
+    )]
+    pub market: Box<Account<'info, Market>>,
+
+    #[account(
+        mut,
+        token::mint = market.mint,
+        token::authority = market,
+        token::token_program = token_program,
+        seeds = [
+            HIGHER_POOL_SEED.as_bytes(),
+            market.key().as_ref(),
+        ],
+        bump = market.higher_pool_bump,
+    )]
+    pub higher_pool: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        token::mint = market.mint,
+        token::authority = market,
+        token::token_program = token_program,
+        seeds = [
+            LOWER_POOL_SEED.as_bytes(),
+            market.key().as_ref()
+        ],
+        bump = market.lower_pool_bump,
+    )]
+    pub lower_pool: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    //mint of the direction bought
+    #[account(
+        mut,
+        mint::authority = market,
+        mint::token_program = token_program,
+        seeds = [
+            bet_direction.mint_seed(),
+            market.key().as_ref(),
+        ],
+        bump = market.outcome_mint_bump(&bet_direction),
+    )]
+    pub outcome_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        init_if_needed,
+        payer = user,
+        associated_token::mint = outcome_mint,
+        associated_token::authority = user,
+        associated_token::token_program = token_program,
+    )]
+    pub user_outcome_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        associated_token::mint = market.mint,
+        associated_token::authority = user,
+        associated_token::token_program = token_program,
+    )]
+    pub user_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [
+            TREASURY_SEED.as_bytes(),
+            market.mint.as_ref(),
+        ],
+        bump = treasury.bump,
+    )]
+    pub treasury: Account<'info, Treasury>,
+
+    #[account(
+        mut,
+        token::mint = market.mint,
+        token::authority = treasury,
+        token::token_program = token_program,
+        seeds = [
+            TREASURY_VAULT_SEED.as_bytes(),
+            market.mint.as_ref(),
+        ],
+        bump = treasury.vault_bump,
+    )]
+    pub treasury_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        address = market.mint,
+        mint::token_program = token_program,
+    )]
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+
+    pub system_program: Program<'info, System>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
@@ -0,0 +1,189 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::*;
+
+use crate::constants::*;
+use crate::math::{cpmm_buy, lmsr_buy};
+use crate::states::*;
+use crate::MarketError;
+use crate::utils::hash_to_bytes;
+
+pub fn _buy_shares(
+    ctx: Context<BuyShares>,
+    bet_amount: u64,
+    bet_direction: Direction,
+    min_shares_out: u64,
+) -> Result<()> {
+    let market = &mut ctx.accounts.market;
+
+    let clock = Clock::get()?;
+    market.require_status(MarketStatus::Open, clock.unix_timestamp)?;
+    //tokenized markets pay shares out per token, mixing in Bet accounts would split the pools two ways
+    require!(!market.outcome_tokens, MarketError::WrongMarketKind);
+    require_gte!(bet_amount, market.min_bet, MarketError::BetTooSmall);
+    require_gte!(market.max_bet, bet_amount, MarketError::BetTooLarge);
+
+    let shares_out = buy_from_market_maker(market, bet_amount, &bet_direction)?;
+    require_gte!(shares_out, min_shares_out, MarketError::SlippageExceeded);
+
+    let bet_pool: AccountInfo = match bet_direction {
+        Direction::Higher => ctx.accounts.higher_pool.to_account_info(),
+        Direction::Lower => ctx.accounts.lower_pool.to_account_info()
+    };
+
+    transfer_checked(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            TransferChecked {
+                from: ctx.accounts.user_ata.to_account_info(),
+                mint: ctx.accounts.mint.to_account_info(),
+                to: bet_pool,
+                authority: ctx.accounts.user.to_account_info(),
+            },
+        ),
+        bet_amount,
+        ctx.accounts.mint.decimals,
+    )?;
+
+    market.open_bets = market.open_bets.checked_add(1).ok_or(MarketError::OpenBetsOverflow)?;
+
+    let user_position = &mut ctx.accounts.user_position;
+    user_position.user = ctx.accounts.user.key();
+    user_position.market = ctx.accounts.market.key();
+    user_position.bump = ctx.bumps.user_position;
+    let bet_id = user_position.next_bet_id;
+    user_position.next_bet_id = bet_id.checked_add(1).ok_or(MarketError::OpenBetsOverflow)?;
+
+    let bet = &mut ctx.accounts.bet;
+    bet.user = ctx.accounts.user.key();
+    bet.bump = ctx.bumps.bet;
+    bet.amount = bet_amount;
+    bet.shares = shares_out;
+    //payout per unit staked if the direction wins
+    bet.odds = ((shares_out as u128 * ODDS_FIXED_POINT_MULTIPLIER as u128) / bet_amount as u128)
+        .try_into()
+        .unwrap_or(u64::MAX);
+    bet.claimed = false;
+    bet.market = ctx.accounts.market.key();
+    bet.direction = bet_direction;
+    bet.initialized = true;
+    bet.id = bet_id;
+
+    Ok(())
+}
+
+//prices the trade along the market maker's curve and books it, returning the shares bought
+pub fn buy_from_market_maker(market: &mut Market, amount: u64, direction: &Direction) -> Result<u64> {
+    match (market.pricing_mode, direction) {
+        (PricingMode::Parimutuel, _) => err!(MarketError::WrongPricingMode),
+        (PricingMode::Cpmm, Direction::Higher) => {
+            let trade = cpmm_buy(amount, market.higher_reserve, market.lower_reserve)?;
+            market.higher_reserve = trade.own_reserve;
+            market.lower_reserve = trade.other_reserve;
+            Ok(trade.shares_out)
+        }
+        (PricingMode::Cpmm, Direction::Lower) => {
+            let trade = cpmm_buy(amount, market.lower_reserve, market.higher_reserve)?;
+            market.lower_reserve = trade.own_reserve;
+            market.higher_reserve = trade.other_reserve;
+            Ok(trade.shares_out)
+        }
+        (PricingMode::Lmsr, Direction::Higher) => {
+            let trade = lmsr_buy(amount, market.higher_shares, market.lower_shares, market.liquidity_param)?;
+            market.higher_shares = trade.own_shares;
+            Ok(trade.shares_out)
+        }
+        (PricingMode::Lmsr, Direction::Lower) => {
+            let trade = lmsr_buy(amount, market.lower_shares, market.higher_shares, market.liquidity_param)?;
+            market.lower_shares = trade.own_shares;
+            Ok(trade.shares_out)
+        }
+    }
+}
+
+#[derive(Accounts)]
+pub struct BuyShares<'info> {
+    #[account(
+        mut,
+        seeds = [
+            market.creator.key().as_ref(), 
+            &hash_to_bytes(&market.feed_id),
+            &market.target_price.to_le_bytes(), 
+            &market.market_duration.to_le_bytes(),
+        ],
+        bump = market.bump,
+    )]
+    pub market: Account<'info, Market>,
+
+    #[account(
+        mut,
+        token::mint = market.mint, 
+        token::authority = market,
+        token::token_program = token_program,
+        seeds = [
+            HIGHER_POOL_SEED.as_bytes(),
+            market.key().as_ref(),
+        ],
+        bump = market.higher_pool_bump,
+    )]
+    pub higher_pool: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        token::mint = market.mint, 
+        token::authority = market,
+        token::token_program = token_program,
+        seeds = [
+            LOWER_POOL_SEED.as_bytes(),
+            market.key().as_ref()
+        ],
+        bump = market.lower_pool_bump,
+    )]
+    pub lower_pool: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        associated_token::mint = market.mint,
+        associated_token::authority = user,
+        associated_token::token_program = token_program,
+    )]
+    pub user_ata: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+    
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = 8 + UserMarketPosition::INIT_SPACE,
+        seeds = [
+            USER_POSITION_SEED.as_bytes(),
+            user.key().as_ref(),
+            market.key().as_ref(),
+        ],
+        bump
+    )]
+    pub user_position: Account<'info, UserMarketPosition>,
+
+    #[account(
+        init,
+        payer = user,
+        space = 8 + Bet::INIT_SPACE,
+        seeds = [
+            BET_SEED.as_bytes(),
+            user.key().as_ref(),
+            market.key().as_ref(),
+            user_position.next_bet_id.to_le_bytes().as_ref(),
+        ],
+        bump
+    )]
+    pub bet: Account<'info,Bet>,
+
+    pub system_program: Program<'info, System>,
+    #[account(
+        address = market.mint,
+        mint::token_program = token_program,
+    )]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
@@ -1,5 +1,5 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{close_account, CloseAccount,transfer,Transfer, Token, TokenAccount};
+use anchor_spl::token_interface::{close_account, CloseAccount,transfer_checked,TransferChecked, Mint, TokenAccount, TokenInterface};
 
 use crate::constants::*;
 use crate::states::*;
@@ -10,19 +10,26 @@ use crate::utils::hash_to_bytes;
 pub fn _cancel_market(
     ctx: Context<CancelMarket>,
 ) -> Result<()> {
-    let market = &ctx.accounts.market;
+    let market = &mut ctx.accounts.market;
     let higher_pool = &ctx.accounts.higher_pool;
     let lower_pool = &ctx.accounts.lower_pool;
     let creator = &ctx.accounts.market_creator;
+    let clock = Clock::get()?;
 
-    require_eq!(higher_pool.amount + lower_pool.amount,INITIAL_USDC_POOL_AMOUNT*2,MarketError::NonZeroPools);
     require_keys_eq!(creator.key(),market.creator,MarketError::UnauthorizedUser);
+    //a market nobody bet on can be cancelled while open or after betting closed, it has nothing to resolve
+    require_eq!(market.open_bets,0,MarketError::NonZeroPools);
+    require_eq!(market.outstanding_outcome_tokens,0,MarketError::NonZeroPools);
+    market.transition(MarketStatus::Closed, clock.unix_timestamp)?;
+
+    let market = &ctx.accounts.market;
 
-    transfer(
+    transfer_checked(
         CpiContext::new_with_signer(
             ctx.accounts.token_program.to_account_info(),
-            Transfer {
+            TransferChecked {
                 from: higher_pool.to_account_info(),
+                mint: ctx.accounts.mint.to_account_info(),
                 to: ctx.accounts.creator_ata.to_account_info(),
                 authority: ctx.accounts.market.to_account_info(),
             },
@@ -34,14 +41,16 @@ pub fn _cancel_market(
             &[ctx.accounts.market.bump],
         ]],
         ),
-        INITIAL_USDC_POOL_AMOUNT,
+        higher_pool.amount,
+        ctx.accounts.mint.decimals,
     )?;
 
-    transfer(
+    transfer_checked(
         CpiContext::new_with_signer(
             ctx.accounts.token_program.to_account_info(),
-            Transfer {
+            TransferChecked {
                 from: lower_pool.to_account_info(),
+                mint: ctx.accounts.mint.to_account_info(),
                 to: ctx.accounts.creator_ata.to_account_info(),
                 authority: ctx.accounts.market.to_account_info(),
             },
@@ -53,7 +62,8 @@ pub fn _cancel_market(
             &[ctx.accounts.market.bump],
         ]],
         ),
-        INITIAL_USDC_POOL_AMOUNT,
+        lower_pool.amount,
+        ctx.accounts.mint.decimals,
     )?;
 
     close_account(CpiContext::new_with_signer(
@@ -88,6 +98,43 @@ pub fn _cancel_market(
         ]],
     ))?;
 
+    //no set is outstanding, so only the rent of the vault is left to return
+    if market.has_outcome_mints() {
+        let set_vault = ctx.accounts.set_vault.as_ref().ok_or(MarketError::MissingSetVault)?;
+        let signer_seeds: &[&[&[u8]]] = &[&[
+            market.creator.as_ref(),
+            &hash_to_bytes(&market.feed_id),
+            &market.target_price.to_le_bytes(),
+            &market.market_duration.to_le_bytes(),
+            &[market.bump],
+        ]];
+
+        transfer_checked(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                TransferChecked {
+                    from: set_vault.to_account_info(),
+                    mint: ctx.accounts.mint.to_account_info(),
+                    to: ctx.accounts.creator_ata.to_account_info(),
+                    authority: market.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            set_vault.amount,
+            ctx.accounts.mint.decimals,
+        )?;
+
+        close_account(CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            CloseAccount {
+                account: set_vault.to_account_info(),
+                destination: ctx.accounts.market_creator.to_account_info(),
+                authority: market.to_account_info(),
+            },
+            signer_seeds,
+        ))?;
+    }
+
     Ok(())
 }
 
@@ -108,36 +155,51 @@ pub struct CancelMarket<'info> {
 
     #[account(
         mut,
-        close = market_creator,
         token::mint = market.mint, 
         token::authority = market,
+        token::token_program = token_program,
         seeds = [
             HIGHER_POOL_SEED.as_bytes(),
             market.key().as_ref(), 
         ],
         bump = market.higher_pool_bump,
     )]
-    pub higher_pool: Account<'info, TokenAccount>,
+    pub higher_pool: InterfaceAccount<'info, TokenAccount>,
 
     #[account(
         mut,
-        close = market_creator,
         token::mint = market.mint, 
         token::authority = market,
+        token::token_program = token_program,
         seeds = [
             LOWER_POOL_SEED.as_bytes(),
             market.key().as_ref(),
         ],
         bump = market.lower_pool_bump,
     )]
-    pub lower_pool: Account<'info, TokenAccount>,
+    pub lower_pool: InterfaceAccount<'info, TokenAccount>,
+
+    //only exists once the outcome mints of the market are initialized
+    #[account(
+        mut,
+        token::mint = market.mint,
+        token::authority = market,
+        token::token_program = token_program,
+        seeds = [
+            SET_VAULT_SEED.as_bytes(),
+            market.key().as_ref(),
+        ],
+        bump = market.set_vault_bump,
+    )]
+    pub set_vault: Option<InterfaceAccount<'info, TokenAccount>>,
 
     #[account(
         mut,
         associated_token::mint = market.mint,
         associated_token::authority = market_creator,
+        associated_token::token_program = token_program,
     )]
-    pub creator_ata: Account<'info, TokenAccount>,
+    pub creator_ata: InterfaceAccount<'info, TokenAccount>,
 
     #[account(
         mut,
@@ -146,6 +208,12 @@ pub struct CancelMarket<'info> {
     pub market_creator: Signer<'info>,
 
     pub system_program: Program<'info, System>,
-    pub token_program: Program<'info, Token>,
+    #[account(
+        address = market.mint,
+        mint::token_program = token_program,
+    )]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    pub token_program: Interface<'info, TokenInterface>,
 
 }
@@ -1,9 +1,8 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::*;
-use num_traits::*;
-use pyth_solana_receiver_sdk::price_update::{get_feed_id_from_hex,PriceUpdateV2};
+use anchor_spl::token_interface::*;
 
 use crate::constants::*;
+use crate::math::{fee_amount, parimutuel_winnings};
 use crate::states::*;
 use crate::MarketError;
 use crate::utils::hash_to_bytes;
@@ -14,77 +13,216 @@ pub fn _claim_bet(
 ) -> Result<()> {
     let bet = &mut ctx.accounts.bet;
     let market = &mut ctx.accounts.market;
-    let price_update = &ctx.accounts.price_update;
     let clock = Clock::get()?;
 
-    require!(market.initialization == MarketInitialization::InitializedPools,MarketError::InvalidMarketInitialization);
+    market.require_status(MarketStatus::Resolved, clock.unix_timestamp)?;
     require_keys_eq!(market.key(),bet.market,MarketError::BetMarketMismatch);
     require_keys_eq!(ctx.accounts.user.key(),bet.user,MarketError::UnauthorizedUser);
-    require_gt!(clock.slot,market.start_time + market.market_duration,MarketError::MarketDurationNotOver);
     require_eq!(bet.claimed,false,MarketError::BetIsClaimed);
 
+    bet.is_won = market.is_winning_direction(&bet.direction)?;
 
-    if market.final_price.is_none() {
-        let feed_id_str = std::str::from_utf8(&market.feed_id)
-            .map_err(|_| MarketError::InvalidUtf8)?;
-
-        let feed_id = get_feed_id_from_hex(feed_id_str)
-            .map_err(|_| MarketError::InvalidFeedId)?;
-
-        let price =price_update.get_price_no_older_than(
-            &clock,
-            30_u64,
-            &feed_id 
-        )?;
+    let accounts = PayoutAccounts {
+        higher_pool: &ctx.accounts.higher_pool,
+        lower_pool: &ctx.accounts.lower_pool,
+        user_ata: &ctx.accounts.user_ata,
+        mint: &ctx.accounts.mint,
+        token_program: &ctx.accounts.token_program,
+    };
 
-        let adjusted_price = if price.exponent < 0 {
-            (price.price as u64).checked_mul(10_u64.pow(price.exponent.abs() as u32))
-                .ok_or(MarketError::PriceAdjustmentOverflow)?
-        } else {
-            (price.price as u64).checked_div(10_u64.pow(price.exponent as u32))
-                .ok_or(MarketError::PriceAdjustmentOverflow)?
+    if market.is_push() {
+        refund_stake(market, &accounts, &bet.direction, bet.amount)?;
+    } else if bet.is_won {
+        //an amm bet wins one unit per share, of which the stake is already its own
+        let winnings = match market.pricing_mode {
+            PricingMode::Parimutuel => {
+                let (winning_total, losing_total) = market.pool_totals(&bet.direction)?;
+                parimutuel_winnings(bet.amount, winning_total, losing_total)?
+            }
+            PricingMode::Cpmm | PricingMode::Lmsr => bet.shares
+                .checked_sub(bet.amount)
+                .ok_or(MarketError::PayoutOverflow)?,
         };
+        pay_out_winner(
+            market,
+            &mut ctx.accounts.treasury,
+            &ctx.accounts.treasury_vault,
+            &accounts,
+            &bet.direction,
+            bet.amount,
+            winnings,
+        )?;
+    }
 
-        market.final_price = Some(adjusted_price);
+    market.open_bets = market.open_bets.checked_sub(1).ok_or(MarketError::OpenBetsOverflow)?;
+    if market.open_bets == 0 {
+        market.transition(MarketStatus::Settled, clock.unix_timestamp)?;
     }
 
-    require!(
-        market.final_price.is_some(),
-        MarketError::NoneFinalPrice
-    );
+    //just for increased redundancy because the bet account should be closed after
+    bet.amount = 0;
+    bet.claimed = true;
+    bet.initialized = false;
+
+    Ok(())
+}
+
+pub struct PayoutAccounts<'a, 'info> {
+    pub higher_pool: &'a InterfaceAccount<'info, TokenAccount>,
+    pub lower_pool: &'a InterfaceAccount<'info, TokenAccount>,
+    pub user_ata: &'a InterfaceAccount<'info, TokenAccount>,
+    pub mint: &'a InterfaceAccount<'info, Mint>,
+    pub token_program: &'a Interface<'info, TokenInterface>,
+}
+
+//returns a stake from the pool it was placed in, no fees are charged on refunds
+pub fn refund_stake<'info>(
+    market: &Account<'info, Market>,
+    accounts: &PayoutAccounts<'_, 'info>,
+    direction: &Direction,
+    stake: u64,
+) -> Result<()> {
+    let pool = match direction {
+        Direction::Higher => accounts.higher_pool.to_account_info(),
+        Direction::Lower => accounts.lower_pool.to_account_info(),
+    };
+
+    transfer_checked(
+        CpiContext::new_with_signer(
+            accounts.token_program.to_account_info(),
+            TransferChecked {
+                from: pool,
+                mint: accounts.mint.to_account_info(),
+                to: accounts.user_ata.to_account_info(),
+                authority: market.to_account_info(),
+            },
+            &[&[
+                market.creator.as_ref(),
+                &hash_to_bytes(&market.feed_id),
+                &market.target_price.to_le_bytes(),
+                &market.market_duration.to_le_bytes(),
+                &[market.bump],
+            ]],
+        ),
+        stake,
+        accounts.mint.decimals,
+    )
+}
 
-    bet.is_won = match bet.direction {
-        Direction::Higher => market.final_price.unwrap() > market.target_price,
-        Direction::Lower => market.final_price.unwrap() < market.target_price,
+//winners get their stake back from their own pool and their winnings from the losing pool,
+//protocol and creator fees are taken out of the winnings and moved to the treasury vault
+pub fn pay_out_winner<'info>(
+    market: &mut Account<'info, Market>,
+    treasury: &mut Account<'info, Treasury>,
+    treasury_vault: &InterfaceAccount<'info, TokenAccount>,
+    accounts: &PayoutAccounts<'_, 'info>,
+    direction: &Direction,
+    stake: u64,
+    winnings: u64,
+) -> Result<()> {
+    let (winning_pool, losing_pool) = match direction {
+        Direction::Higher => (
+            accounts.higher_pool.to_account_info(),
+            accounts.lower_pool.to_account_info(),
+        ),
+        Direction::Lower => (
+            accounts.lower_pool.to_account_info(),
+            accounts.higher_pool.to_account_info(),
+        ),
     };
-    
-    if bet.is_won {
-        let bet_pool: AccountInfo = match bet.direction {
-            Direction::Higher => ctx.accounts.higher_pool.to_account_info(),
-            Direction::Lower => ctx.accounts.lower_pool.to_account_info(),
-        };
 
-        let payout = bet.amount.checked_mul(bet.odds).unwrap() / ODDS_FIXED_POINT_MULTIPLIER;
-    
-        transfer(
-            CpiContext::new(
-                ctx.accounts.token_program.to_account_info(),
-                Transfer {
-                    from: bet_pool,
-                    to: ctx.accounts.user_ata.to_account_info(),
-                    authority: ctx.accounts.market.to_account_info(),
+    let losing_amount = match direction {
+        Direction::Higher => accounts.lower_pool.amount,
+        Direction::Lower => accounts.higher_pool.amount,
+    };
+
+    let protocol_fee = fee_amount(winnings, market.protocol_fee_bps)?;
+    let creator_fee = fee_amount(winnings, market.creator_fee_bps)?;
+    let fees = protocol_fee.checked_add(creator_fee).ok_or(MarketError::FeeOverflow)?;
+
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        market.creator.as_ref(),
+        &hash_to_bytes(&market.feed_id),
+        &market.target_price.to_le_bytes(),
+        &market.market_duration.to_le_bytes(),
+        &[market.bump],
+    ]];
+
+    //the forfeits of sold bets on the winning side are still in the winning pool, once the
+    //losing pool runs dry they are moved over before the winnings are paid
+    let shortfall = winnings.saturating_sub(losing_amount);
+    if shortfall > 0 {
+        transfer_checked(
+            CpiContext::new_with_signer(
+                accounts.token_program.to_account_info(),
+                TransferChecked {
+                    from: winning_pool.clone(),
+                    mint: accounts.mint.to_account_info(),
+                    to: losing_pool.clone(),
+                    authority: market.to_account_info(),
                 },
+                signer_seeds,
             ),
-            payout,
+            shortfall,
+            accounts.mint.decimals,
         )?;
     }
 
-    
+    transfer_checked(
+        CpiContext::new_with_signer(
+            accounts.token_program.to_account_info(),
+            TransferChecked {
+                from: winning_pool,
+                mint: accounts.mint.to_account_info(),
+                to: accounts.user_ata.to_account_info(),
+                authority: market.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        stake,
+        accounts.mint.decimals,
+    )?;
 
-    //just for increased redundancy because the bet account should be closed after
-    bet.amount = 0;
-    bet.claimed = true;
-    bet.initialized = false;
+    if winnings > fees {
+        transfer_checked(
+            CpiContext::new_with_signer(
+                accounts.token_program.to_account_info(),
+                TransferChecked {
+                    from: losing_pool.clone(),
+                    mint: accounts.mint.to_account_info(),
+                    to: accounts.user_ata.to_account_info(),
+                    authority: market.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            winnings.checked_sub(fees).ok_or(MarketError::PayoutOverflow)?,
+            accounts.mint.decimals,
+        )?;
+    }
+
+    if fees > 0 {
+        transfer_checked(
+            CpiContext::new_with_signer(
+                accounts.token_program.to_account_info(),
+                TransferChecked {
+                    from: losing_pool,
+                    mint: accounts.mint.to_account_info(),
+                    to: treasury_vault.to_account_info(),
+                    authority: market.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            fees,
+            accounts.mint.decimals,
+        )?;
+    }
+
+    treasury.protocol_fees = treasury.protocol_fees
+        .checked_add(protocol_fee)
+        .ok_or(MarketError::FeeOverflow)?;
+    market.creator_fees = market.creator_fees
+        .checked_add(creator_fee)
+        .ok_or(MarketError::FeeOverflow)?;
 
     Ok(())
 }
@@ -105,33 +243,38 @@ pub struct ClaimBet<'info> {
     pub market: Account<'info, Market>,
 
     #[account(
+        mut,
         token::mint = market.mint, 
         token::authority = market,
+        token::token_program = token_program,
         seeds = [
             HIGHER_POOL_SEED.as_bytes(),
             market.key().as_ref(),
         ],
         bump = market.higher_pool_bump,
     )]
-    pub higher_pool: Account<'info, TokenAccount>,
+    pub higher_pool: InterfaceAccount<'info, TokenAccount>,
 
     #[account(
+        mut,
         token::mint = market.mint, 
         token::authority = market,
+        token::token_program = token_program,
         seeds = [
             LOWER_POOL_SEED.as_bytes(),
             market.key().as_ref()
         ],
         bump = market.lower_pool_bump,
     )]
-    pub lower_pool: Account<'info, TokenAccount>,
+    pub lower_pool: InterfaceAccount<'info, TokenAccount>,
 
     #[account(
         mut,
         associated_token::mint = market.mint,
         associated_token::authority = user,
+        associated_token::token_program = token_program,
     )]
-    pub user_ata: Account<'info, TokenAccount>,
+    pub user_ata: InterfaceAccount<'info, TokenAccount>,
 
     #[account(
         mut,
@@ -145,15 +288,41 @@ pub struct ClaimBet<'info> {
             BET_SEED.as_bytes(),
             user.key().as_ref(),
             market.key().as_ref(),
-            bet.amount.to_le_bytes().as_ref(),
-            &bet.direction.to_u8().unwrap().to_le_bytes(),
+            bet.id.to_le_bytes().as_ref(),
         ], 
         bump = bet.bump,
     )]
     pub bet: Account<'info,Bet>,
 
-    pub price_update: Account<'info, PriceUpdateV2>,
+    #[account(
+        mut,
+        seeds = [
+            TREASURY_SEED.as_bytes(),
+            market.mint.as_ref(),
+        ],
+        bump = treasury.bump,
+    )]
+    pub treasury: Account<'info, Treasury>,
+
+    #[account(
+        mut,
+        token::mint = market.mint,
+        token::authority = treasury,
+        token::token_program = token_program,
+        seeds = [
+            TREASURY_VAULT_SEED.as_bytes(),
+            market.mint.as_ref(),
+        ],
+        bump = treasury.vault_bump,
+    )]
+    pub treasury_vault: InterfaceAccount<'info, TokenAccount>,
 
     pub system_program: Program<'info, System>,
-    pub token_program: Program<'info, Token>,
+    #[account(
+        address = market.mint,
+        mint::token_program = token_program,
+    )]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    pub token_program: Interface<'info, TokenInterface>,
 }
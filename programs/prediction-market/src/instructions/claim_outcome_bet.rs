@@ -0,0 +1,198 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::*;
+
+use crate::constants::*;
+use crate::math::{fee_amount, parimutuel_winnings};
+use crate::states::*;
+use crate::MarketError;
+use crate::utils::hash_to_bytes;
+
+
+pub fn _claim_outcome_bet(
+    ctx: Context<ClaimOutcomeBet>,
+) -> Result<()> {
+    let bet = &mut ctx.accounts.bet;
+    let market = &mut ctx.accounts.market;
+    let clock = Clock::get()?;
+
+    require!(market.is_categorical(), MarketError::WrongMarketKind);
+    require_keys_eq!(ctx.accounts.user.key(),bet.user,MarketError::UnauthorizedUser);
+    require_eq!(bet.claimed,false,MarketError::BetIsClaimed);
+
+    //a voided market refunds every stake, a resolved one pays the winners out of the winning
+    //pool, which holds every losing pool since resolution
+    let payout = if market.status_at(clock.unix_timestamp) == MarketStatus::Voided {
+        bet.amount
+    } else {
+        market.require_status(MarketStatus::Resolved, clock.unix_timestamp)?;
+        bet.is_won = market.winning_outcome == Some(bet.outcome_index);
+        if bet.is_won {
+            let winning_total = market.outcome_totals[bet.outcome_index as usize];
+            let losing_total = market.outcome_totals_sum()?
+                .checked_sub(winning_total)
+                .ok_or(MarketError::PayoutOverflow)?;
+            let winnings = parimutuel_winnings(bet.amount, winning_total, losing_total)?;
+            let protocol_fee = fee_amount(winnings, market.protocol_fee_bps)?;
+            let creator_fee = fee_amount(winnings, market.creator_fee_bps)?;
+
+            let fees = protocol_fee.checked_add(creator_fee).ok_or(MarketError::FeeOverflow)?;
+            if fees > 0 {
+                transfer_checked(
+                    CpiContext::new_with_signer(
+                        ctx.accounts.token_program.to_account_info(),
+                        TransferChecked {
+                            from: ctx.accounts.outcome_pool.to_account_info(),
+                            mint: ctx.accounts.mint.to_account_info(),
+                            to: ctx.accounts.treasury_vault.to_account_info(),
+                            authority: market.to_account_info(),
+                        },
+                        &[&[
+                            market.creator.as_ref(),
+                            &hash_to_bytes(&market.feed_id),
+                            &market.target_price.to_le_bytes(),
+                            &market.market_duration.to_le_bytes(),
+                            &[market.bump],
+                        ]],
+                    ),
+                    fees,
+                    ctx.accounts.mint.decimals,
+                )?;
+            }
+            ctx.accounts.treasury.protocol_fees = ctx.accounts.treasury.protocol_fees
+                .checked_add(protocol_fee)
+                .ok_or(MarketError::FeeOverflow)?;
+            market.creator_fees = market.creator_fees
+                .checked_add(creator_fee)
+                .ok_or(MarketError::FeeOverflow)?;
+
+            bet.amount
+                .checked_add(winnings)
+                .and_then(|payout| payout.checked_sub(fees))
+                .ok_or(MarketError::PayoutOverflow)?
+        } else {
+            0
+        }
+    };
+
+    if payout > 0 {
+        transfer_checked(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                TransferChecked {
+                    from: ctx.accounts.outcome_pool.to_account_info(),
+                    mint: ctx.accounts.mint.to_account_info(),
+                    to: ctx.accounts.user_ata.to_account_info(),
+                    authority: market.to_account_info(),
+                },
+                &[&[
+                    market.creator.as_ref(),
+                    &hash_to_bytes(&market.feed_id),
+                    &market.target_price.to_le_bytes(),
+                    &market.market_duration.to_le_bytes(),
+                    &[market.bump],
+                ]],
+            ),
+            payout,
+            ctx.accounts.mint.decimals,
+        )?;
+    }
+
+    market.open_bets = market.open_bets.checked_sub(1).ok_or(MarketError::OpenBetsOverflow)?;
+    if market.open_bets == 0 {
+        market.transition(MarketStatus::Settled, clock.unix_timestamp)?;
+    }
+
+    //just for increased redundancy because the bet account should be closed after
+    bet.amount = 0;
+    bet.claimed = true;
+    bet.initialized = false;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ClaimOutcomeBet<'info> {
+    #[account(
+        mut,
+        seeds = [
+            market.creator.key().as_ref(), 
+            &hash_to_bytes(&market.feed_id),
+            &market.target_price.to_le_bytes(), 
+            &market.market_duration.to_le_bytes(),
+        ],
+        bump = market.bump,
+        address = bet.market, 
+    )]
+    pub market: Box<Account<'info, Market>>,
+
+    //the pool of the bet's own outcome, which is the winning pool for any bet that gets paid
+    #[account(
+        mut,
+        token::mint = market.mint, 
+        token::authority = market,
+        token::token_program = token_program,
+        seeds = [
+            OUTCOME_POOL_SEED.as_bytes(),
+            market.key().as_ref(),
+            &[bet.outcome_index],
+        ],
+        bump = market.outcome_pool_bump(bet.outcome_index),
+    )]
+    pub outcome_pool: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        associated_token::mint = market.mint,
+        associated_token::authority = user,
+        associated_token::token_program = token_program,
+    )]
+    pub user_ata: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+    
+    #[account(
+        mut,
+        close = user,
+        seeds = [
+            BET_SEED.as_bytes(),
+            user.key().as_ref(),
+            market.key().as_ref(),
+            bet.id.to_le_bytes().as_ref(),
+        ], 
+        bump = bet.bump,
+    )]
+    pub bet: Account<'info,Bet>,
+
+    #[account(
+        mut,
+        seeds = [
+            TREASURY_SEED.as_bytes(),
+            market.mint.as_ref(),
+        ],
+        bump = treasury.bump,
+    )]
+    pub treasury: Account<'info, Treasury>,
+
+    #[account(
+        mut,
+        token::mint = market.mint,
+        token::authority = treasury,
+        token::token_program = token_program,
+        seeds = [
+            TREASURY_VAULT_SEED.as_bytes(),
+            market.mint.as_ref(),
+        ],
+        bump = treasury.vault_bump,
+    )]
+    pub treasury_vault: InterfaceAccount<'info, TokenAccount>,
+
+    pub system_program: Program<'info, System>,
+    #[account(
+        address = market.mint,
+        mint::token_program = token_program,
+    )]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
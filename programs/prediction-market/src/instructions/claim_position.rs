@@ -0,0 +1,167 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::*;
+
+use crate::constants::*;
+use crate::instructions::{pay_out_winner, refund_stake, PayoutAccounts};
+use crate::math::parimutuel_winnings;
+use crate::states::*;
+use crate::MarketError;
+use crate::utils::hash_to_bytes;
+
+
+pub fn _claim_position(
+    ctx: Context<ClaimPosition>,
+) -> Result<()> {
+    let position = &ctx.accounts.position;
+    let market = &mut ctx.accounts.market;
+    let clock = Clock::get()?;
+
+    //positions are also how stakes come back out of a voided market
+    let voided = market.status_at(clock.unix_timestamp) == MarketStatus::Voided;
+    if !voided {
+        market.require_status(MarketStatus::Resolved, clock.unix_timestamp)?;
+    }
+    require_keys_eq!(market.key(),position.market,MarketError::BetMarketMismatch);
+    require_keys_eq!(ctx.accounts.user.key(),position.user,MarketError::UnauthorizedUser);
+    require!(position.higher_amount > 0 || position.lower_amount > 0, MarketError::EmptyPosition);
+
+    //both sides are settled in one go, at most one of them can be the winning side
+    for (direction, stake) in [
+        (Direction::Higher, position.higher_amount),
+        (Direction::Lower, position.lower_amount),
+    ] {
+        if stake == 0 {
+            continue;
+        }
+
+        let accounts = PayoutAccounts {
+            higher_pool: &ctx.accounts.higher_pool,
+            lower_pool: &ctx.accounts.lower_pool,
+            user_ata: &ctx.accounts.user_ata,
+            mint: &ctx.accounts.mint,
+            token_program: &ctx.accounts.token_program,
+        };
+
+        if voided || market.is_push() {
+            refund_stake(market, &accounts, &direction, stake)?;
+        } else if market.is_winning_direction(&direction)? {
+            let (winning_total, losing_total) = market.pool_totals(&direction)?;
+            let winnings = parimutuel_winnings(stake, winning_total, losing_total)?;
+            pay_out_winner(
+                market,
+                &mut ctx.accounts.treasury,
+                &ctx.accounts.treasury_vault,
+                &accounts,
+                &direction,
+                stake,
+                winnings,
+            )?;
+        }
+    }
+
+    market.open_bets = market.open_bets.checked_sub(1).ok_or(MarketError::OpenBetsOverflow)?;
+    if market.open_bets == 0 {
+        market.transition(MarketStatus::Settled, clock.unix_timestamp)?;
+    }
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ClaimPosition<'info> {
+    #[account(
+        mut,
+        seeds = [
+            market.creator.key().as_ref(), 
+            &hash_to_bytes(&market.feed_id),
+            &market.target_price.to_le_bytes(), 
+            &market.market_duration.to_le_bytes(),
+        ],
+        bump = market.bump,
+        address = position.market, 
+    )]
+    pub market: Account<'info, Market>,
+
+    #[account(
+        mut,
+        token::mint = market.mint, 
+        token::authority = market,
+        token::token_program = token_program,
+        seeds = [
+            HIGHER_POOL_SEED.as_bytes(),
+            market.key().as_ref(),
+        ],
+        bump = market.higher_pool_bump,
+    )]
+    pub higher_pool: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        token::mint = market.mint, 
+        token::authority = market,
+        token::token_program = token_program,
+        seeds = [
+            LOWER_POOL_SEED.as_bytes(),
+            market.key().as_ref()
+        ],
+        bump = market.lower_pool_bump,
+    )]
+    pub lower_pool: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        associated_token::mint = market.mint,
+        associated_token::authority = user,
+        associated_token::token_program = token_program,
+    )]
+    pub user_ata: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+    )]
+    pub user: Signer<'info>,
+    
+    #[account(
+        mut,
+        close = user,
+        seeds = [
+            POSITION_SEED.as_bytes(),
+            user.key().as_ref(),
+            market.key().as_ref(),
+        ], 
+        bump = position.bump,
+    )]
+    pub position: Account<'info,Position>,
+
+    #[account(
+        mut,
+        seeds = [
+            TREASURY_SEED.as_bytes(),
+            market.mint.as_ref(),
+        ],
+        bump = treasury.bump,
+    )]
+    pub treasury: Account<'info, Treasury>,
+
+    #[account(
+        mut,
+        token::mint = market.mint,
+        token::authority = treasury,
+        token::token_program = token_program,
+        seeds = [
+            TREASURY_VAULT_SEED.as_bytes(),
+            market.mint.as_ref(),
+        ],
+        bump = treasury.vault_bump,
+    )]
+    pub treasury_vault: InterfaceAccount<'info, TokenAccount>,
+
+    pub system_program: Program<'info, System>,
+    #[account(
+        address = market.mint,
+        mint::token_program = token_program,
+    )]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
@@ -0,0 +1,142 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{close_account, CloseAccount, Mint, TokenAccount, TokenInterface, transfer_checked, TransferChecked};
+
+use crate::constants::*;
+use crate::states::*;
+use crate::MarketError;
+use crate::utils::hash_to_bytes;
+
+
+pub fn _close_outcome_pool(
+    ctx: Context<CloseOutcomePool>,
+    outcome_index: u8,
+) -> Result<()> {
+    let market = &mut ctx.accounts.market;
+    let clock = Clock::get()?;
+
+    require!(market.is_categorical(), MarketError::WrongMarketKind);
+    require!(market.funded_outcome_pools & (1 << outcome_index) != 0, MarketError::InvalidOutcomePool);
+    require_eq!(market.creator_fees,0,MarketError::UnwithdrawnCreatorFees);
+
+    //the first pool closed moves the market to Closed, the others just follow
+    match market.status_at(clock.unix_timestamp) {
+        MarketStatus::Closed => {}
+        MarketStatus::Created | MarketStatus::Settled => market.transition(MarketStatus::Closed, clock.unix_timestamp)?,
+        MarketStatus::Open | MarketStatus::Locked => {
+            require_eq!(market.open_bets, 0, MarketError::NonZeroPools);
+            market.transition(MarketStatus::Closed, clock.unix_timestamp)?;
+        }
+        _ => {
+            //unclaimed bets can only be swept once the lock period is over
+            market.transition(MarketStatus::Settled, clock.unix_timestamp)?;
+            require_gt!(
+                clock.slot,
+                market
+                    .lock_period_end(ctx.accounts.config.market_lock_period)
+                    .ok_or(MarketError::MarketDurationOverflow)?,
+                MarketError::MarketLockPeriodNotOver
+            );
+            market.transition(MarketStatus::Closed, clock.unix_timestamp)?;
+        }
+    }
+    market.funded_outcome_pools &= !(1 << outcome_index);
+
+    let market = &ctx.accounts.market;
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        market.creator.as_ref(),
+        &hash_to_bytes(&market.feed_id),
+        &market.target_price.to_le_bytes(),
+        &market.market_duration.to_le_bytes(),
+        &[market.bump],
+    ]];
+
+    transfer_checked(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            TransferChecked {
+                from: ctx.accounts.outcome_pool.to_account_info(),
+                mint: ctx.accounts.mint.to_account_info(),
+                to: ctx.accounts.creator_ata.to_account_info(),
+                authority: market.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        ctx.accounts.outcome_pool.amount,
+        ctx.accounts.mint.decimals,
+    )?;
+
+    close_account(CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        CloseAccount {
+            account: ctx.accounts.outcome_pool.to_account_info(),
+            destination: ctx.accounts.market_creator.to_account_info(),
+            authority: market.to_account_info(),
+        },
+        signer_seeds,
+    ))?;
+
+    //the market account goes with the last pool
+    if market.funded_outcome_pools == 0 {
+        ctx.accounts.market.close(ctx.accounts.market_creator.to_account_info())?;
+    }
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(outcome_index: u8)]
+pub struct CloseOutcomePool<'info> {
+    #[account(
+        mut,
+        seeds = [
+            market.creator.key().as_ref(), 
+            &hash_to_bytes(&market.feed_id),
+            &market.target_price.to_le_bytes(), 
+            &market.market_duration.to_le_bytes(),
+        ],
+        bump = market.bump,
+    )]
+    pub market: Box<Account<'info, Market>>,
+
+    #[account(
+        seeds = [CONFIG_SEED.as_bytes()],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, ProgramConfig>,
+
+    #[account(
+        mut,
+        token::mint = market.mint, 
+        token::authority = market,
+        token::token_program = token_program,
+        seeds = [
+            OUTCOME_POOL_SEED.as_bytes(),
+            market.key().as_ref(),
+            &[outcome_index],
+        ],
+        bump = market.outcome_pool_bump(outcome_index),
+    )]
+    pub outcome_pool: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        associated_token::mint = market.mint,
+        associated_token::authority = market_creator,
+        associated_token::token_program = token_program,
+    )]
+    pub creator_ata: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        address = market.creator,
+    )]
+    pub market_creator: Signer<'info>,
+
+    #[account(
+        address = market.mint,
+        mint::token_program = token_program,
+    )]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
@@ -0,0 +1,82 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface, transfer_checked, TransferChecked};
+
+use crate::constants::*;
+use crate::states::*;
+use crate::MarketError;
+use crate::utils::hash_to_bytes;
+
+
+pub fn _dispute_outcome(
+    ctx: Context<DisputeOutcome>,
+) -> Result<()> {
+    let market = &mut ctx.accounts.market;
+    let clock = Clock::get()?;
+
+    require_gte!(market.challenge_deadline, clock.unix_timestamp, MarketError::ChallengeWindowClosed);
+    market.transition(MarketStatus::Disputed, clock.unix_timestamp)?;
+    market.disputer = Some(ctx.accounts.disputer.key());
+
+    //the disputer matches the proposer's bond, the ruling decides who takes both
+    transfer_checked(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            TransferChecked {
+                from: ctx.accounts.disputer_ata.to_account_info(),
+                mint: ctx.accounts.mint.to_account_info(),
+                to: ctx.accounts.bond_vault.to_account_info(),
+                authority: ctx.accounts.disputer.to_account_info(),
+            },
+        ),
+        market.bond_amount,
+        ctx.accounts.mint.decimals,
+    )?;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct DisputeOutcome<'info> {
+    #[account(
+        mut,
+        seeds = [
+            market.creator.key().as_ref(), 
+            &hash_to_bytes(&market.feed_id),
+            &market.target_price.to_le_bytes(), 
+            &market.market_duration.to_le_bytes(),
+        ],
+        bump = market.bump,
+    )]
+    pub market: Box<Account<'info, Market>>,
+
+    #[account(
+        mut,
+        token::mint = market.mint,
+        token::authority = market,
+        token::token_program = token_program,
+        seeds = [
+            BOND_VAULT_SEED.as_bytes(),
+            market.key().as_ref(),
+        ],
+        bump = market.bond_vault_bump,
+    )]
+    pub bond_vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        associated_token::mint = market.mint,
+        associated_token::authority = disputer,
+        associated_token::token_program = token_program,
+    )]
+    pub disputer_ata: InterfaceAccount<'info, TokenAccount>,
+
+    pub disputer: Signer<'info>,
+
+    #[account(
+        address = market.mint,
+        mint::token_program = token_program,
+    )]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
@@ -1,5 +1,5 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{close_account, CloseAccount, Token, TokenAccount,transfer,Transfer};
+use anchor_spl::token_interface::{close_account, CloseAccount, Mint, TokenAccount, TokenInterface,transfer_checked,TransferChecked};
 
 use crate::constants::*;
 use crate::states::*;
@@ -10,22 +10,40 @@ use crate::utils::hash_to_bytes;
 pub fn _finalize_market(
     ctx: Context<FinalizeMarket>,
 ) -> Result<()> {
-    let market = &ctx.accounts.market;
-    let _clock = Clock::get()?;
+    let market = &mut ctx.accounts.market;
+    let clock = Clock::get()?;
     let higher_pool = &mut ctx.accounts.higher_pool;
     let lower_pool = &mut ctx.accounts.lower_pool;
 
-    require!(market.initialization == MarketInitialization::InitializedPools,MarketError::InvalidMarketInitialization);
     require_keys_eq!(ctx.accounts.market_creator.key(),market.creator,MarketError::UnauthorizedUser);
-    // require_gt!(clock.slot,market.start_time + market.market_duration + MARKET_LOCK_PERIOD,MarketError::MarketLockPeriodNotOver);
-    require_eq!(higher_pool.amount + lower_pool.amount,INITIAL_USDC_POOL_AMOUNT*2,MarketError::NonZeroPools);
+    require_eq!(market.creator_fees,0,MarketError::UnwithdrawnCreatorFees);
+
+    //unclaimed bets can only be swept once the lock period is over, outcome tokens are not
+    //counted in open_bets so a market with tokens left waits it out even when settled
+    let settled = market.status_at(clock.unix_timestamp) == MarketStatus::Settled;
+    if !settled {
+        market.transition(MarketStatus::Settled, clock.unix_timestamp)?;
+    }
+    if !settled || market.outstanding_outcome_tokens > 0 {
+        require_gt!(
+            clock.slot,
+            market
+                .lock_period_end(ctx.accounts.config.market_lock_period)
+                .ok_or(MarketError::MarketDurationOverflow)?,
+            MarketError::MarketLockPeriodNotOver
+        );
+    }
+    market.transition(MarketStatus::Closed, clock.unix_timestamp)?;
+
+    let market = &ctx.accounts.market;
 
 
-    transfer(
+    transfer_checked(
         CpiContext::new_with_signer(
             ctx.accounts.token_program.to_account_info(),
-            Transfer {
+            TransferChecked {
                 from: higher_pool.to_account_info(),
+                mint: ctx.accounts.mint.to_account_info(),
                 to: ctx.accounts.creator_ata.to_account_info(),
                 authority: ctx.accounts.market.to_account_info(),
             },
@@ -37,14 +55,16 @@ pub fn _finalize_market(
             &[ctx.accounts.market.bump],
         ]],
         ),
-        INITIAL_USDC_POOL_AMOUNT,
+        higher_pool.amount,
+        ctx.accounts.mint.decimals,
     )?;
 
-    transfer(
+    transfer_checked(
         CpiContext::new_with_signer(
             ctx.accounts.token_program.to_account_info(),
-            Transfer {
+            TransferChecked {
                 from: lower_pool.to_account_info(),
+                mint: ctx.accounts.mint.to_account_info(),
                 to: ctx.accounts.creator_ata.to_account_info(),
                 authority: ctx.accounts.market.to_account_info(),
             },
@@ -56,7 +76,8 @@ pub fn _finalize_market(
             &[ctx.accounts.market.bump],
         ]],
         ),
-        INITIAL_USDC_POOL_AMOUNT,
+        lower_pool.amount,
+        ctx.accounts.mint.decimals,
     )?;
 
     close_account(CpiContext::new_with_signer(
@@ -91,6 +112,43 @@ pub fn _finalize_market(
         ]],
     ))?;
 
+    //collateral of complete sets nobody redeemed is swept with the pools
+    if market.has_outcome_mints() {
+        let set_vault = ctx.accounts.set_vault.as_ref().ok_or(MarketError::MissingSetVault)?;
+        let signer_seeds: &[&[&[u8]]] = &[&[
+            market.creator.as_ref(),
+            &hash_to_bytes(&market.feed_id),
+            &market.target_price.to_le_bytes(),
+            &market.market_duration.to_le_bytes(),
+            &[market.bump],
+        ]];
+
+        transfer_checked(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                TransferChecked {
+                    from: set_vault.to_account_info(),
+                    mint: ctx.accounts.mint.to_account_info(),
+                    to: ctx.accounts.creator_ata.to_account_info(),
+                    authority: market.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            set_vault.amount,
+            ctx.accounts.mint.decimals,
+        )?;
+
+        close_account(CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            CloseAccount {
+                account: set_vault.to_account_info(),
+                destination: ctx.accounts.market_creator.to_account_info(),
+                authority: market.to_account_info(),
+            },
+            signer_seeds,
+        ))?;
+    }
+
     Ok(())
 }
 
@@ -109,38 +167,59 @@ pub struct FinalizeMarket<'info> {
     )]
     pub market: Account<'info, Market>,
 
+    #[account(
+        seeds = [CONFIG_SEED.as_bytes()],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, ProgramConfig>,
+
     #[account(
         mut,
-        close = market_creator,
         token::mint = market.mint, 
         token::authority = market,
+        token::token_program = token_program,
         seeds = [
             HIGHER_POOL_SEED.as_bytes(),
             market.key().as_ref(), 
         ],
         bump = market.higher_pool_bump,
     )]
-    pub higher_pool: Account<'info, TokenAccount>,
+    pub higher_pool: InterfaceAccount<'info, TokenAccount>,
 
     #[account(
         mut,
-        close = market_creator,
         token::mint = market.mint, 
         token::authority = market,
+        token::token_program = token_program,
         seeds = [
             LOWER_POOL_SEED.as_bytes(),
             market.key().as_ref(),
         ],
         bump = market.lower_pool_bump,
     )]
-    pub lower_pool: Account<'info, TokenAccount>,
+    pub lower_pool: InterfaceAccount<'info, TokenAccount>,
+
+    //only exists for markets with outcome tokens
+    #[account(
+        mut,
+        token::mint = market.mint,
+        token::authority = market,
+        token::token_program = token_program,
+        seeds = [
+            SET_VAULT_SEED.as_bytes(),
+            market.key().as_ref(),
+        ],
+        bump = market.set_vault_bump,
+    )]
+    pub set_vault: Option<InterfaceAccount<'info, TokenAccount>>,
 
     #[account(
         mut,
         associated_token::mint = market.mint,
         associated_token::authority = market_creator,
+        associated_token::token_program = token_program,
     )]
-    pub creator_ata: Account<'info, TokenAccount>,
+    pub creator_ata: InterfaceAccount<'info, TokenAccount>,
 
     #[account(
         mut,
@@ -149,6 +228,12 @@ pub struct FinalizeMarket<'info> {
     pub market_creator: Signer<'info>,
     
     pub system_program: Program<'info, System>,
-    pub token_program: Program<'info, Token>,
+    #[account(
+        address = market.mint,
+        mint::token_program = token_program,
+    )]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    pub token_program: Interface<'info, TokenInterface>,
 
 }
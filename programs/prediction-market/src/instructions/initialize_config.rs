@@ -0,0 +1,44 @@
+use anchor_lang::prelude::*;
+
+use crate::constants::*;
+use crate::program::PredictionMarket;
+use crate::states::{ConfigParams, ProgramConfig};
+use crate::MarketError;
+
+pub fn _initialize_config(
+    ctx: Context<InitializeConfig>,
+    params: ConfigParams,
+) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+    config.apply(&params)?;
+    config.admin = ctx.accounts.admin.key();
+    config.pending_admin = None;
+    config.bump = ctx.bumps.config;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct InitializeConfig<'info> {
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + ProgramConfig::INIT_SPACE,
+        seeds = [CONFIG_SEED.as_bytes()],
+        bump
+    )]
+    pub config: Account<'info, ProgramConfig>,
+
+    //only the upgrade authority can create the config so it can not be front-run after deployment
+    #[account(constraint = program.programdata_address()? == Some(program_data.key()))]
+    pub program: Program<'info, PredictionMarket>,
+
+    #[account(
+        constraint = program_data.upgrade_authority_address == Some(admin.key()) @ MarketError::UnauthorizedUser,
+    )]
+    pub program_data: Account<'info, ProgramData>,
+
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
@@ -1,17 +1,111 @@
 use anchor_lang::prelude::*;
+use anchor_lang::solana_program::clock::DEFAULT_MS_PER_SLOT;
+use pyth_solana_receiver_sdk::price_update::get_feed_id_from_hex;
 
+use crate::constants::*;
 use crate::MarketError;
-use crate::states::{Market,MarketInitialization};
+use crate::states::{Market,MarketStatus,OracleFeed,OracleSource,PricingMode,ProgramConfig,ResolutionMode,ResolutionParams};
 use crate::utils::hash_to_bytes;
 
 pub fn _initialize_market(
     ctx: Context<InitializeMarket>,
     target_price: u64,
-    feed_id: String, // from https://pyth.network/developers/price-feed-ids#solana-stables
+    feed_id: String, // from https://pyth.network/developers/price-feed-ids#solana-stables, a switchboard feed address in hex, or the hex hash of the event for committee markets
     market_duration: u64,
+    params: ResolutionParams,
 ) -> Result<()> {
     require_eq!(feed_id.len(), 66, MarketError::IncorrectFeedIDLength);
-    require_gte!(market_duration, 1200, MarketError::ShortMarketDuration); //more than one hour
+    let config = &ctx.accounts.config;
+    require_gte!(market_duration, config.min_market_duration, MarketError::ShortMarketDuration);
+    require!(
+        params.resolution_window > 0 && params.resolution_window <= config.max_resolution_window,
+        MarketError::InvalidResolutionWindow
+    );
+    require!(
+        params.max_conf_bps > 0 && (params.max_conf_bps as u64) < BPS_DENOMINATOR,
+        MarketError::InvalidConfidenceLimit
+    );
+    let primary_feed = OracleFeed {
+        source: params.oracle_source,
+        feed_id: get_feed_id_from_hex(&feed_id).map_err(|_| MarketError::InvalidFeedId)?,
+    };
+    require_gte!(MAX_SECONDARY_FEEDS as usize, params.secondary_feeds.len(), MarketError::TooManyOracleFeeds);
+    //listing a feed twice would let one print count towards the quorum more than once
+    for (i, feed) in params.secondary_feeds.iter().enumerate() {
+        require!(
+            *feed != primary_feed && !params.secondary_feeds[..i].contains(feed),
+            MarketError::DuplicateOracleFeed
+        );
+    }
+    require!(
+        params.oracle_quorum > 0 && params.oracle_quorum as usize <= params.secondary_feeds.len() + 1,
+        MarketError::InvalidOracleQuorum
+    );
+    //a switchboard feed only keeps the time of its latest update, so the first price at or after
+    //expiry can not be told apart from a later one
+    if params.resolution_mode == ResolutionMode::Spot {
+        require!(
+            params.oracle_source == OracleSource::Pyth
+                && params.secondary_feeds.iter().all(|feed| feed.source == OracleSource::Pyth),
+            MarketError::UnsupportedOracleSource
+        );
+    }
+    if params.resolution_mode == ResolutionMode::Twap {
+        require!(
+            params.twap_window > 0 && params.secondary_feeds.is_empty(),
+            MarketError::InvalidTwapConfig
+        );
+    }
+    if params.resolution_mode == ResolutionMode::Committee {
+        let committee = &params.committee;
+        require!(
+            !committee.is_empty()
+                && committee.len() <= MAX_COMMITTEE_SIZE as usize
+                && params.committee_threshold > 0
+                && params.committee_threshold as usize <= committee.len()
+                && params.secondary_feeds.is_empty(),
+            MarketError::InvalidCommittee
+        );
+        //a key listed twice could approve twice
+        for (i, member) in committee.iter().enumerate() {
+            require!(!committee[..i].contains(member), MarketError::InvalidCommittee);
+        }
+    } else {
+        require!(params.committee.is_empty(), MarketError::InvalidCommittee);
+    }
+    if params.resolution_mode == ResolutionMode::Optimistic {
+        require!(
+            params.bond_amount > 0 && params.challenge_period > 0 && params.secondary_feeds.is_empty(),
+            MarketError::InvalidOptimisticConfig
+        );
+    }
+    if !params.outcome_bounds.is_empty() {
+        require!(
+            params.outcome_bounds.len() < MAX_OUTCOMES as usize
+                && params.outcome_bounds.windows(2).all(|pair| pair[0] < pair[1])
+                && params.resolution_mode == ResolutionMode::Spot
+                && params.secondary_feeds.is_empty(),
+            MarketError::InvalidOutcomeBounds
+        );
+        require!(params.pricing_mode == PricingMode::Parimutuel, MarketError::WrongPricingMode);
+    }
+    //a scalar market settles on a price and is only traded through complete sets of long and short tokens
+    let scalar = params.lower_bound > 0 || params.upper_bound > 0;
+    if scalar {
+        require!(
+            params.upper_bound > params.lower_bound
+                && params.outcome_tokens
+                && params.outcome_bounds.is_empty()
+                && params.pricing_mode == PricingMode::Parimutuel
+                && matches!(params.resolution_mode, ResolutionMode::Spot | ResolutionMode::Twap),
+            MarketError::InvalidScalarBounds
+        );
+    }
+    //a token redeems for a fixed amount, which only holds for shares bought from a market maker
+    if params.outcome_tokens && !scalar {
+        require!(params.pricing_mode != PricingMode::Parimutuel, MarketError::WrongPricingMode);
+    }
+    require_gte!(MAX_TARGET_EXPONENT, params.target_exponent.unsigned_abs(), MarketError::InvalidTargetExponent);
 
     let market = &mut ctx.accounts.market;
     
@@ -19,17 +113,59 @@ pub fn _initialize_market(
     market.start_time = clock.slot;
    
     market.target_price = target_price;
+    market.target_exponent = params.target_exponent;
     market.market_duration = market_duration;
 
+    //betting closes and oracle prices are stamped in unix time, so the slot based duration is converted once here
+    let duration_secs = market_duration
+        .checked_mul(DEFAULT_MS_PER_SLOT)
+        .ok_or(MarketError::MarketDurationOverflow)?
+        / 1000;
+    market.expiry_timestamp = clock
+        .unix_timestamp
+        .checked_add(duration_secs as i64)
+        .ok_or(MarketError::MarketDurationOverflow)?;
+    //samples are only taken while the market exists, and each one needs its own second of the window
+    if params.resolution_mode == ResolutionMode::Twap {
+        require!(
+            params.twap_window >= MAX_PRICE_SAMPLES as u64 && params.twap_window <= duration_secs,
+            MarketError::InvalidTwapConfig
+        );
+    }
+    market.resolution_window = params.resolution_window;
+    market.tie_band = params.tie_band;
+    market.max_conf_bps = params.max_conf_bps;
+
     let mut feed_data = [0u8; 66];
-    feed_data[..feed_id.as_bytes().len()].copy_from_slice(feed_id.as_bytes());
+    feed_data[..feed_id.len()].copy_from_slice(feed_id.as_bytes());
     market.feed_id = feed_data;    
+    market.oracle_source = params.oracle_source;
+    market.secondary_feeds = params.secondary_feeds;
+    market.oracle_quorum = params.oracle_quorum;
+    market.resolution_mode = params.resolution_mode;
+    market.twap_window = params.twap_window;
+    market.committee = params.committee;
+    market.committee_threshold = params.committee_threshold;
+    market.bond_amount = params.bond_amount;
+    market.challenge_period = params.challenge_period;
+    market.outcome_bounds = params.outcome_bounds;
+    market.pricing_mode = params.pricing_mode;
+    market.outcome_tokens = params.outcome_tokens;
+    market.lower_bound = params.lower_bound;
+    market.upper_bound = params.upper_bound;
 
     market.creator = ctx.accounts.market_creator.key();
+
+    market.protocol_fee_bps = config.protocol_fee_bps;
+    market.creator_fee_bps = config.creator_fee_bps;
+    market.exit_fee_bps = config.exit_fee_bps;
+    market.void_grace_period = config.void_grace_period;
+    //a deadline that overflows would leave an unresolved market impossible to void
+    market.void_deadline().ok_or(MarketError::MarketDurationOverflow)?;
     
     market.bump = ctx.bumps.market;
 
-    market.initialization = MarketInitialization::InitializedMarket;
+    market.status = MarketStatus::Created;
 
     Ok(())
 }
@@ -51,6 +187,12 @@ pub struct InitializeMarket<'info> {
     )]
     pub market: Account<'info, Market>,
 
+    #[account(
+        seeds = [CONFIG_SEED.as_bytes()],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, ProgramConfig>,
+
     #[account(mut)]
     pub market_creator: Signer<'info>,
     pub system_program: Program<'info, System>,
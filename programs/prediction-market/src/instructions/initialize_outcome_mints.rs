@@ -0,0 +1,96 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::*;
+
+use crate::constants::*;
+use crate::states::*;
+use crate::MarketError;
+use crate::utils::hash_to_bytes;
+
+pub fn _initialize_outcome_mints(
+    ctx: Context<InitializeOutcomeMints>,
+) -> Result<()> {
+    let market = &mut ctx.accounts.market;
+    let clock = Clock::get()?;
+
+    require!(market.outcome_tokens, MarketError::WrongMarketKind);
+    //the outcome mints copy the decimals of the pool mint, which is only known once the pools exist
+    market.require_status(MarketStatus::Open, clock.unix_timestamp)?;
+
+    market.higher_mint_bump = ctx.bumps.higher_mint;
+    market.lower_mint_bump = ctx.bumps.lower_mint;
+    market.set_vault_bump = ctx.bumps.set_vault;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct InitializeOutcomeMints<'info> {
+    #[account(
+        mut,
+        seeds = [
+            market.creator.key().as_ref(),
+            &hash_to_bytes(&market.feed_id),
+            &market.target_price.to_le_bytes(),
+            &market.market_duration.to_le_bytes(),
+        ],
+        bump = market.bump,
+    )]
+    pub market: Box<Account<'info, Market>>,
+
+    #[account(
+        init,
+        payer = market_creator,
+        mint::decimals = mint.decimals,
+        mint::authority = market,
+        mint::token_program = token_program,
+        seeds = [
+            HIGHER_MINT_SEED.as_bytes(),
+            market.key().as_ref(),
+        ],
+        bump
+    )]
+    pub higher_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        init,
+        payer = market_creator,
+        mint::decimals = mint.decimals,
+        mint::authority = market,
+        mint::token_program = token_program,
+        seeds = [
+            LOWER_MINT_SEED.as_bytes(),
+            market.key().as_ref(),
+        ],
+        bump
+    )]
+    pub lower_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        init,
+        payer = market_creator,
+        token::mint = mint,
+        token::authority = market,
+        token::token_program = token_program,
+        seeds = [
+            SET_VAULT_SEED.as_bytes(),
+            market.key().as_ref(),
+        ],
+        bump
+    )]
+    pub set_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        address = market.mint,
+        mint::token_program = token_program,
+    )]
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        address = market.creator,
+    )]
+    pub market_creator: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
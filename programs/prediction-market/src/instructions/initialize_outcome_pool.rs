@@ -0,0 +1,129 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::*;
+
+use crate::constants::*;
+use crate::states::*;
+use crate::MarketError;
+use crate::utils::{hash_to_bytes, load_registered, validate_mint_extensions};
+
+pub fn _initialize_outcome_pool(
+    ctx: Context<InitializeOutcomePool>,
+    outcome_index: u8,
+) -> Result<()> {
+    let market = &mut ctx.accounts.market;
+    let clock = Clock::get()?;
+
+    require!(market.is_categorical(), MarketError::WrongMarketKind);
+    require_gt!(market.outcome_count(), outcome_index, MarketError::InvalidOutcomeIndex);
+    require!(market.status == MarketStatus::Created, MarketError::MarketAlreadyOpen);
+
+    //the first pool picks the mint, every other pool has to use the same one
+    if market.funded_outcome_pools == 0 {
+        let allowed_mint = load_registered::<AllowedMint>(&ctx.accounts.allowed_mint)?;
+        load_registered::<Treasury>(&ctx.accounts.treasury)?;
+        validate_mint_extensions(&ctx.accounts.pool_token_mint.to_account_info())?;
+        market.mint = ctx.accounts.pool_token_mint.key();
+        market.seed_liquidity_amount = allowed_mint.seed_liquidity_amount;
+        market.min_bet = allowed_mint.min_bet;
+        market.max_bet = allowed_mint.max_bet;
+    } else {
+        require_keys_eq!(market.mint, ctx.accounts.pool_token_mint.key(), MarketError::InvalidPoolMint);
+    }
+
+    transfer_checked(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            TransferChecked {
+                from: ctx.accounts.user_ata.to_account_info(),
+                mint: ctx.accounts.pool_token_mint.to_account_info(),
+                to: ctx.accounts.outcome_pool.to_account_info(),
+                authority: ctx.accounts.market_creator.to_account_info(),
+            },
+        ),
+        market.seed_liquidity_amount,
+        ctx.accounts.pool_token_mint.decimals,
+    )?;
+
+    market.outcome_pool_bumps[outcome_index as usize] = ctx.bumps.outcome_pool;
+    market.outcome_totals[outcome_index as usize] = market.seed_liquidity_amount;
+    market.funded_outcome_pools |= 1 << outcome_index;
+
+    //betting opens once every outcome has a funded pool
+    if market.funded_outcome_pools.count_ones() == market.outcome_count() as u32 {
+        market.transition(MarketStatus::Open, clock.unix_timestamp)?;
+    }
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(outcome_index: u8)]
+pub struct InitializeOutcomePool<'info> {
+    #[account(
+        mut,
+        seeds = [
+            market.creator.key().as_ref(), 
+            &hash_to_bytes(&market.feed_id),
+            &market.target_price.to_le_bytes(), 
+            &market.market_duration.to_le_bytes(),
+        ],
+        bump = market.bump,
+    )]
+    pub market: Box<Account<'info, Market>>,
+
+    /// CHECK: the AllowedMint of pool_token_mint, loaded by the handler so an unregistered mint fails with InvalidPoolMint
+    #[account(
+        seeds = [
+            ALLOWED_MINT_SEED.as_bytes(),
+            pool_token_mint.key().as_ref(),
+        ],
+        bump,
+    )]
+    pub allowed_mint: UncheckedAccount<'info>,
+
+    /// CHECK: the Treasury of pool_token_mint, loaded by the handler like allowed_mint. Claims pay their
+    /// fees into it, so a market can only be funded once it exists
+    #[account(
+        seeds = [
+            TREASURY_SEED.as_bytes(),
+            pool_token_mint.key().as_ref(),
+        ],
+        bump,
+    )]
+    pub treasury: UncheckedAccount<'info>,
+
+    #[account(
+        init,
+        payer = market_creator,
+        token::mint = pool_token_mint, 
+        token::authority = market,
+        token::token_program = token_program,
+        seeds = [
+            OUTCOME_POOL_SEED.as_bytes(),
+            market.key().as_ref(),
+            &[outcome_index],
+        ],
+        bump
+    )]
+    pub outcome_pool: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        associated_token::mint = pool_token_mint,
+        associated_token::authority = market.creator,
+        associated_token::token_program = token_program,
+    )]
+    pub user_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mint::token_program = token_program)]
+    pub pool_token_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        address = market.creator,
+    )]
+    pub market_creator: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
@@ -1,55 +1,75 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::*;
+use anchor_spl::token_interface::*;
 
 use crate::constants::*;
 use crate::states::*;
 use crate::MarketError;
-use crate::utils::hash_to_bytes;
+use crate::utils::{hash_to_bytes, load_registered, validate_mint_extensions};
 
 pub fn _initialize_pools(
     ctx: Context<InitializePools>,
 ) -> Result<()> {
     let market = &mut ctx.accounts.market;
-    require!(market.initialization == MarketInitialization::InitializedMarket,MarketError::InvalidMarketInitialization);
-    // require_eq!(
-    //     ctx.accounts.pool_token_mint.key().to_string(),
-    //     USDC_MINT, //more tokens can be allowed in the future
-    //     MarketError::InvalidPoolMint
-    // ); 
+    let clock = Clock::get()?;
+    //categorical markets fund one pool per outcome through initialize_outcome_pool
+    require!(!market.is_categorical(), MarketError::WrongMarketKind);
+    market.transition(MarketStatus::Open, clock.unix_timestamp)?;
+    let allowed_mint = load_registered::<AllowedMint>(&ctx.accounts.allowed_mint)?;
+    load_registered::<Treasury>(&ctx.accounts.treasury)?;
+    let seed_liquidity_amount = allowed_mint.seed_liquidity_amount;
+    validate_mint_extensions(&ctx.accounts.pool_token_mint.to_account_info())?;
     
-    transfer(
+    transfer_checked(
         CpiContext::new(
             ctx.accounts.token_program.to_account_info(),
-            Transfer {
+            TransferChecked {
                 from: ctx.accounts.user_ata.to_account_info(),
+                mint: ctx.accounts.pool_token_mint.to_account_info(),
                 to: ctx.accounts.higher_pool.to_account_info(),
                 authority: ctx.accounts.market_creator.to_account_info(),
             },
         ),
-        INITIAL_USDC_POOL_AMOUNT,
+        seed_liquidity_amount,
+        ctx.accounts.pool_token_mint.decimals,
     )?;
 
-    transfer(
+    transfer_checked(
         CpiContext::new(
             ctx.accounts.token_program.to_account_info(),
-            Transfer {
+            TransferChecked {
                 from: ctx.accounts.user_ata.to_account_info(),
+                mint: ctx.accounts.pool_token_mint.to_account_info(),
                 to: ctx.accounts.lower_pool.to_account_info(),
                 authority: ctx.accounts.market_creator.to_account_info(),
             },
         ),
-        INITIAL_USDC_POOL_AMOUNT,
+        seed_liquidity_amount,
+        ctx.accounts.pool_token_mint.decimals,
     )?;
 
     //Nothing after this comments seems to run at all
     
     market.mint = ctx.accounts.pool_token_mint.key();
+    market.seed_liquidity_amount = seed_liquidity_amount;
+    market.min_bet = allowed_mint.min_bet;
+    market.max_bet = allowed_mint.max_bet;
     
+    //the seed backs both pools but only one complete set per unit goes to the amm, so winning
+    //shares bought against the reserves can always be paid out of the seed and the stakes
+    match market.pricing_mode {
+        PricingMode::Parimutuel => {}
+        PricingMode::Cpmm => {
+            market.higher_reserve = seed_liquidity_amount;
+            market.lower_reserve = seed_liquidity_amount;
+        }
+        //b * ln(2) is below the seed of a single pool, so the creator can not lose more than
+        //they deposited
+        PricingMode::Lmsr => market.liquidity_param = seed_liquidity_amount,
+    }
+
     market.lower_pool_bump = ctx.bumps.lower_pool;
     market.higher_pool_bump = ctx.bumps.higher_pool;
 
-    market.initialization = MarketInitialization::InitializedPools;
-
     Ok(())
 }
 
@@ -67,41 +87,66 @@ pub struct InitializePools<'info> {
     )]
     pub market: Box<Account<'info, Market>>,
 
+    /// CHECK: the AllowedMint of pool_token_mint, loaded by the handler so an unregistered mint fails with InvalidPoolMint
+    #[account(
+        seeds = [
+            ALLOWED_MINT_SEED.as_bytes(),
+            pool_token_mint.key().as_ref(),
+        ],
+        bump,
+    )]
+    pub allowed_mint: UncheckedAccount<'info>,
+
+    /// CHECK: the Treasury of pool_token_mint, loaded by the handler like allowed_mint. Claims pay their
+    /// fees into it, so a market can only be funded once it exists
+    #[account(
+        seeds = [
+            TREASURY_SEED.as_bytes(),
+            pool_token_mint.key().as_ref(),
+        ],
+        bump,
+    )]
+    pub treasury: UncheckedAccount<'info>,
+
     #[account(
         init,
         payer = market_creator,
         token::mint = pool_token_mint, 
         token::authority = market,
+        token::token_program = token_program,
         seeds = [
             HIGHER_POOL_SEED.as_bytes(),
             market.key().as_ref(), 
         ],
         bump
     )]
-    pub higher_pool: Box<Account<'info, TokenAccount>>,
+    pub higher_pool: Box<InterfaceAccount<'info, TokenAccount>>,
 
     #[account(
         init,
         payer = market_creator,
         token::mint = pool_token_mint, 
         token::authority = market,
+        token::token_program = token_program,
         seeds = [
             LOWER_POOL_SEED.as_bytes(),
             market.key().as_ref(),
         ],
         bump
     )]
-    pub lower_pool: Box<Account<'info, TokenAccount>>,
+    pub lower_pool: Box<InterfaceAccount<'info, TokenAccount>>,
 
     #[account(
         mut,
         associated_token::mint = pool_token_mint,
         associated_token::authority = market.creator,
+        associated_token::token_program = token_program,
     )]
-    pub user_ata: Box<Account<'info, TokenAccount>>,
+    pub user_ata: Box<InterfaceAccount<'info, TokenAccount>>,
 
     //token mint account that bets are gonna be made with e.g USDC
-    pub pool_token_mint: Account<'info,Mint>,
+    #[account(mint::token_program = token_program)]
+    pub pool_token_mint: Box<InterfaceAccount<'info, Mint>>,
 
     #[account(
         mut,
@@ -110,6 +155,6 @@ pub struct InitializePools<'info> {
     pub market_creator: Signer<'info>,
     
     pub system_program: Program<'info, System>,
-    pub token_program: Program<'info, Token>,
+    pub token_program: Interface<'info, TokenInterface>,
 
 }
@@ -0,0 +1,62 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::*;
+
+use crate::constants::*;
+use crate::states::{ProgramConfig, Treasury};
+use crate::MarketError;
+
+pub fn _initialize_treasury(
+    ctx: Context<InitializeTreasury>,
+) -> Result<()> {
+    let treasury = &mut ctx.accounts.treasury;
+    treasury.mint = ctx.accounts.mint.key();
+    treasury.protocol_fees = 0;
+    treasury.bump = ctx.bumps.treasury;
+    treasury.vault_bump = ctx.bumps.treasury_vault;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct InitializeTreasury<'info> {
+    #[account(
+        seeds = [CONFIG_SEED.as_bytes()],
+        bump = config.bump,
+        has_one = admin @ MarketError::UnauthorizedUser,
+    )]
+    pub config: Account<'info, ProgramConfig>,
+
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + Treasury::INIT_SPACE,
+        seeds = [
+            TREASURY_SEED.as_bytes(),
+            mint.key().as_ref(),
+        ],
+        bump
+    )]
+    pub treasury: Account<'info, Treasury>,
+
+    #[account(
+        init,
+        payer = admin,
+        token::mint = mint,
+        token::authority = treasury,
+        token::token_program = token_program,
+        seeds = [
+            TREASURY_VAULT_SEED.as_bytes(),
+            mint.key().as_ref(),
+        ],
+        bump
+    )]
+    pub treasury_vault: InterfaceAccount<'info, TokenAccount>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
@@ -0,0 +1,165 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::*;
+
+use crate::constants::*;
+use crate::math::parimutuel_winnings;
+use crate::states::*;
+use crate::MarketError;
+use crate::utils::hash_to_bytes;
+
+pub fn _merge_complete_set(
+    ctx: Context<MergeCompleteSet>,
+    amount: u64,
+) -> Result<()> {
+    let market = &mut ctx.accounts.market;
+    let clock = Clock::get()?;
+
+    require!(market.outcome_tokens, MarketError::WrongMarketKind);
+    market.require_unresolved(clock.unix_timestamp)?;
+    require_gt!(amount, 0, MarketError::EmptyPosition);
+
+    //any higher and lower token make a set, including shares bought from the market maker, the
+    //vault only holds what was minted as sets so that is all it can pay back
+    require_gte!(ctx.accounts.set_vault.amount, amount, MarketError::InsufficientSetCollateral);
+    market.outstanding_outcome_tokens = amount
+        .checked_mul(2)
+        .and_then(|burned| market.outstanding_outcome_tokens.checked_sub(burned))
+        .ok_or(MarketError::PayoutOverflow)?;
+
+    //the burned tokens leave at the average cost of their direction, so what is refunded per
+    //token if the market is voided stays the same for everyone else
+    for (direction, outcome_mint) in [
+        (Direction::Higher, &ctx.accounts.higher_mint),
+        (Direction::Lower, &ctx.accounts.lower_mint),
+    ] {
+        let token_cost = market.token_cost_mut(&direction);
+        *token_cost -= parimutuel_winnings(amount, outcome_mint.supply, *token_cost)?;
+    }
+
+    for (outcome_mint, user_outcome_ata) in [
+        (&ctx.accounts.higher_mint, &ctx.accounts.user_higher_ata),
+        (&ctx.accounts.lower_mint, &ctx.accounts.user_lower_ata),
+    ] {
+        burn(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                Burn {
+                    mint: outcome_mint.to_account_info(),
+                    from: user_outcome_ata.to_account_info(),
+                    authority: ctx.accounts.user.to_account_info(),
+                },
+            ),
+            amount,
+        )?;
+    }
+
+    let market = &ctx.accounts.market;
+    transfer_checked(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            TransferChecked {
+                from: ctx.accounts.set_vault.to_account_info(),
+                mint: ctx.accounts.mint.to_account_info(),
+                to: ctx.accounts.user_ata.to_account_info(),
+                authority: market.to_account_info(),
+            },
+            &[&[
+                market.creator.as_ref(),
+                &hash_to_bytes(&market.feed_id),
+                &market.target_price.to_le_bytes(),
+                &market.market_duration.to_le_bytes(),
+                &[market.bump],
+            ]],
+        ),
+        amount,
+        ctx.accounts.mint.decimals,
+    )?;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct MergeCompleteSet<'info> {
+    #[account(
+        mut,
+        seeds = [
+            market.creator.key().as_ref(),
+            &hash_to_bytes(&market.feed_id),
+            &market.target_price.to_le_bytes(),
+            &market.market_duration.to_le_bytes(),
+        ],
+        bump = market.bump,
+    )]
+    pub market: Box<Account<'info, Market>>,
+
+    #[account(
+        mut,
+        token::mint = market.mint,
+        token::authority = market,
+        token::token_program = token_program,
+        seeds = [
+            SET_VAULT_SEED.as_bytes(),
+            market.key().as_ref(),
+        ],
+        bump = market.set_vault_bump,
+    )]
+    pub set_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        mint::authority = market,
+        mint::token_program = token_program,
+        seeds = [
+            HIGHER_MINT_SEED.as_bytes(),
+            market.key().as_ref(),
+        ],
+        bump = market.higher_mint_bump,
+    )]
+    pub higher_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        mint::authority = market,
+        mint::token_program = token_program,
+        seeds = [
+            LOWER_MINT_SEED.as_bytes(),
+            market.key().as_ref(),
+        ],
+        bump = market.lower_mint_bump,
+    )]
+    pub lower_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        token::mint = higher_mint,
+        token::authority = user,
+        token::token_program = token_program,
+    )]
+    pub user_higher_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        token::mint = lower_mint,
+        token::authority = user,
+        token::token_program = token_program,
+    )]
+    pub user_lower_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        associated_token::mint = market.mint,
+        associated_token::authority = user,
+        associated_token::token_program = token_program,
+    )]
+    pub user_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub user: Signer<'info>,
+
+    #[account(
+        address = market.mint,
+        mint::token_program = token_program,
+    )]
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
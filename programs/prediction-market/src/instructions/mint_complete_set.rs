@@ -0,0 +1,167 @@
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token_interface::*;
+
+use crate::constants::*;
+use crate::states::*;
+use crate::MarketError;
+use crate::utils::hash_to_bytes;
+
+pub fn _mint_complete_set(
+    ctx: Context<MintCompleteSet>,
+    amount: u64,
+) -> Result<()> {
+    let market = &mut ctx.accounts.market;
+    let clock = Clock::get()?;
+
+    require!(market.outcome_tokens, MarketError::WrongMarketKind);
+    market.require_unresolved(clock.unix_timestamp)?;
+    require_gt!(amount, 0, MarketError::EmptyPosition);
+
+    //one higher and one lower token always pay out one unit together, so the set is backed by
+    //the vault and neither the pools nor the market maker reserves move
+    market.outstanding_outcome_tokens = amount
+        .checked_mul(2)
+        .and_then(|minted| market.outstanding_outcome_tokens.checked_add(minted))
+        .ok_or(MarketError::PayoutOverflow)?;
+    market.higher_token_cost = market.higher_token_cost
+        .checked_add(amount / 2)
+        .ok_or(MarketError::PayoutOverflow)?;
+    market.lower_token_cost = market.lower_token_cost
+        .checked_add(amount - amount / 2)
+        .ok_or(MarketError::PayoutOverflow)?;
+
+    transfer_checked(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            TransferChecked {
+                from: ctx.accounts.user_ata.to_account_info(),
+                mint: ctx.accounts.mint.to_account_info(),
+                to: ctx.accounts.set_vault.to_account_info(),
+                authority: ctx.accounts.user.to_account_info(),
+            },
+        ),
+        amount,
+        ctx.accounts.mint.decimals,
+    )?;
+
+    let market = &ctx.accounts.market;
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        market.creator.as_ref(),
+        &hash_to_bytes(&market.feed_id),
+        &market.target_price.to_le_bytes(),
+        &market.market_duration.to_le_bytes(),
+        &[market.bump],
+    ]];
+
+    for (outcome_mint, user_outcome_ata) in [
+        (&ctx.accounts.higher_mint, &ctx.accounts.user_higher_ata),
+        (&ctx.accounts.lower_mint, &ctx.accounts.user_lower_ata),
+    ] {
+        mint_to(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                MintTo {
+                    mint: outcome_mint.to_account_info(),
+                    to: user_outcome_ata.to_account_info(),
+                    authority: market.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            amount,
+        )?;
+    }
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct MintCompleteSet<'info> {
+    #[account(
+        mut,
+        seeds = [
+            market.creator.key().as_ref(),
+            &hash_to_bytes(&market.feed_id),
+            &market.target_price.to_le_bytes(),
+            &market.market_duration.to_le_bytes(),
+        ],
+        bump = market.bump,
+    )]
+    pub market: Box<Account<'info, Market>>,
+
+    #[account(
+        mut,
+        token::mint = market.mint,
+        token::authority = market,
+        token::token_program = token_program,
+        seeds = [
+            SET_VAULT_SEED.as_bytes(),
+            market.key().as_ref(),
+        ],
+        bump = market.set_vault_bump,
+    )]
+    pub set_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        mint::authority = market,
+        mint::token_program = token_program,
+        seeds = [
+            HIGHER_MINT_SEED.as_bytes(),
+            market.key().as_ref(),
+        ],
+        bump = market.higher_mint_bump,
+    )]
+    pub higher_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        mint::authority = market,
+        mint::token_program = token_program,
+        seeds = [
+            LOWER_MINT_SEED.as_bytes(),
+            market.key().as_ref(),
+        ],
+        bump = market.lower_mint_bump,
+    )]
+    pub lower_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        init_if_needed,
+        payer = user,
+        associated_token::mint = higher_mint,
+        associated_token::authority = user,
+        associated_token::token_program = token_program,
+    )]
+    pub user_higher_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        init_if_needed,
+        payer = user,
+        associated_token::mint = lower_mint,
+        associated_token::authority = user,
+        associated_token::token_program = token_program,
+    )]
+    pub user_lower_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        associated_token::mint = market.mint,
+        associated_token::authority = user,
+        associated_token::token_program = token_program,
+    )]
+    pub user_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        address = market.mint,
+        mint::token_program = token_program,
+    )]
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+
+    pub system_program: Program<'info, System>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
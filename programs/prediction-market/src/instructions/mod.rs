@@ -1,3 +1,30 @@
+pub mod initialize_config;
+pub use initialize_config::*;
+
+pub mod update_config;
+pub use update_config::*;
+
+pub mod propose_admin;
+pub use propose_admin::*;
+
+pub mod accept_admin;
+pub use accept_admin::*;
+
+pub mod add_allowed_mint;
+pub use add_allowed_mint::*;
+
+pub mod remove_allowed_mint;
+pub use remove_allowed_mint::*;
+
+pub mod initialize_treasury;
+pub use initialize_treasury::*;
+
+pub mod withdraw_protocol_fees;
+pub use withdraw_protocol_fees::*;
+
+pub mod withdraw_creator_fees;
+pub use withdraw_creator_fees::*;
+
 pub mod initialize_market;
 pub use initialize_market::*;
 
@@ -15,3 +42,75 @@ pub use place_bet::*;
 
 pub mod claim_bet;
 pub use claim_bet::*;
+
+pub mod void_market;
+pub use void_market::*;
+
+pub mod refund_bet;
+pub use refund_bet::*;
+
+pub mod claim_position;
+pub use claim_position::*;
+
+pub mod resolve_market;
+pub use resolve_market::*;
+
+pub mod record_price_sample;
+pub use record_price_sample::*;
+
+pub mod resolve_market_twap;
+pub use resolve_market_twap::*;
+
+pub mod propose_outcome;
+pub use propose_outcome::*;
+
+pub mod approve_outcome;
+pub use approve_outcome::*;
+
+pub mod propose_bonded_outcome;
+pub use propose_bonded_outcome::*;
+
+pub mod dispute_outcome;
+pub use dispute_outcome::*;
+
+pub mod settle_proposal;
+pub use settle_proposal::*;
+
+pub mod rule_dispute;
+pub use rule_dispute::*;
+
+pub mod initialize_outcome_pool;
+pub use initialize_outcome_pool::*;
+
+pub mod place_outcome_bet;
+pub use place_outcome_bet::*;
+
+pub mod resolve_categorical_market;
+pub use resolve_categorical_market::*;
+
+pub mod claim_outcome_bet;
+pub use claim_outcome_bet::*;
+
+pub mod close_outcome_pool;
+pub use close_outcome_pool::*;
+
+pub mod buy_shares;
+pub use buy_shares::*;
+
+pub mod initialize_outcome_mints;
+pub use initialize_outcome_mints::*;
+
+pub mod buy_outcome_tokens;
+pub use buy_outcome_tokens::*;
+
+pub mod redeem;
+pub use redeem::*;
+
+pub mod mint_complete_set;
+pub use mint_complete_set::*;
+
+pub mod merge_complete_set;
+pub use merge_complete_set::*;
+
+pub mod sell_bet;
+pub use sell_bet::*;
@@ -1,12 +1,14 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::*;
-use num_traits::*;
+use anchor_spl::token_interface::*;
 
 use crate::constants::*;
+use crate::math::{pool_odds, weighted_odds};
 use crate::states::*;
 use crate::MarketError;
 use crate::utils::hash_to_bytes;
 
+//a wager either gets its own Bet under the next bet id, or tops up the aggregated Position of the
+//user when one is passed instead, which is claimed once for both sides
 pub fn _place_bet(
     ctx: Context<PlaceBet>,
     bet_amount:u64,
@@ -14,18 +16,28 @@ pub fn _place_bet(
 ) -> Result<()> {
     let market = &mut ctx.accounts.market;
 
-    require!(market.initialization == MarketInitialization::InitializedPools, MarketError::InvalidMarketInitialization);
+    let clock = Clock::get()?;
+    market.require_status(MarketStatus::Open, clock.unix_timestamp)?;
+    require!(market.pricing_mode == PricingMode::Parimutuel, MarketError::WrongPricingMode);
+    require!(!market.is_scalar(), MarketError::WrongMarketKind);
+    require_gte!(bet_amount, market.min_bet, MarketError::BetTooSmall);
+    require_gte!(market.max_bet, bet_amount, MarketError::BetTooLarge);
+    require!(
+        ctx.accounts.position.is_none() || (ctx.accounts.bet.is_none() && ctx.accounts.user_position.is_none()),
+        MarketError::InvalidBetAccounts
+    );
 
     let bet_pool: AccountInfo = match bet_direction {
         Direction::Higher => ctx.accounts.higher_pool.to_account_info(),
         Direction::Lower => ctx.accounts.lower_pool.to_account_info()
     };
 
-    transfer(
+    transfer_checked(
         CpiContext::new_with_signer(
             ctx.accounts.token_program.to_account_info(),
-            Transfer {
+            TransferChecked {
                 from: ctx.accounts.user_ata.to_account_info(),
+                mint: ctx.accounts.mint.to_account_info(),
                 to: bet_pool,
                 authority: ctx.accounts.user.to_account_info(),
             },
@@ -34,46 +46,72 @@ pub fn _place_bet(
             &hash_to_bytes(&market.feed_id),
             &market.target_price.to_le_bytes(), 
             &market.market_duration.to_le_bytes(),
-            &[ctx.accounts.market.bump],
+            &[market.bump],
         ]],
         ),
         bet_amount,
+        ctx.accounts.mint.decimals,
     )?;
 
-    let higher_pool_amount = ctx.accounts.higher_pool.amount;
-    let lower_pool_amount = ctx.accounts.lower_pool.amount;
-    let odds = match bet_direction {
-        Direction::Higher => {
-            if lower_pool_amount == 0 {
-                ODDS_FIXED_POINT_MULTIPLIER // 1.0 in fixed-point representation
-            } else {
-                (higher_pool_amount * ODDS_FIXED_POINT_MULTIPLIER) / lower_pool_amount
-            }
+    let odds = pool_odds(
+        &bet_direction,
+        ctx.accounts.higher_pool.amount,
+        ctx.accounts.lower_pool.amount,
+    );
+
+    if let Some(position) = ctx.accounts.position.as_mut() {
+        //a position is claimed once, so it only counts as a single open bet on the market
+        if position.higher_amount == 0 && position.lower_amount == 0 {
+            market.open_bets = market.open_bets.checked_add(1).ok_or(MarketError::OpenBetsOverflow)?;
         }
-        Direction::Lower => {
-            if higher_pool_amount == 0 {
-                ODDS_FIXED_POINT_MULTIPLIER // 1.0 in fixed-point representation
-            } else {
-                (lower_pool_amount * ODDS_FIXED_POINT_MULTIPLIER) / higher_pool_amount
+
+        position.user = ctx.accounts.user.key();
+        position.market = market.key();
+        position.bump = ctx.bumps.position.ok_or(MarketError::InvalidBetAccounts)?;
+
+        match bet_direction {
+            Direction::Higher => {
+                position.higher_odds = weighted_odds(position.higher_amount, position.higher_odds, bet_amount, odds)?;
+                position.higher_amount = position.higher_amount
+                    .checked_add(bet_amount)
+                    .ok_or(MarketError::PayoutOverflow)?;
+            }
+            Direction::Lower => {
+                position.lower_odds = weighted_odds(position.lower_amount, position.lower_odds, bet_amount, odds)?;
+                position.lower_amount = position.lower_amount
+                    .checked_add(bet_amount)
+                    .ok_or(MarketError::PayoutOverflow)?;
             }
         }
+        return Ok(());
+    }
+
+    let (Some(user_position), Some(bet)) = (ctx.accounts.user_position.as_mut(), ctx.accounts.bet.as_mut()) else {
+        return err!(MarketError::InvalidBetAccounts);
     };
 
-    let bet = &mut ctx.accounts.bet;
+    market.open_bets = market.open_bets.checked_add(1).ok_or(MarketError::OpenBetsOverflow)?;
+
+    user_position.user = ctx.accounts.user.key();
+    user_position.market = market.key();
+    user_position.bump = ctx.bumps.user_position.ok_or(MarketError::InvalidBetAccounts)?;
+    let bet_id = user_position.next_bet_id;
+    user_position.next_bet_id = bet_id.checked_add(1).ok_or(MarketError::OpenBetsOverflow)?;
+
     bet.user = ctx.accounts.user.key();
-    bet.bump = ctx.bumps.bet;
+    bet.bump = ctx.bumps.bet.ok_or(MarketError::InvalidBetAccounts)?;
     bet.amount = bet_amount;
     bet.odds =odds;
     bet.claimed = false;
-    bet.market = ctx.accounts.market.key();
+    bet.market = market.key();
     bet.direction = bet_direction;
     bet.initialized = true;
+    bet.id = bet_id;
 
     Ok(())
 }
 
 #[derive(Accounts)]
-#[instruction(bet_amount:u64,bet_direction:Direction)]
 pub struct PlaceBet<'info> {
     #[account(
         mut,
@@ -91,36 +129,53 @@ pub struct PlaceBet<'info> {
         mut,
         token::mint = market.mint, 
         token::authority = market,
+        token::token_program = token_program,
         seeds = [
             HIGHER_POOL_SEED.as_bytes(),
             market.key().as_ref(),
         ],
         bump = market.higher_pool_bump,
     )]
-    pub higher_pool: Account<'info, TokenAccount>,
+    pub higher_pool: InterfaceAccount<'info, TokenAccount>,
 
     #[account(
         mut,
         token::mint = market.mint, 
         token::authority = market,
+        token::token_program = token_program,
         seeds = [
             LOWER_POOL_SEED.as_bytes(),
             market.key().as_ref()
         ],
         bump = market.lower_pool_bump,
     )]
-    pub lower_pool: Account<'info, TokenAccount>,
+    pub lower_pool: InterfaceAccount<'info, TokenAccount>,
 
     #[account(
         mut,
         associated_token::mint = market.mint,
         associated_token::authority = user,
+        associated_token::token_program = token_program,
     )]
-    pub user_ata: Account<'info, TokenAccount>,
+    pub user_ata: InterfaceAccount<'info, TokenAccount>,
 
     #[account(mut)]
     pub user: Signer<'info>,
     
+    //user_position and bet are passed for a single bet, position alone to top up a position
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = 8 + UserMarketPosition::INIT_SPACE,
+        seeds = [
+            USER_POSITION_SEED.as_bytes(),
+            user.key().as_ref(),
+            market.key().as_ref(),
+        ],
+        bump
+    )]
+    pub user_position: Option<Account<'info, UserMarketPosition>>,
+
     #[account(
         init,
         payer = user,
@@ -129,13 +184,31 @@ pub struct PlaceBet<'info> {
             BET_SEED.as_bytes(),
             user.key().as_ref(),
             market.key().as_ref(),
-            bet_amount.to_le_bytes().as_ref(),
-            &bet_direction.to_u8().unwrap().to_le_bytes(),
-        ], // I realize that a users may need to place the same exact bet multiple using a Bet Id might solve that
+            user_position.as_ref().map_or(0, |position| position.next_bet_id).to_le_bytes().as_ref(),
+        ],
         bump
     )]
-    pub bet: Account<'info,Bet>,
+    pub bet: Option<Account<'info,Bet>>,
+
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = 8 + Position::INIT_SPACE,
+        seeds = [
+            POSITION_SEED.as_bytes(),
+            user.key().as_ref(),
+            market.key().as_ref(),
+        ],
+        bump
+    )]
+    pub position: Option<Account<'info, Position>>,
 
     pub system_program: Program<'info, System>,
-    pub token_program: Program<'info, Token>,
+    #[account(
+        address = market.mint,
+        mint::token_program = token_program,
+    )]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    pub token_program: Interface<'info, TokenInterface>,
 }
@@ -0,0 +1,146 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::*;
+
+use crate::constants::*;
+use crate::math::outcome_odds;
+use crate::states::*;
+use crate::MarketError;
+use crate::utils::hash_to_bytes;
+
+pub fn _place_outcome_bet(
+    ctx: Context<PlaceOutcomeBet>,
+    bet_amount: u64,
+    outcome_index: u8,
+) -> Result<()> {
+    let market = &mut ctx.accounts.market;
+
+    let clock = Clock::get()?;
+    require!(market.is_categorical(), MarketError::WrongMarketKind);
+    require_gt!(market.outcome_count(), outcome_index, MarketError::InvalidOutcomeIndex);
+    market.require_status(MarketStatus::Open, clock.unix_timestamp)?;
+    require_gte!(bet_amount, market.min_bet, MarketError::BetTooSmall);
+    require_gte!(market.max_bet, bet_amount, MarketError::BetTooLarge);
+
+    transfer_checked(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            TransferChecked {
+                from: ctx.accounts.user_ata.to_account_info(),
+                mint: ctx.accounts.mint.to_account_info(),
+                to: ctx.accounts.outcome_pool.to_account_info(),
+                authority: ctx.accounts.user.to_account_info(),
+            },
+        ),
+        bet_amount,
+        ctx.accounts.mint.decimals,
+    )?;
+
+    let index = outcome_index as usize;
+    market.outcome_totals[index] = market.outcome_totals[index]
+        .checked_add(bet_amount)
+        .ok_or(MarketError::PayoutOverflow)?;
+    let total = market.outcome_totals_sum()?;
+    let odds = outcome_odds(market.outcome_totals[index], total - market.outcome_totals[index]);
+
+    market.open_bets = market.open_bets.checked_add(1).ok_or(MarketError::OpenBetsOverflow)?;
+
+    let user_position = &mut ctx.accounts.user_position;
+    user_position.user = ctx.accounts.user.key();
+    user_position.market = ctx.accounts.market.key();
+    user_position.bump = ctx.bumps.user_position;
+    let bet_id = user_position.next_bet_id;
+    user_position.next_bet_id = bet_id.checked_add(1).ok_or(MarketError::OpenBetsOverflow)?;
+
+    let bet = &mut ctx.accounts.bet;
+    bet.user = ctx.accounts.user.key();
+    bet.bump = ctx.bumps.bet;
+    bet.amount = bet_amount;
+    bet.odds = odds;
+    bet.claimed = false;
+    bet.market = ctx.accounts.market.key();
+    //categorical bets are settled on outcome_index, direction is only there to fill the shared
+    //Bet layout and is never read for them
+    bet.direction = Direction::Higher;
+    bet.outcome_index = outcome_index;
+    bet.initialized = true;
+    bet.id = bet_id;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(bet_amount: u64, outcome_index: u8)]
+pub struct PlaceOutcomeBet<'info> {
+    #[account(
+        mut,
+        seeds = [
+            market.creator.key().as_ref(), 
+            &hash_to_bytes(&market.feed_id),
+            &market.target_price.to_le_bytes(), 
+            &market.market_duration.to_le_bytes(),
+        ],
+        bump = market.bump,
+    )]
+    pub market: Box<Account<'info, Market>>,
+
+    #[account(
+        mut,
+        token::mint = market.mint, 
+        token::authority = market,
+        token::token_program = token_program,
+        seeds = [
+            OUTCOME_POOL_SEED.as_bytes(),
+            market.key().as_ref(),
+            &[outcome_index],
+        ],
+        bump = market.outcome_pool_bump(outcome_index),
+    )]
+    pub outcome_pool: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        associated_token::mint = market.mint,
+        associated_token::authority = user,
+        associated_token::token_program = token_program,
+    )]
+    pub user_ata: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+    
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = 8 + UserMarketPosition::INIT_SPACE,
+        seeds = [
+            USER_POSITION_SEED.as_bytes(),
+            user.key().as_ref(),
+            market.key().as_ref(),
+        ],
+        bump
+    )]
+    pub user_position: Account<'info, UserMarketPosition>,
+
+    #[account(
+        init,
+        payer = user,
+        space = 8 + Bet::INIT_SPACE,
+        seeds = [
+            BET_SEED.as_bytes(),
+            user.key().as_ref(),
+            market.key().as_ref(),
+            user_position.next_bet_id.to_le_bytes().as_ref(),
+        ],
+        bump
+    )]
+    pub bet: Account<'info,Bet>,
+
+    pub system_program: Program<'info, System>,
+    #[account(
+        address = market.mint,
+        mint::token_program = token_program,
+    )]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
@@ -0,0 +1,28 @@
+use anchor_lang::prelude::*;
+
+use crate::constants::*;
+use crate::states::ProgramConfig;
+use crate::MarketError;
+
+pub fn _propose_admin(
+    ctx: Context<ProposeAdmin>,
+    new_admin: Pubkey,
+) -> Result<()> {
+    //the current admin stays in charge until the new one accepts
+    ctx.accounts.config.pending_admin = Some(new_admin);
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ProposeAdmin<'info> {
+    #[account(
+        mut,
+        seeds = [CONFIG_SEED.as_bytes()],
+        bump = config.bump,
+        has_one = admin @ MarketError::UnauthorizedUser,
+    )]
+    pub config: Account<'info, ProgramConfig>,
+
+    pub admin: Signer<'info>,
+}
@@ -0,0 +1,93 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface, transfer_checked, TransferChecked};
+
+use crate::constants::*;
+use crate::states::*;
+use crate::MarketError;
+use crate::utils::hash_to_bytes;
+
+
+pub fn _propose_bonded_outcome(
+    ctx: Context<ProposeBondedOutcome>,
+    outcome: Outcome,
+) -> Result<()> {
+    let market = &mut ctx.accounts.market;
+    let clock = Clock::get()?;
+
+    require!(market.resolution_mode == ResolutionMode::Optimistic, MarketError::WrongResolutionMode);
+    market.transition(MarketStatus::Proposed, clock.unix_timestamp)?;
+
+    market.proposed_outcome = Some(outcome);
+    market.proposer = Some(ctx.accounts.proposer.key());
+    market.challenge_deadline = clock
+        .unix_timestamp
+        .checked_add(market.challenge_period as i64)
+        .ok_or(MarketError::MarketDurationOverflow)?;
+    market.bond_vault_bump = ctx.bumps.bond_vault;
+
+    transfer_checked(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            TransferChecked {
+                from: ctx.accounts.proposer_ata.to_account_info(),
+                mint: ctx.accounts.mint.to_account_info(),
+                to: ctx.accounts.bond_vault.to_account_info(),
+                authority: ctx.accounts.proposer.to_account_info(),
+            },
+        ),
+        market.bond_amount,
+        ctx.accounts.mint.decimals,
+    )?;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ProposeBondedOutcome<'info> {
+    #[account(
+        mut,
+        seeds = [
+            market.creator.key().as_ref(), 
+            &hash_to_bytes(&market.feed_id),
+            &market.target_price.to_le_bytes(), 
+            &market.market_duration.to_le_bytes(),
+        ],
+        bump = market.bump,
+    )]
+    pub market: Box<Account<'info, Market>>,
+
+    #[account(
+        init,
+        payer = proposer,
+        token::mint = mint,
+        token::authority = market,
+        token::token_program = token_program,
+        seeds = [
+            BOND_VAULT_SEED.as_bytes(),
+            market.key().as_ref(),
+        ],
+        bump,
+    )]
+    pub bond_vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        associated_token::mint = market.mint,
+        associated_token::authority = proposer,
+        associated_token::token_program = token_program,
+    )]
+    pub proposer_ata: InterfaceAccount<'info, TokenAccount>,
+
+    //anyone can propose, the bond is lost if the proposal is overturned
+    #[account(mut)]
+    pub proposer: Signer<'info>,
+
+    #[account(
+        address = market.mint,
+        mint::token_program = token_program,
+    )]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
@@ -0,0 +1,85 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::TokenAccount;
+
+use crate::constants::*;
+use crate::states::*;
+use crate::MarketError;
+use crate::utils::hash_to_bytes;
+
+
+pub fn _propose_outcome(
+    ctx: Context<ProposeOutcome>,
+    outcome: Outcome,
+) -> Result<()> {
+    let market = &mut ctx.accounts.market;
+    let clock = Clock::get()?;
+
+    require!(market.resolution_mode == ResolutionMode::Committee, MarketError::WrongResolutionMode);
+    market.require_status(MarketStatus::Locked, clock.unix_timestamp)?;
+    let member = market.committee_index(&ctx.accounts.proposer.key())?;
+
+    //other outcomes can be proposed alongside, an outcome somebody proposed is approved instead
+    require!(market.approval_count(outcome) == 0, MarketError::OutcomeAlreadyProposed);
+    market.record_approval(member, outcome)?;
+
+    resolve_if_approved(market, outcome, &ctx.accounts.higher_pool, &ctx.accounts.lower_pool, &clock)
+}
+
+//resolves the market to the outcome once the committee threshold is reached for it
+pub fn resolve_if_approved(
+    market: &mut Account<Market>,
+    outcome: Outcome,
+    higher_pool: &InterfaceAccount<TokenAccount>,
+    lower_pool: &InterfaceAccount<TokenAccount>,
+    clock: &Clock,
+) -> Result<()> {
+    if market.approval_count(outcome) < market.committee_threshold as u32 {
+        return Ok(());
+    }
+
+    market.transition(MarketStatus::Resolved, clock.unix_timestamp)?;
+    market.outcome = Some(outcome);
+    market.resolution_slot = clock.slot;
+    market.snapshot_pools(higher_pool, lower_pool);
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ProposeOutcome<'info> {
+    #[account(
+        mut,
+        seeds = [
+            market.creator.key().as_ref(), 
+            &hash_to_bytes(&market.feed_id),
+            &market.target_price.to_le_bytes(), 
+            &market.market_duration.to_le_bytes(),
+        ],
+        bump = market.bump,
+    )]
+    pub market: Account<'info, Market>,
+
+    #[account(
+        token::mint = market.mint, 
+        token::authority = market,
+        seeds = [
+            HIGHER_POOL_SEED.as_bytes(),
+            market.key().as_ref(),
+        ],
+        bump = market.higher_pool_bump,
+    )]
+    pub higher_pool: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        token::mint = market.mint, 
+        token::authority = market,
+        seeds = [
+            LOWER_POOL_SEED.as_bytes(),
+            market.key().as_ref()
+        ],
+        bump = market.lower_pool_bump,
+    )]
+    pub lower_pool: InterfaceAccount<'info, TokenAccount>,
+
+    pub proposer: Signer<'info>,
+}
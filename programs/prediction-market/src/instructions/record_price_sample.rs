@@ -0,0 +1,86 @@
+use anchor_lang::prelude::*;
+use pyth_solana_receiver_sdk::price_update::get_feed_id_from_hex;
+
+use crate::constants::*;
+use crate::instructions::sample_price;
+use crate::oracle::read_oracle_price;
+use crate::states::*;
+use crate::MarketError;
+use crate::utils::hash_to_bytes;
+
+
+pub fn _record_price_sample(
+    ctx: Context<RecordPriceSample>,
+) -> Result<()> {
+    let market = &ctx.accounts.market;
+    let price_history = &mut ctx.accounts.price_history;
+    let clock = Clock::get()?;
+
+    require!(market.resolution_mode == ResolutionMode::Twap, MarketError::WrongResolutionMode);
+    match market.status_at(clock.unix_timestamp) {
+        MarketStatus::Open | MarketStatus::Locked => {}
+        MarketStatus::Created => return err!(MarketError::MarketNotOpen),
+        _ => return err!(MarketError::MarketAlreadyResolved),
+    }
+
+    let feed_id_str = std::str::from_utf8(&market.feed_id)
+        .map_err(|_| MarketError::InvalidUtf8)?;
+
+    let feed_id = get_feed_id_from_hex(feed_id_str)
+        .map_err(|_| MarketError::InvalidFeedId)?;
+
+    let price = read_oracle_price(market.oracle_source, &ctx.accounts.price_feed, &feed_id)?;
+
+    //only prints from the final window before expiry count towards the average
+    let sample = sample_price(market, &price, market.twap_window_start(), market.expiry_timestamp)?;
+
+    //spacing samples out keeps a cranker from filling the buffer with prints of their choosing
+    //and makes the buffer last for the whole window
+    if let Some(latest) = price_history.latest() {
+        require_gte!(
+            sample.publish_time,
+            latest.publish_time + market.twap_sample_spacing(),
+            MarketError::StalePriceSample
+        );
+    }
+
+    price_history.market = market.key();
+    price_history.bump = ctx.bumps.price_history;
+    price_history.push(sample);
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct RecordPriceSample<'info> {
+    #[account(
+        seeds = [
+            market.creator.key().as_ref(), 
+            &hash_to_bytes(&market.feed_id),
+            &market.target_price.to_le_bytes(), 
+            &market.market_duration.to_le_bytes(),
+        ],
+        bump = market.bump,
+    )]
+    pub market: Account<'info, Market>,
+
+    #[account(
+        init_if_needed,
+        payer = cranker,
+        space = 8 + PriceHistory::INIT_SPACE,
+        seeds = [
+            PRICE_HISTORY_SEED.as_bytes(),
+            market.key().as_ref(),
+        ],
+        bump,
+    )]
+    pub price_history: Box<Account<'info, PriceHistory>>,
+
+    /// CHECK: owner, feed and layout are checked by the adapter of the market's oracle source
+    pub price_feed: UncheckedAccount<'info>,
+
+    //anyone can record samples, the first one pays for the history account
+    #[account(mut)]
+    pub cranker: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
@@ -0,0 +1,209 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::*;
+
+use crate::constants::*;
+use crate::math::{parimutuel_winnings, scalar_payout};
+use crate::states::*;
+use crate::MarketError;
+use crate::utils::hash_to_bytes;
+
+pub fn _redeem(
+    ctx: Context<Redeem>,
+    amount: u64,
+    direction: Direction,
+) -> Result<()> {
+    let market = &mut ctx.accounts.market;
+    let clock = Clock::get()?;
+
+    require!(market.outcome_tokens, MarketError::WrongMarketKind);
+    require!(
+        matches!(
+            market.status_at(clock.unix_timestamp),
+            MarketStatus::Resolved | MarketStatus::Voided | MarketStatus::Settled
+        ),
+        MarketError::MarketNotRedeemable
+    );
+    require_gt!(amount, 0, MarketError::EmptyPosition);
+    let scalar = market.is_scalar();
+
+    //a winning token pays one unit and on a push every token pays half, which one complete set
+    //of minted shares always covers. A resolved scalar market pays each token its side of the
+    //range instead. A voided market has no outcome, so each token gets back its share of what
+    //was paid for its direction
+    let payout = match (market.outcome, market.final_price) {
+        (Some(Outcome::Push), _) => amount / 2,
+        (Some(_), _) => {
+            require!(market.is_winning_direction(&direction)?, MarketError::LosingOutcomeToken);
+            amount
+        }
+        (None, Some(final_price)) if scalar => {
+            scalar_payout(amount, &direction, final_price, market.lower_bound, market.upper_bound)?
+        }
+        (None, _) => {
+            let supply = ctx.accounts.outcome_mint.supply;
+            let token_cost = market.token_cost_mut(&direction);
+            let refund = parimutuel_winnings(amount, supply, *token_cost)?;
+            *token_cost -= refund;
+            refund
+        }
+    };
+    market.outstanding_outcome_tokens = market.outstanding_outcome_tokens
+        .checked_sub(amount)
+        .ok_or(MarketError::PayoutOverflow)?;
+
+    burn(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            Burn {
+                mint: ctx.accounts.outcome_mint.to_account_info(),
+                from: ctx.accounts.user_outcome_ata.to_account_info(),
+                authority: ctx.accounts.user.to_account_info(),
+            },
+        ),
+        amount,
+    )?;
+
+    //the payout is taken from the pool of the redeemed direction first, then from the stakes on
+    //the other side and finally from the collateral of complete sets. The pools of a scalar market
+    //only hold the seed liquidity of the creator, its tokens are all backed by the vault
+    let (own_pool, other_pool) = match direction {
+        Direction::Higher => (&ctx.accounts.higher_pool, &ctx.accounts.lower_pool),
+        Direction::Lower => (&ctx.accounts.lower_pool, &ctx.accounts.higher_pool),
+    };
+    let (from_own, from_other) = if scalar {
+        (0, 0)
+    } else {
+        let from_own = payout.min(own_pool.amount);
+        (from_own, (payout - from_own).min(other_pool.amount))
+    };
+    let from_vault = payout - from_own - from_other;
+
+    let market = &ctx.accounts.market;
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        market.creator.as_ref(),
+        &hash_to_bytes(&market.feed_id),
+        &market.target_price.to_le_bytes(),
+        &market.market_duration.to_le_bytes(),
+        &[market.bump],
+    ]];
+
+    for (pool, pool_amount) in [
+        (own_pool, from_own),
+        (other_pool, from_other),
+        (&ctx.accounts.set_vault, from_vault),
+    ] {
+        if pool_amount == 0 {
+            continue;
+        }
+        transfer_checked(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                TransferChecked {
+                    from: pool.to_account_info(),
+                    mint: ctx.accounts.mint.to_account_info(),
+                    to: ctx.accounts.user_ata.to_account_info(),
+                    authority: market.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            pool_amount,
+            ctx.accounts.mint.decimals,
+        )?;
+    }
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(amount: u64, direction: Direction)]
+pub struct Redeem<'info> {
+    #[account(
+        mut,
+        seeds = [
+            market.creator.key().as_ref(),
+            &hash_to_bytes(&market.feed_id),
+            &market.target_price.to_le_bytes(),
+            &market.market_duration.to_le_bytes(),
+        ],
+        bump = market.bump,
+    )]
+    pub market: Box<Account<'info, Market>>,
+
+    #[account(
+        mut,
+        token::mint = market.mint,
+        token::authority = market,
+        token::token_program = token_program,
+        seeds = [
+            HIGHER_POOL_SEED.as_bytes(),
+            market.key().as_ref(),
+        ],
+        bump = market.higher_pool_bump,
+    )]
+    pub higher_pool: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        token::mint = market.mint,
+        token::authority = market,
+        token::token_program = token_program,
+        seeds = [
+            LOWER_POOL_SEED.as_bytes(),
+            market.key().as_ref()
+        ],
+        bump = market.lower_pool_bump,
+    )]
+    pub lower_pool: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        token::mint = market.mint,
+        token::authority = market,
+        token::token_program = token_program,
+        seeds = [
+            SET_VAULT_SEED.as_bytes(),
+            market.key().as_ref(),
+        ],
+        bump = market.set_vault_bump,
+    )]
+    pub set_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    //mint of the direction redeemed
+    #[account(
+        mut,
+        mint::authority = market,
+        mint::token_program = token_program,
+        seeds = [
+            direction.mint_seed(),
+            market.key().as_ref(),
+        ],
+        bump = market.outcome_mint_bump(&direction),
+    )]
+    pub outcome_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        token::mint = outcome_mint,
+        token::authority = user,
+        token::token_program = token_program,
+    )]
+    pub user_outcome_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        associated_token::mint = market.mint,
+        associated_token::authority = user,
+        associated_token::token_program = token_program,
+    )]
+    pub user_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub user: Signer<'info>,
+
+    #[account(
+        address = market.mint,
+        mint::token_program = token_program,
+    )]
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
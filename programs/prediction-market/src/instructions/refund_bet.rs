@@ -0,0 +1,122 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::*;
+
+use crate::constants::*;
+use crate::instructions::{refund_stake, PayoutAccounts};
+use crate::states::*;
+use crate::MarketError;
+use crate::utils::hash_to_bytes;
+
+
+pub fn _refund_bet(
+    ctx: Context<RefundBet>,
+) -> Result<()> {
+    let bet = &mut ctx.accounts.bet;
+    let market = &mut ctx.accounts.market;
+    let clock = Clock::get()?;
+
+    market.require_status(MarketStatus::Voided, clock.unix_timestamp)?;
+    require_keys_eq!(market.key(),bet.market,MarketError::BetMarketMismatch);
+    require_keys_eq!(ctx.accounts.user.key(),bet.user,MarketError::UnauthorizedUser);
+    require_eq!(bet.claimed,false,MarketError::BetIsClaimed);
+
+    refund_stake(
+        market,
+        &PayoutAccounts {
+            higher_pool: &ctx.accounts.higher_pool,
+            lower_pool: &ctx.accounts.lower_pool,
+            user_ata: &ctx.accounts.user_ata,
+            mint: &ctx.accounts.mint,
+            token_program: &ctx.accounts.token_program,
+        },
+        &bet.direction,
+        bet.amount,
+    )?;
+
+    market.open_bets = market.open_bets.checked_sub(1).ok_or(MarketError::OpenBetsOverflow)?;
+    if market.open_bets == 0 {
+        market.transition(MarketStatus::Settled, clock.unix_timestamp)?;
+    }
+
+    bet.amount = 0;
+    bet.claimed = true;
+    bet.initialized = false;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct RefundBet<'info> {
+    #[account(
+        mut,
+        seeds = [
+            market.creator.key().as_ref(), 
+            &hash_to_bytes(&market.feed_id),
+            &market.target_price.to_le_bytes(), 
+            &market.market_duration.to_le_bytes(),
+        ],
+        bump = market.bump,
+        address = bet.market, 
+    )]
+    pub market: Account<'info, Market>,
+
+    #[account(
+        mut,
+        token::mint = market.mint, 
+        token::authority = market,
+        token::token_program = token_program,
+        seeds = [
+            HIGHER_POOL_SEED.as_bytes(),
+            market.key().as_ref(),
+        ],
+        bump = market.higher_pool_bump,
+    )]
+    pub higher_pool: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        token::mint = market.mint, 
+        token::authority = market,
+        token::token_program = token_program,
+        seeds = [
+            LOWER_POOL_SEED.as_bytes(),
+            market.key().as_ref()
+        ],
+        bump = market.lower_pool_bump,
+    )]
+    pub lower_pool: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        associated_token::mint = market.mint,
+        associated_token::authority = user,
+        associated_token::token_program = token_program,
+    )]
+    pub user_ata: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+    )]
+    pub user: Signer<'info>,
+    
+    #[account(
+        mut,
+        close = user,
+        seeds = [
+            BET_SEED.as_bytes(),
+            user.key().as_ref(),
+            market.key().as_ref(),
+            bet.id.to_le_bytes().as_ref(),
+        ], 
+        bump = bet.bump,
+    )]
+    pub bet: Account<'info,Bet>,
+
+    #[account(
+        address = market.mint,
+        mint::token_program = token_program,
+    )]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
@@ -0,0 +1,36 @@
+use anchor_lang::prelude::*;
+
+use crate::constants::*;
+use crate::states::{AllowedMint, ProgramConfig};
+use crate::MarketError;
+
+pub fn _remove_allowed_mint(
+    _ctx: Context<RemoveAllowedMint>,
+) -> Result<()> {
+    //markets that are already funded keep the limits they snapshotted
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct RemoveAllowedMint<'info> {
+    #[account(
+        seeds = [CONFIG_SEED.as_bytes()],
+        bump = config.bump,
+        has_one = admin @ MarketError::UnauthorizedUser,
+    )]
+    pub config: Account<'info, ProgramConfig>,
+
+    #[account(
+        mut,
+        close = admin,
+        seeds = [
+            ALLOWED_MINT_SEED.as_bytes(),
+            allowed_mint.mint.as_ref(),
+        ],
+        bump = allowed_mint.bump,
+    )]
+    pub allowed_mint: Account<'info, AllowedMint>,
+
+    #[account(mut)]
+    pub admin: Signer<'info>,
+}
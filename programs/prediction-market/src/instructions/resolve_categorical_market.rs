@@ -0,0 +1,126 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface, transfer_checked, TransferChecked};
+use pyth_solana_receiver_sdk::price_update::get_feed_id_from_hex;
+
+use crate::constants::*;
+use crate::instructions::sample_expiry_price;
+use crate::oracle::read_oracle_price;
+use crate::states::*;
+use crate::MarketError;
+use crate::utils::hash_to_bytes;
+
+
+pub fn _resolve_categorical_market<'info>(
+    ctx: Context<'_, '_, 'info, 'info, ResolveCategoricalMarket<'info>>,
+) -> Result<()> {
+    let market = &mut ctx.accounts.market;
+    let clock = Clock::get()?;
+
+    market.transition(MarketStatus::Resolved, clock.unix_timestamp)?;
+    require!(market.is_categorical(), MarketError::WrongMarketKind);
+
+    let feed_id_str = std::str::from_utf8(&market.feed_id)
+        .map_err(|_| MarketError::InvalidUtf8)?;
+
+    let feed_id = get_feed_id_from_hex(feed_id_str)
+        .map_err(|_| MarketError::InvalidFeedId)?;
+
+    //the outcome pools follow as remaining accounts, in outcome index order. They are checked
+    //before the oracle is read so a wrong pool list fails the same way whatever the price
+    let pools = ctx.remaining_accounts;
+    require_eq!(pools.len(), market.outcome_count() as usize, MarketError::InvalidOutcomePool);
+    let market_key = market.key();
+    for (index, pool) in pools.iter().enumerate() {
+        let expected = Pubkey::create_program_address(
+            &[
+                OUTCOME_POOL_SEED.as_bytes(),
+                market_key.as_ref(),
+                &[index as u8],
+                &[market.outcome_pool_bumps[index]],
+            ],
+            &crate::ID,
+        )
+        .map_err(|_| MarketError::InvalidOutcomePool)?;
+        require_keys_eq!(pool.key(), expected, MarketError::InvalidOutcomePool);
+        let outcome_pool = TokenAccount::try_deserialize(&mut &pool.data.borrow()[..])?;
+        market.snapshot_outcome_pool(index, &outcome_pool);
+    }
+
+    let price = read_oracle_price(market.oracle_source, &ctx.accounts.price_feed, &feed_id)?;
+    let sample = sample_expiry_price(market, &price)?;
+
+    //if the band around the price spans two buckets, nobody is picked as the winner
+    let winning_outcome = market.outcome_index_for(sample.price);
+    require!(
+        market.outcome_index_for(sample.lowest_price) == winning_outcome
+            && market.outcome_index_for(sample.highest_price) == winning_outcome,
+        MarketError::OutcomeUndetermined
+    );
+
+    market.final_price = Some(sample.price);
+    market.winning_outcome = Some(winning_outcome);
+    market.resolution_slot = clock.slot;
+    market.resolution_publish_time = sample.publish_time;
+
+    let market = &ctx.accounts.market;
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        market.creator.as_ref(),
+        &hash_to_bytes(&market.feed_id),
+        &market.target_price.to_le_bytes(),
+        &market.market_duration.to_le_bytes(),
+        &[market.bump],
+    ]];
+
+    //losing pools are folded into the winning one so claims only ever touch a single pool
+    let winning_pool = &pools[winning_outcome as usize];
+    for (index, pool) in pools.iter().enumerate() {
+        let amount = market.outcome_totals[index];
+        if index == winning_outcome as usize || amount == 0 {
+            continue;
+        }
+        transfer_checked(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                TransferChecked {
+                    from: pool.to_account_info(),
+                    mint: ctx.accounts.mint.to_account_info(),
+                    to: winning_pool.to_account_info(),
+                    authority: market.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            amount,
+            ctx.accounts.mint.decimals,
+        )?;
+    }
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ResolveCategoricalMarket<'info> {
+    #[account(
+        mut,
+        seeds = [
+            market.creator.key().as_ref(), 
+            &hash_to_bytes(&market.feed_id),
+            &market.target_price.to_le_bytes(), 
+            &market.market_duration.to_le_bytes(),
+        ],
+        bump = market.bump,
+    )]
+    pub market: Box<Account<'info, Market>>,
+
+    /// CHECK: owner, feed and layout are checked by the adapter of the market's oracle source
+    pub price_feed: UncheckedAccount<'info>,
+
+    #[account(
+        address = market.mint,
+        mint::token_program = token_program,
+    )]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    //anyone can crank the resolution once the market duration is over
+    pub resolver: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
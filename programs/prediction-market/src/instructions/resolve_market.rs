@@ -0,0 +1,166 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::TokenAccount;
+use pyth_solana_receiver_sdk::price_update::get_feed_id_from_hex;
+
+use crate::constants::*;
+use crate::math::{median, normalize_price};
+use crate::oracle::{read_oracle_price, OraclePrice};
+use crate::states::*;
+use crate::MarketError;
+use crate::utils::hash_to_bytes;
+
+
+pub fn _resolve_market(
+    ctx: Context<ResolveMarket>,
+) -> Result<()> {
+    let market = &mut ctx.accounts.market;
+    let clock = Clock::get()?;
+
+    market.transition(MarketStatus::Resolved, clock.unix_timestamp)?;
+    require!(market.resolution_mode == ResolutionMode::Spot, MarketError::WrongResolutionMode);
+    require!(!market.is_categorical(), MarketError::WrongMarketKind);
+
+    let feed_id_str = std::str::from_utf8(&market.feed_id)
+        .map_err(|_| MarketError::InvalidUtf8)?;
+
+    let feed_id = get_feed_id_from_hex(feed_id_str)
+        .map_err(|_| MarketError::InvalidFeedId)?;
+
+    //every feed is read up front so a wrong account fails the call instead of being skipped
+    require_eq!(
+        ctx.remaining_accounts.len(),
+        market.secondary_feeds.len(),
+        MarketError::MissingOracleFeeds
+    );
+    let mut prices = vec![read_oracle_price(market.oracle_source, &ctx.accounts.price_feed, &feed_id)?];
+    for (feed, account) in market.secondary_feeds.iter().zip(ctx.remaining_accounts) {
+        prices.push(read_oracle_price(feed.source, account, &feed.feed_id)?);
+    }
+
+    let mut samples = Vec::with_capacity(prices.len());
+    for price in prices.iter() {
+        match sample_expiry_price(market, price) {
+            Ok(sample) => samples.push(sample),
+            //a lone feed keeps its own error, with several feeds a stale or uncertain print is left out
+            Err(err) if market.secondary_feeds.is_empty() => return Err(err),
+            Err(_) => {}
+        }
+    }
+    require_gte!(samples.len(), market.oracle_quorum as usize, MarketError::OracleQuorumNotMet);
+
+    let adjusted_price = median(&mut samples.iter().map(|s| s.price).collect::<Vec<_>>())
+        .ok_or(MarketError::NoneFinalPrice)?;
+    let lowest_price = median(&mut samples.iter().map(|s| s.lowest_price).collect::<Vec<_>>())
+        .ok_or(MarketError::NoneFinalPrice)?;
+    let highest_price = median(&mut samples.iter().map(|s| s.highest_price).collect::<Vec<_>>())
+        .ok_or(MarketError::NoneFinalPrice)?;
+    //a scalar market pays out on where the price landed in its range, there is no side to pick
+    let outcome = if market.is_scalar() {
+        None
+    } else {
+        Some(market.determined_outcome(adjusted_price, lowest_price, highest_price)?)
+    };
+
+    market.final_price = Some(adjusted_price);
+    market.outcome = outcome;
+    market.resolution_slot = clock.slot;
+    market.resolution_publish_time = samples
+        .iter()
+        .map(|s| s.publish_time)
+        .max()
+        .ok_or(MarketError::NoneFinalPrice)?;
+
+    market.snapshot_pools(&ctx.accounts.higher_pool, &ctx.accounts.lower_pool);
+
+    Ok(())
+}
+
+//the price must be sampled at expiry, not whenever someone decides to crank: only the first update
+//published at or after expiry_timestamp settles the market, a later one is rejected even inside the
+//resolution window so the resolver can not pick between prints
+pub fn sample_expiry_price(market: &Market, price: &OraclePrice) -> Result<PriceObservation> {
+    let prev_publish_time = price.prev_publish_time.ok_or(MarketError::PriceUpdateNotFirstAfterExpiry)?;
+    require_gt!(market.expiry_timestamp, prev_publish_time, MarketError::PriceUpdateNotFirstAfterExpiry);
+
+    let window_end = market.expiry_timestamp + market.resolution_window as i64;
+    sample_price(market, price, market.expiry_timestamp, window_end)
+}
+
+//the price has to be published inside [window_start, window_end] to be sampled
+pub fn sample_price(
+    market: &Market,
+    price: &OraclePrice,
+    window_start: i64,
+    window_end: i64,
+) -> Result<PriceObservation> {
+    require_gte!(price.publish_time, window_start, MarketError::PriceUpdateTooEarly);
+    require_gte!(window_end, price.publish_time, MarketError::PriceUpdateTooLate);
+
+    let adjusted_price = normalize_price(price.price, price.exponent, market.target_exponent)?;
+
+    //confidence is compared in the oracle scale, before any adjustment
+    require!(
+        (price.conf as u128) * (BPS_DENOMINATOR as u128)
+            <= (price.price as u128) * (market.max_conf_bps as u128),
+        MarketError::ConfidenceTooWide
+    );
+
+    Ok(PriceObservation {
+        price: adjusted_price,
+        lowest_price: normalize_price(
+            price.price.saturating_sub(price.conf as i64),
+            price.exponent,
+            market.target_exponent,
+        )?,
+        highest_price: normalize_price(
+            price.price.saturating_add(price.conf as i64),
+            price.exponent,
+            market.target_exponent,
+        )?,
+        publish_time: price.publish_time,
+    })
+}
+
+#[derive(Accounts)]
+pub struct ResolveMarket<'info> {
+    #[account(
+        mut,
+        seeds = [
+            market.creator.key().as_ref(), 
+            &hash_to_bytes(&market.feed_id),
+            &market.target_price.to_le_bytes(), 
+            &market.market_duration.to_le_bytes(),
+        ],
+        bump = market.bump,
+    )]
+    pub market: Account<'info, Market>,
+
+    #[account(
+        token::mint = market.mint, 
+        token::authority = market,
+        seeds = [
+            HIGHER_POOL_SEED.as_bytes(),
+            market.key().as_ref(),
+        ],
+        bump = market.higher_pool_bump,
+    )]
+    pub higher_pool: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        token::mint = market.mint, 
+        token::authority = market,
+        seeds = [
+            LOWER_POOL_SEED.as_bytes(),
+            market.key().as_ref()
+        ],
+        bump = market.lower_pool_bump,
+    )]
+    pub lower_pool: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: owner, feed and layout are checked by the adapter of the market's oracle source
+    pub price_feed: UncheckedAccount<'info>,
+    //the accounts of market.secondary_feeds follow as remaining accounts, in the same order
+
+    //anyone can crank the resolution once the market duration is over
+    pub resolver: Signer<'info>,
+}
@@ -0,0 +1,100 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::TokenAccount;
+
+use crate::constants::*;
+use crate::math::time_weighted_average;
+use crate::states::*;
+use crate::MarketError;
+use crate::utils::hash_to_bytes;
+
+
+pub fn _resolve_market_twap(
+    ctx: Context<ResolveMarketTwap>,
+) -> Result<()> {
+    let market = &mut ctx.accounts.market;
+    let clock = Clock::get()?;
+
+    market.transition(MarketStatus::Resolved, clock.unix_timestamp)?;
+    require!(market.resolution_mode == ResolutionMode::Twap, MarketError::WrongResolutionMode);
+
+    //samples can be recorded up to expiry, so the average is only final after it
+    require_gt!(clock.unix_timestamp, market.expiry_timestamp, MarketError::MarketDurationNotOver);
+
+    let observations = ctx.accounts.price_history.observations();
+    require_gte!(observations.len(), MIN_TWAP_SAMPLES as usize, MarketError::InsufficientPriceSamples);
+
+    let average = |value: fn(&PriceObservation) -> u64| {
+        let points: Vec<(i64, u64)> = observations.iter().map(|o| (o.publish_time, value(o))).collect();
+        time_weighted_average(&points, market.twap_window_start(), market.expiry_timestamp, market.twap_max_gap())
+    };
+    let adjusted_price = average(|o| o.price)?;
+    let lowest_price = average(|o| o.lowest_price)?;
+    let highest_price = average(|o| o.highest_price)?;
+    //scalar tokens are paid on the average itself
+    let outcome = if market.is_scalar() {
+        None
+    } else {
+        Some(market.determined_outcome(adjusted_price, lowest_price, highest_price)?)
+    };
+
+    market.final_price = Some(adjusted_price);
+    market.outcome = outcome;
+    market.resolution_slot = clock.slot;
+    market.resolution_publish_time = observations
+        .last()
+        .map(|o| o.publish_time)
+        .ok_or(MarketError::InsufficientPriceSamples)?;
+
+    market.snapshot_pools(&ctx.accounts.higher_pool, &ctx.accounts.lower_pool);
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ResolveMarketTwap<'info> {
+    #[account(
+        mut,
+        seeds = [
+            market.creator.key().as_ref(), 
+            &hash_to_bytes(&market.feed_id),
+            &market.target_price.to_le_bytes(), 
+            &market.market_duration.to_le_bytes(),
+        ],
+        bump = market.bump,
+    )]
+    pub market: Account<'info, Market>,
+
+    #[account(
+        token::mint = market.mint, 
+        token::authority = market,
+        seeds = [
+            HIGHER_POOL_SEED.as_bytes(),
+            market.key().as_ref(),
+        ],
+        bump = market.higher_pool_bump,
+    )]
+    pub higher_pool: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        token::mint = market.mint, 
+        token::authority = market,
+        seeds = [
+            LOWER_POOL_SEED.as_bytes(),
+            market.key().as_ref()
+        ],
+        bump = market.lower_pool_bump,
+    )]
+    pub lower_pool: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        seeds = [
+            PRICE_HISTORY_SEED.as_bytes(),
+            market.key().as_ref(),
+        ],
+        bump = price_history.bump,
+    )]
+    pub price_history: Box<Account<'info, PriceHistory>>,
+
+    //anyone can crank the resolution once the market duration is over
+    pub resolver: Signer<'info>,
+}
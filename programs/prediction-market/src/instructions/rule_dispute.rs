@@ -0,0 +1,126 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::constants::*;
+use crate::instructions::release_bonds;
+use crate::states::*;
+use crate::MarketError;
+use crate::utils::hash_to_bytes;
+
+
+pub fn _rule_dispute(
+    ctx: Context<RuleDispute>,
+    outcome: Outcome,
+) -> Result<()> {
+    let market = &mut ctx.accounts.market;
+    let clock = Clock::get()?;
+
+    //an undisputed proposal is settled by settle_proposal, the admin only rules on disputes
+    market.require_status(MarketStatus::Disputed, clock.unix_timestamp)?;
+    market.transition(MarketStatus::Resolved, clock.unix_timestamp)?;
+    require!(market.proposer == Some(ctx.accounts.proposer.key()), MarketError::WrongBondRecipient);
+
+    //whoever the ruling proves right takes both bonds
+    let winner = if market.proposed_outcome == Some(outcome) {
+        market.proposer
+    } else {
+        market.disputer
+    };
+    require!(winner == Some(ctx.accounts.winner.key()), MarketError::WrongBondRecipient);
+
+    market.outcome = Some(outcome);
+    market.resolution_slot = clock.slot;
+
+    market.snapshot_pools(&ctx.accounts.higher_pool, &ctx.accounts.lower_pool);
+
+    release_bonds(
+        &ctx.accounts.market,
+        &ctx.accounts.bond_vault,
+        &ctx.accounts.winner_ata,
+        &ctx.accounts.proposer,
+        &ctx.accounts.mint,
+        &ctx.accounts.token_program,
+    )
+}
+
+#[derive(Accounts)]
+pub struct RuleDispute<'info> {
+    #[account(
+        mut,
+        seeds = [
+            market.creator.key().as_ref(), 
+            &hash_to_bytes(&market.feed_id),
+            &market.target_price.to_le_bytes(), 
+            &market.market_duration.to_le_bytes(),
+        ],
+        bump = market.bump,
+    )]
+    pub market: Box<Account<'info, Market>>,
+
+    #[account(
+        seeds = [CONFIG_SEED.as_bytes()],
+        bump = config.bump,
+        has_one = admin @ MarketError::UnauthorizedUser,
+    )]
+    pub config: Account<'info, ProgramConfig>,
+
+    #[account(
+        token::mint = market.mint, 
+        token::authority = market,
+        seeds = [
+            HIGHER_POOL_SEED.as_bytes(),
+            market.key().as_ref(),
+        ],
+        bump = market.higher_pool_bump,
+    )]
+    pub higher_pool: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        token::mint = market.mint, 
+        token::authority = market,
+        seeds = [
+            LOWER_POOL_SEED.as_bytes(),
+            market.key().as_ref()
+        ],
+        bump = market.lower_pool_bump,
+    )]
+    pub lower_pool: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        token::mint = market.mint,
+        token::authority = market,
+        token::token_program = token_program,
+        seeds = [
+            BOND_VAULT_SEED.as_bytes(),
+            market.key().as_ref(),
+        ],
+        bump = market.bond_vault_bump,
+    )]
+    pub bond_vault: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: checked against the proposer or disputer the ruling sides with
+    pub winner: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        associated_token::mint = market.mint,
+        associated_token::authority = winner,
+        associated_token::token_program = token_program,
+    )]
+    pub winner_ata: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: checked against market.proposer, receives the bond vault rent
+    #[account(mut)]
+    pub proposer: UncheckedAccount<'info>,
+
+    #[account(
+        address = market.mint,
+        mint::token_program = token_program,
+    )]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    //the config admin arbitrates disputes
+    pub admin: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
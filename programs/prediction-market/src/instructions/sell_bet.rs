@@ -0,0 +1,188 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::*;
+
+use crate::constants::*;
+use crate::math::{exit_value, fee_amount, outcome_odds};
+use crate::states::*;
+use crate::MarketError;
+use crate::utils::hash_to_bytes;
+
+
+pub fn _sell_bet(
+    ctx: Context<SellBet>,
+) -> Result<()> {
+    let bet = &mut ctx.accounts.bet;
+    let market = &mut ctx.accounts.market;
+    let clock = Clock::get()?;
+
+    market.require_status(MarketStatus::Open, clock.unix_timestamp)?;
+    require!(!market.is_categorical(), MarketError::WrongMarketKind);
+    require!(market.pricing_mode == PricingMode::Parimutuel, MarketError::WrongPricingMode);
+    require_keys_eq!(market.key(),bet.market,MarketError::BetMarketMismatch);
+    require_keys_eq!(ctx.accounts.user.key(),bet.user,MarketError::UnauthorizedUser);
+    require_eq!(bet.claimed,false,MarketError::BetIsClaimed);
+
+    //bet.odds were taken from the pools before the stake went in, so the current odds leave the
+    //stake out as well. Whatever is not paid back stays in the pool as a forfeit that the winners
+    //share at resolution, and the exit fee goes to the treasury as a protocol fee
+    let (bet_pool, other_pool) = match bet.direction {
+        Direction::Higher => (&ctx.accounts.higher_pool, &ctx.accounts.lower_pool),
+        Direction::Lower => (&ctx.accounts.lower_pool, &ctx.accounts.higher_pool),
+    };
+    let current_odds = outcome_odds(bet_pool.amount.saturating_sub(bet.amount), other_pool.amount);
+    let value = exit_value(bet.amount, bet.odds, current_odds)?;
+    let exit_fee = fee_amount(value, market.exit_fee_bps)?;
+    let forfeit = bet.amount.checked_sub(value).ok_or(MarketError::PayoutOverflow)?;
+    let forfeited = market.forfeited_mut(&bet.direction);
+    *forfeited = forfeited.checked_add(forfeit).ok_or(MarketError::PayoutOverflow)?;
+    let bet_pool = bet_pool.to_account_info();
+
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        market.creator.as_ref(),
+        &hash_to_bytes(&market.feed_id),
+        &market.target_price.to_le_bytes(),
+        &market.market_duration.to_le_bytes(),
+        &[market.bump],
+    ]];
+
+    transfer_checked(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            TransferChecked {
+                from: bet_pool.clone(),
+                mint: ctx.accounts.mint.to_account_info(),
+                to: ctx.accounts.user_ata.to_account_info(),
+                authority: market.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        value - exit_fee,
+        ctx.accounts.mint.decimals,
+    )?;
+
+    if exit_fee > 0 {
+        transfer_checked(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                TransferChecked {
+                    from: bet_pool,
+                    mint: ctx.accounts.mint.to_account_info(),
+                    to: ctx.accounts.treasury_vault.to_account_info(),
+                    authority: market.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            exit_fee,
+            ctx.accounts.mint.decimals,
+        )?;
+    }
+
+    ctx.accounts.treasury.protocol_fees = ctx.accounts.treasury.protocol_fees
+        .checked_add(exit_fee)
+        .ok_or(MarketError::FeeOverflow)?;
+    market.open_bets = market.open_bets.checked_sub(1).ok_or(MarketError::OpenBetsOverflow)?;
+
+    //just for increased redundancy because the bet account should be closed after
+    bet.amount = 0;
+    bet.claimed = true;
+    bet.initialized = false;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SellBet<'info> {
+    #[account(
+        mut,
+        seeds = [
+            market.creator.key().as_ref(), 
+            &hash_to_bytes(&market.feed_id),
+            &market.target_price.to_le_bytes(), 
+            &market.market_duration.to_le_bytes(),
+        ],
+        bump = market.bump,
+        address = bet.market, 
+    )]
+    pub market: Account<'info, Market>,
+
+    #[account(
+        mut,
+        token::mint = market.mint, 
+        token::authority = market,
+        token::token_program = token_program,
+        seeds = [
+            HIGHER_POOL_SEED.as_bytes(),
+            market.key().as_ref(),
+        ],
+        bump = market.higher_pool_bump,
+    )]
+    pub higher_pool: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        token::mint = market.mint, 
+        token::authority = market,
+        token::token_program = token_program,
+        seeds = [
+            LOWER_POOL_SEED.as_bytes(),
+            market.key().as_ref()
+        ],
+        bump = market.lower_pool_bump,
+    )]
+    pub lower_pool: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        associated_token::mint = market.mint,
+        associated_token::authority = user,
+        associated_token::token_program = token_program,
+    )]
+    pub user_ata: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+    
+    #[account(
+        mut,
+        close = user,
+        seeds = [
+            BET_SEED.as_bytes(),
+            user.key().as_ref(),
+            market.key().as_ref(),
+            bet.id.to_le_bytes().as_ref(),
+        ], 
+        bump = bet.bump,
+    )]
+    pub bet: Account<'info,Bet>,
+
+    #[account(
+        mut,
+        seeds = [
+            TREASURY_SEED.as_bytes(),
+            market.mint.as_ref(),
+        ],
+        bump = treasury.bump,
+    )]
+    pub treasury: Account<'info, Treasury>,
+
+    #[account(
+        mut,
+        token::mint = market.mint,
+        token::authority = treasury,
+        token::token_program = token_program,
+        seeds = [
+            TREASURY_VAULT_SEED.as_bytes(),
+            market.mint.as_ref(),
+        ],
+        bump = treasury.vault_bump,
+    )]
+    pub treasury_vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        address = market.mint,
+        mint::token_program = token_program,
+    )]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
@@ -0,0 +1,151 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{close_account, CloseAccount, Mint, TokenAccount, TokenInterface, transfer_checked, TransferChecked};
+
+use crate::constants::*;
+use crate::states::*;
+use crate::MarketError;
+use crate::utils::hash_to_bytes;
+
+
+pub fn _settle_proposal(
+    ctx: Context<SettleProposal>,
+) -> Result<()> {
+    let market = &mut ctx.accounts.market;
+    let clock = Clock::get()?;
+
+    //a disputed proposal waits for the admin ruling, whatever the time
+    market.require_status(MarketStatus::Proposed, clock.unix_timestamp)?;
+    require_gt!(clock.unix_timestamp, market.challenge_deadline, MarketError::ChallengeWindowOpen);
+    market.transition(MarketStatus::Resolved, clock.unix_timestamp)?;
+    require!(market.proposer == Some(ctx.accounts.proposer.key()), MarketError::WrongBondRecipient);
+
+    //nobody disputed, so the proposal stands and the proposer gets the bond back
+    market.outcome = market.proposed_outcome;
+    market.resolution_slot = clock.slot;
+
+    market.snapshot_pools(&ctx.accounts.higher_pool, &ctx.accounts.lower_pool);
+
+    release_bonds(
+        &ctx.accounts.market,
+        &ctx.accounts.bond_vault,
+        &ctx.accounts.proposer_ata,
+        &ctx.accounts.proposer,
+        &ctx.accounts.mint,
+        &ctx.accounts.token_program,
+    )
+}
+
+//sends everything in the bond vault to `recipient_ata` and returns the vault rent to the proposer who paid it
+pub fn release_bonds<'info>(
+    market: &Account<'info, Market>,
+    bond_vault: &InterfaceAccount<'info, TokenAccount>,
+    recipient_ata: &InterfaceAccount<'info, TokenAccount>,
+    proposer: &AccountInfo<'info>,
+    mint: &InterfaceAccount<'info, Mint>,
+    token_program: &Interface<'info, TokenInterface>,
+) -> Result<()> {
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        market.creator.as_ref(),
+        &hash_to_bytes(&market.feed_id),
+        &market.target_price.to_le_bytes(),
+        &market.market_duration.to_le_bytes(),
+        &[market.bump],
+    ]];
+
+    transfer_checked(
+        CpiContext::new_with_signer(
+            token_program.to_account_info(),
+            TransferChecked {
+                from: bond_vault.to_account_info(),
+                mint: mint.to_account_info(),
+                to: recipient_ata.to_account_info(),
+                authority: market.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        bond_vault.amount,
+        mint.decimals,
+    )?;
+
+    close_account(CpiContext::new_with_signer(
+        token_program.to_account_info(),
+        CloseAccount {
+            account: bond_vault.to_account_info(),
+            destination: proposer.to_account_info(),
+            authority: market.to_account_info(),
+        },
+        signer_seeds,
+    ))
+}
+
+#[derive(Accounts)]
+pub struct SettleProposal<'info> {
+    #[account(
+        mut,
+        seeds = [
+            market.creator.key().as_ref(), 
+            &hash_to_bytes(&market.feed_id),
+            &market.target_price.to_le_bytes(), 
+            &market.market_duration.to_le_bytes(),
+        ],
+        bump = market.bump,
+    )]
+    pub market: Box<Account<'info, Market>>,
+
+    #[account(
+        token::mint = market.mint, 
+        token::authority = market,
+        seeds = [
+            HIGHER_POOL_SEED.as_bytes(),
+            market.key().as_ref(),
+        ],
+        bump = market.higher_pool_bump,
+    )]
+    pub higher_pool: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        token::mint = market.mint, 
+        token::authority = market,
+        seeds = [
+            LOWER_POOL_SEED.as_bytes(),
+            market.key().as_ref()
+        ],
+        bump = market.lower_pool_bump,
+    )]
+    pub lower_pool: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        token::mint = market.mint,
+        token::authority = market,
+        token::token_program = token_program,
+        seeds = [
+            BOND_VAULT_SEED.as_bytes(),
+            market.key().as_ref(),
+        ],
+        bump = market.bond_vault_bump,
+    )]
+    pub bond_vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        associated_token::mint = market.mint,
+        associated_token::authority = proposer,
+        associated_token::token_program = token_program,
+    )]
+    pub proposer_ata: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: checked against market.proposer, receives the bond vault rent
+    #[account(mut)]
+    pub proposer: UncheckedAccount<'info>,
+
+    #[account(
+        address = market.mint,
+        mint::token_program = token_program,
+    )]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    //anyone can settle an undisputed proposal once the challenge window is over
+    pub caller: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
@@ -0,0 +1,26 @@
+use anchor_lang::prelude::*;
+
+use crate::constants::*;
+use crate::states::{ConfigParams, ProgramConfig};
+use crate::MarketError;
+
+pub fn _update_config(
+    ctx: Context<UpdateConfig>,
+    params: ConfigParams,
+) -> Result<()> {
+    //markets snapshot what they need at initialization, so updates only affect new markets
+    ctx.accounts.config.apply(&params)
+}
+
+#[derive(Accounts)]
+pub struct UpdateConfig<'info> {
+    #[account(
+        mut,
+        seeds = [CONFIG_SEED.as_bytes()],
+        bump = config.bump,
+        has_one = admin @ MarketError::UnauthorizedUser,
+    )]
+    pub config: Account<'info, ProgramConfig>,
+
+    pub admin: Signer<'info>,
+}
@@ -0,0 +1,143 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{close_account, CloseAccount, Mint, TokenAccount, TokenInterface, transfer_checked, TransferChecked};
+
+use crate::constants::*;
+use crate::states::*;
+use crate::MarketError;
+use crate::utils::hash_to_bytes;
+
+
+pub fn _void_market(
+    ctx: Context<VoidMarket>,
+) -> Result<()> {
+    let market = &mut ctx.accounts.market;
+    let clock = Clock::get()?;
+
+    //gives the resolvers the whole grace period before the market is given up on
+    require_gt!(
+        clock.unix_timestamp,
+        market.void_deadline().ok_or(MarketError::MarketDurationOverflow)?,
+        MarketError::VoidGracePeriodNotOver
+    );
+    let bonded = matches!(
+        market.status_at(clock.unix_timestamp),
+        MarketStatus::Proposed | MarketStatus::Disputed
+    );
+    market.transition(MarketStatus::Voided, clock.unix_timestamp)?;
+
+    if market.open_bets == 0 {
+        market.transition(MarketStatus::Settled, clock.unix_timestamp)?;
+    }
+
+    if bonded {
+        refund_bonds(&ctx)?;
+    }
+
+    Ok(())
+}
+
+//nobody was proven right, so the proposer and the disputer each get their own bond back
+fn refund_bonds(ctx: &Context<VoidMarket>) -> Result<()> {
+    let market = &ctx.accounts.market;
+    let bond_vault = ctx.accounts.bond_vault.as_ref().ok_or(MarketError::MissingBondAccounts)?;
+    let proposer = ctx.accounts.proposer.as_ref().ok_or(MarketError::MissingBondAccounts)?;
+    let proposer_ata = ctx.accounts.proposer_ata.as_ref().ok_or(MarketError::MissingBondAccounts)?;
+    let mint = ctx.accounts.mint.as_ref().ok_or(MarketError::MissingBondAccounts)?;
+    let token_program = ctx.accounts.token_program.as_ref().ok_or(MarketError::MissingBondAccounts)?;
+
+    require!(market.proposer == Some(proposer.key()), MarketError::WrongBondRecipient);
+    require!(market.proposer == Some(proposer_ata.owner), MarketError::WrongBondRecipient);
+
+    let mut refunds = vec![proposer_ata];
+    if let Some(disputer) = market.disputer {
+        let disputer_ata = ctx.accounts.disputer_ata.as_ref().ok_or(MarketError::MissingBondAccounts)?;
+        require_keys_eq!(disputer_ata.owner, disputer, MarketError::WrongBondRecipient);
+        refunds.push(disputer_ata);
+    }
+
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        market.creator.as_ref(),
+        &hash_to_bytes(&market.feed_id),
+        &market.target_price.to_le_bytes(),
+        &market.market_duration.to_le_bytes(),
+        &[market.bump],
+    ]];
+
+    for recipient_ata in refunds {
+        transfer_checked(
+            CpiContext::new_with_signer(
+                token_program.to_account_info(),
+                TransferChecked {
+                    from: bond_vault.to_account_info(),
+                    mint: mint.to_account_info(),
+                    to: recipient_ata.to_account_info(),
+                    authority: market.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            market.bond_amount,
+            mint.decimals,
+        )?;
+    }
+
+    close_account(CpiContext::new_with_signer(
+        token_program.to_account_info(),
+        CloseAccount {
+            account: bond_vault.to_account_info(),
+            destination: proposer.to_account_info(),
+            authority: market.to_account_info(),
+        },
+        signer_seeds,
+    ))
+}
+
+#[derive(Accounts)]
+pub struct VoidMarket<'info> {
+    #[account(
+        mut,
+        seeds = [
+            market.creator.key().as_ref(), 
+            &hash_to_bytes(&market.feed_id),
+            &market.target_price.to_le_bytes(), 
+            &market.market_duration.to_le_bytes(),
+        ],
+        bump = market.bump,
+    )]
+    pub market: Box<Account<'info, Market>>,
+
+    //only needed to refund the bonds of a proposed or disputed market
+    #[account(
+        mut,
+        token::mint = market.mint,
+        token::authority = market,
+        seeds = [
+            BOND_VAULT_SEED.as_bytes(),
+            market.key().as_ref(),
+        ],
+        bump = market.bond_vault_bump,
+    )]
+    pub bond_vault: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// CHECK: checked against market.proposer, receives the bond vault rent
+    #[account(mut)]
+    pub proposer: Option<UncheckedAccount<'info>>,
+
+    #[account(
+        mut,
+        token::mint = market.mint,
+    )]
+    pub proposer_ata: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        token::mint = market.mint,
+    )]
+    pub disputer_ata: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(address = market.mint)]
+    pub mint: Option<InterfaceAccount<'info, Mint>>,
+
+    //anyone can void a market that nobody managed to resolve
+    pub caller: Signer<'info>,
+    pub token_program: Option<Interface<'info, TokenInterface>>,
+}
@@ -0,0 +1,95 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::*;
+
+use crate::constants::*;
+use crate::states::{Market, Treasury};
+use crate::MarketError;
+use crate::utils::hash_to_bytes;
+
+pub fn _withdraw_creator_fees(
+    ctx: Context<WithdrawCreatorFees>,
+) -> Result<()> {
+    let market = &mut ctx.accounts.market;
+    let treasury = &ctx.accounts.treasury;
+    let amount = market.creator_fees;
+    require_gt!(amount, 0, MarketError::NoFeesToWithdraw);
+
+    transfer_checked(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            TransferChecked {
+                from: ctx.accounts.treasury_vault.to_account_info(),
+                mint: ctx.accounts.mint.to_account_info(),
+                to: ctx.accounts.creator_ata.to_account_info(),
+                authority: treasury.to_account_info(),
+            },
+            &[&[
+                TREASURY_SEED.as_bytes(),
+                treasury.mint.as_ref(),
+                &[treasury.bump],
+            ]],
+        ),
+        amount,
+        ctx.accounts.mint.decimals,
+    )?;
+
+    market.creator_fees = 0;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct WithdrawCreatorFees<'info> {
+    #[account(
+        mut,
+        seeds = [
+            market.creator.key().as_ref(), 
+            &hash_to_bytes(&market.feed_id),
+            &market.target_price.to_le_bytes(), 
+            &market.market_duration.to_le_bytes(),
+        ],
+        bump = market.bump,
+        has_one = creator @ MarketError::UnauthorizedUser,
+    )]
+    pub market: Account<'info, Market>,
+
+    #[account(
+        seeds = [
+            TREASURY_SEED.as_bytes(),
+            market.mint.as_ref(),
+        ],
+        bump = treasury.bump,
+    )]
+    pub treasury: Account<'info, Treasury>,
+
+    #[account(
+        mut,
+        token::mint = market.mint,
+        token::authority = treasury,
+        token::token_program = token_program,
+        seeds = [
+            TREASURY_VAULT_SEED.as_bytes(),
+            market.mint.as_ref(),
+        ],
+        bump = treasury.vault_bump,
+    )]
+    pub treasury_vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        associated_token::mint = market.mint,
+        associated_token::authority = creator,
+        associated_token::token_program = token_program,
+    )]
+    pub creator_ata: InterfaceAccount<'info, TokenAccount>,
+
+    pub creator: Signer<'info>,
+
+    #[account(
+        address = market.mint,
+        mint::token_program = token_program,
+    )]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
@@ -3,12 +3,16 @@ use anchor_lang::prelude::*;
 pub mod constants;
 pub mod error;
 pub mod instructions;
+pub mod math;
+pub mod oracle;
 pub mod state;
 pub mod utils;
 
 pub use constants::*;
 pub use error::*;
 pub use instructions::*;
+pub use math::*;
+pub use oracle::*;
 pub use state::*;
 pub use utils::*;
 
@@ -18,13 +22,55 @@ declare_id!("26Ub21yKBNNrdKC9PZNPxtmjPqEd4phgveuUvesDZkqx");
 pub mod prediction_market {
     use super::*;
 
+    pub fn initialize_config(ctx: Context<InitializeConfig>, params: ConfigParams) -> Result<()> {
+        _initialize_config(ctx, params)
+    }
+
+    pub fn update_config(ctx: Context<UpdateConfig>, params: ConfigParams) -> Result<()> {
+        _update_config(ctx, params)
+    }
+
+    pub fn propose_admin(ctx: Context<ProposeAdmin>, new_admin: Pubkey) -> Result<()> {
+        _propose_admin(ctx, new_admin)
+    }
+
+    pub fn accept_admin(ctx: Context<AcceptAdmin>) -> Result<()> {
+        _accept_admin(ctx)
+    }
+
+    pub fn add_allowed_mint(
+        ctx: Context<AddAllowedMint>,
+        min_bet: u64,
+        max_bet: u64,
+        seed_liquidity_amount: u64,
+    ) -> Result<()> {
+        _add_allowed_mint(ctx, min_bet, max_bet, seed_liquidity_amount)
+    }
+
+    pub fn remove_allowed_mint(ctx: Context<RemoveAllowedMint>) -> Result<()> {
+        _remove_allowed_mint(ctx)
+    }
+
+    pub fn initialize_treasury(ctx: Context<InitializeTreasury>) -> Result<()> {
+        _initialize_treasury(ctx)
+    }
+
+    pub fn withdraw_protocol_fees(ctx: Context<WithdrawProtocolFees>) -> Result<()> {
+        _withdraw_protocol_fees(ctx)
+    }
+
+    pub fn withdraw_creator_fees(ctx: Context<WithdrawCreatorFees>) -> Result<()> {
+        _withdraw_creator_fees(ctx)
+    }
+
     pub fn initialize_market(
         ctx: Context<InitializeMarket>,
         taget_price: u64,
         feed_id: String, // from https://pyth.network/developers/price-feed-ids#solana-stables
         market_duration: u64,
+        params: ResolutionParams,
     ) -> Result<()> {
-        _initialize_market(ctx, taget_price, feed_id, market_duration)
+        _initialize_market(ctx, taget_price, feed_id, market_duration, params)
     }
 
     pub fn initialize_pools(ctx: Context<InitializePools>) -> Result<()> {
@@ -47,7 +93,119 @@ pub mod prediction_market {
         _place_bet(ctx, bet_amount, bet_direction)
     }
 
+    pub fn buy_shares(
+        ctx: Context<BuyShares>,
+        bet_amount: u64,
+        bet_direction: Direction,
+        min_shares_out: u64,
+    ) -> Result<()> {
+        _buy_shares(ctx, bet_amount, bet_direction, min_shares_out)
+    }
+
+    pub fn initialize_outcome_mints(ctx: Context<InitializeOutcomeMints>) -> Result<()> {
+        _initialize_outcome_mints(ctx)
+    }
+
+    pub fn buy_outcome_tokens(
+        ctx: Context<BuyOutcomeTokens>,
+        bet_amount: u64,
+        bet_direction: Direction,
+        min_shares_out: u64,
+    ) -> Result<()> {
+        _buy_outcome_tokens(ctx, bet_amount, bet_direction, min_shares_out)
+    }
+
+    pub fn redeem(ctx: Context<Redeem>, amount: u64, direction: Direction) -> Result<()> {
+        _redeem(ctx, amount, direction)
+    }
+
+    pub fn mint_complete_set(ctx: Context<MintCompleteSet>, amount: u64) -> Result<()> {
+        _mint_complete_set(ctx, amount)
+    }
+
+    pub fn merge_complete_set(ctx: Context<MergeCompleteSet>, amount: u64) -> Result<()> {
+        _merge_complete_set(ctx, amount)
+    }
+
+    pub fn resolve_market(ctx: Context<ResolveMarket>) -> Result<()> {
+        _resolve_market(ctx)
+    }
+
+    pub fn record_price_sample(ctx: Context<RecordPriceSample>) -> Result<()> {
+        _record_price_sample(ctx)
+    }
+
+    pub fn resolve_market_twap(ctx: Context<ResolveMarketTwap>) -> Result<()> {
+        _resolve_market_twap(ctx)
+    }
+
+    pub fn propose_outcome(ctx: Context<ProposeOutcome>, outcome: Outcome) -> Result<()> {
+        _propose_outcome(ctx, outcome)
+    }
+
+    pub fn approve_outcome(ctx: Context<ApproveOutcome>, outcome: Outcome) -> Result<()> {
+        _approve_outcome(ctx, outcome)
+    }
+
+    pub fn propose_bonded_outcome(ctx: Context<ProposeBondedOutcome>, outcome: Outcome) -> Result<()> {
+        _propose_bonded_outcome(ctx, outcome)
+    }
+
+    pub fn dispute_outcome(ctx: Context<DisputeOutcome>) -> Result<()> {
+        _dispute_outcome(ctx)
+    }
+
+    pub fn settle_proposal(ctx: Context<SettleProposal>) -> Result<()> {
+        _settle_proposal(ctx)
+    }
+
+    pub fn rule_dispute(ctx: Context<RuleDispute>, outcome: Outcome) -> Result<()> {
+        _rule_dispute(ctx, outcome)
+    }
+
+    pub fn sell_bet(ctx: Context<SellBet>) -> Result<()> {
+        _sell_bet(ctx)
+    }
+
     pub fn claim_bet(ctx: Context<ClaimBet>) -> Result<()> {
         _claim_bet(ctx)
     }
+
+    pub fn void_market(ctx: Context<VoidMarket>) -> Result<()> {
+        _void_market(ctx)
+    }
+
+    pub fn refund_bet(ctx: Context<RefundBet>) -> Result<()> {
+        _refund_bet(ctx)
+    }
+
+    pub fn claim_position(ctx: Context<ClaimPosition>) -> Result<()> {
+        _claim_position(ctx)
+    }
+
+    pub fn initialize_outcome_pool(ctx: Context<InitializeOutcomePool>, outcome_index: u8) -> Result<()> {
+        _initialize_outcome_pool(ctx, outcome_index)
+    }
+
+    pub fn place_outcome_bet(
+        ctx: Context<PlaceOutcomeBet>,
+        bet_amount: u64,
+        outcome_index: u8,
+    ) -> Result<()> {
+        _place_outcome_bet(ctx, bet_amount, outcome_index)
+    }
+
+    pub fn resolve_categorical_market<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ResolveCategoricalMarket<'info>>,
+    ) -> Result<()> {
+        _resolve_categorical_market(ctx)
+    }
+
+    pub fn claim_outcome_bet(ctx: Context<ClaimOutcomeBet>) -> Result<()> {
+        _claim_outcome_bet(ctx)
+    }
+
+    pub fn close_outcome_pool(ctx: Context<CloseOutcomePool>, outcome_index: u8) -> Result<()> {
+        _close_outcome_pool(ctx, outcome_index)
+    }
 }
@@ -0,0 +1,78 @@
+use anchor_lang::prelude::*;
+
+use crate::MarketError;
+
+//result of buying one side of a constant-product market
+#[derive(Debug, PartialEq, Eq)]
+pub struct CpmmTrade {
+    pub shares_out: u64,
+    pub own_reserve: u64,   // reserve of the bought side after the trade
+    pub other_reserve: u64, // reserve of the other side after the trade
+}
+
+//`amount` of collateral mints as many complete sets into the reserves, then shares of the bought
+//side are taken out until own * other is back to what it was before the trade. The reserve left
+//behind is rounded up so the product never shrinks and every share stays backed by collateral.
+pub fn cpmm_buy(amount: u64, own_reserve: u64, other_reserve: u64) -> Result<CpmmTrade> {
+    require!(own_reserve > 0 && other_reserve > 0, MarketError::EmptyReserves);
+
+    let own_with_sets = (own_reserve as u128)
+        .checked_add(amount as u128)
+        .ok_or(MarketError::PayoutOverflow)?;
+    let other_with_sets = (other_reserve as u128)
+        .checked_add(amount as u128)
+        .ok_or(MarketError::PayoutOverflow)?;
+
+    let product = own_reserve as u128 * other_reserve as u128;
+    let own_after = product.div_ceil(other_with_sets);
+
+    Ok(CpmmTrade {
+        shares_out: u64::try_from(own_with_sets - own_after).map_err(|_| MarketError::PayoutOverflow)?,
+        own_reserve: u64::try_from(own_after).map_err(|_| MarketError::PayoutOverflow)?,
+        other_reserve: u64::try_from(other_with_sets).map_err(|_| MarketError::PayoutOverflow)?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn balanced_reserves_pay_more_than_one_share_per_unit() {
+        let trade = cpmm_buy(100, 1_000, 1_000).unwrap();
+        // 1100 - ceil(1_000_000 / 1100) = 1100 - 910
+        assert_eq!(trade.shares_out, 190);
+        assert_eq!(trade.own_reserve, 910);
+        assert_eq!(trade.other_reserve, 1_100);
+    }
+
+    #[test]
+    fn product_never_shrinks() {
+        for (amount, own, other) in [(1, 3, 7), (7, 1_000, 13), (999_999, 5, 5), (1, u32::MAX as u64, 1)] {
+            let trade = cpmm_buy(amount, own, other).unwrap();
+            assert!(
+                trade.own_reserve as u128 * trade.other_reserve as u128 >= own as u128 * other as u128
+            );
+        }
+    }
+
+    #[test]
+    fn shares_out_is_at_least_the_amount_paid() {
+        for (amount, own, other) in [(1, 1, 1), (10, 1_000_000, 1), (1_000_000, 1, 1_000_000)] {
+            assert!(cpmm_buy(amount, own, other).unwrap().shares_out >= amount);
+        }
+    }
+
+    #[test]
+    fn buying_a_side_makes_it_more_expensive() {
+        let first = cpmm_buy(100, 1_000, 1_000).unwrap();
+        let second = cpmm_buy(100, first.own_reserve, first.other_reserve).unwrap();
+        assert!(second.shares_out < first.shares_out);
+    }
+
+    #[test]
+    fn rejects_empty_reserves() {
+        assert_eq!(cpmm_buy(100, 0, 1_000), Err(MarketError::EmptyReserves.into()));
+        assert_eq!(cpmm_buy(100, 1_000, 0), Err(MarketError::EmptyReserves.into()));
+    }
+}
@@ -0,0 +1,14 @@
+use anchor_lang::prelude::*;
+
+use crate::constants::BPS_DENOMINATOR;
+use crate::MarketError;
+
+//fee taken out of `amount`, rounded down in favour of the bettor
+pub fn fee_amount(amount: u64, fee_bps: u16) -> Result<u64> {
+    let fee = (amount as u128)
+        .checked_mul(fee_bps as u128)
+        .ok_or(MarketError::PayoutOverflow)?
+        / BPS_DENOMINATOR as u128;
+
+    u64::try_from(fee).map_err(|_| MarketError::PayoutOverflow.into())
+}
@@ -0,0 +1,104 @@
+use anchor_lang::prelude::*;
+
+use crate::MarketError;
+
+//fixed-point numbers with 12 decimals, wide enough for token amounts times SCALE
+pub const SCALE: i128 = 1_000_000_000_000;
+pub const LN_2: i128 = 693_147_180_560;
+//e^-x is below one SCALE unit from here on
+const EXP_CUTOFF: i128 = 28 * SCALE;
+
+//e^-x for x >= 0. Only negative exponents are needed by the callers, which keeps every
+//intermediate value below SCALE and the series short enough for the compute budget.
+pub fn exp_neg(x: i128) -> Result<i128> {
+    require!(x >= 0, MarketError::FixedPointOutOfRange);
+    if x >= EXP_CUTOFF {
+        return Ok(0);
+    }
+
+    //e^-x = 2^-k * e^-r with r < ln 2
+    let k = x / LN_2;
+    let r = x - k * LN_2;
+
+    let mut term = SCALE;
+    let mut sum = SCALE;
+    let mut n = 1;
+    while term != 0 {
+        term = -term * r / (n * SCALE);
+        sum += term;
+        n += 1;
+    }
+
+    Ok(sum >> k)
+}
+
+//natural log of x > 0
+pub fn ln(x: i128) -> Result<i128> {
+    require!(x > 0, MarketError::FixedPointOutOfRange);
+
+    //ln x = k * ln 2 + ln y with y in [1, 2)
+    let mut y = x;
+    let mut k: i128 = 0;
+    while y >= 2 * SCALE {
+        y /= 2;
+        k += 1;
+    }
+    while y < SCALE {
+        y *= 2;
+        k -= 1;
+    }
+
+    //ln y = 2 * atanh(z) with z = (y - 1) / (y + 1) <= 1/3
+    let z = (y - SCALE) * SCALE / (y + SCALE);
+    let z_squared = z * z / SCALE;
+    let mut term = z;
+    let mut sum = 0;
+    let mut n = 1;
+    while term != 0 {
+        sum += term / n;
+        term = term * z_squared / SCALE;
+        n += 2;
+    }
+
+    Ok(k * LN_2 + 2 * sum)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(actual: i128, expected: i128) {
+        assert!((actual - expected).abs() <= 10, "{actual} != {expected}");
+    }
+
+    #[test]
+    fn exp_neg_matches_known_values() {
+        assert_eq!(exp_neg(0).unwrap(), SCALE);
+        assert_close(exp_neg(LN_2).unwrap(), SCALE / 2);
+        assert_close(exp_neg(SCALE).unwrap(), 367_879_441_171);
+        assert_close(exp_neg(10 * SCALE).unwrap(), 45_399_930);
+        assert_eq!(exp_neg(EXP_CUTOFF).unwrap(), 0);
+    }
+
+    #[test]
+    fn ln_matches_known_values() {
+        assert_eq!(ln(SCALE).unwrap(), 0);
+        assert_close(ln(2 * SCALE).unwrap(), LN_2);
+        assert_close(ln(SCALE / 2).unwrap(), -LN_2);
+        assert_close(ln(2_718_281_828_459).unwrap(), SCALE);
+        assert_close(ln(1).unwrap(), -27_631_021_115_928);
+    }
+
+    #[test]
+    fn ln_inverts_exp_neg() {
+        for x in [1, SCALE / 3, SCALE, 7 * SCALE] {
+            assert!((ln(exp_neg(x).unwrap()).unwrap() + x).abs() <= x / 1_000_000 + 100);
+        }
+    }
+
+    #[test]
+    fn rejects_out_of_range_inputs() {
+        assert_eq!(exp_neg(-1), Err(MarketError::FixedPointOutOfRange.into()));
+        assert_eq!(ln(0), Err(MarketError::FixedPointOutOfRange.into()));
+    }
+}
@@ -0,0 +1,141 @@
+use anchor_lang::prelude::*;
+
+use crate::math::fixed_point::{exp_neg, ln, SCALE};
+use crate::MarketError;
+
+//result of buying one side of an lmsr market
+#[derive(Debug, PartialEq, Eq)]
+pub struct LmsrTrade {
+    pub shares_out: u64,
+    pub own_shares: u64, // shares of the bought side outstanding after the trade
+}
+
+//C(q) = b * ln(e^(own / b) + e^(other / b)) in token units times SCALE, written as
+//max + b * ln(1 + e^(-|own - other| / b)) so no exponent is ever positive
+pub fn lmsr_cost(own_shares: u64, other_shares: u64, liquidity: u64) -> Result<i128> {
+    require!(liquidity > 0, MarketError::EmptyReserves);
+    let b = liquidity as i128;
+
+    let spread = (own_shares as i128 - other_shares as i128).abs() * SCALE / b;
+    let max = own_shares.max(other_shares) as i128 * SCALE;
+
+    Ok(max + b * ln(SCALE + exp_neg(spread)?)?)
+}
+
+//(price of own, price of other) in SCALE units, each is e^(q / b) over the sum of both
+pub fn lmsr_prices(own_shares: u64, other_shares: u64, liquidity: u64) -> Result<(u64, u64)> {
+    require!(liquidity > 0, MarketError::EmptyReserves);
+    let b = liquidity as i128;
+
+    let spread = (own_shares as i128 - other_shares as i128).abs() * SCALE / b;
+    let cheap_weight = exp_neg(spread)?;
+    let expensive = SCALE * SCALE / (SCALE + cheap_weight);
+    let cheap = cheap_weight * SCALE / (SCALE + cheap_weight);
+
+    let (own, other) = if own_shares >= other_shares {
+        (expensive, cheap)
+    } else {
+        (cheap, expensive)
+    };
+    Ok((own as u64, other as u64))
+}
+
+//shares of one side that `amount` buys, i.e. the largest d with C(own + d, other) - C(own, other)
+//<= amount. Solving C(own', other) = C + amount for own' gives
+//own' = T + b * ln(1 - e^(-(T - other) / b)) with T = C + amount > other, again only negative
+//exponents. The creator funds the difference between the winning shares and what was paid for
+//them, which is at most C(q) - max(q) <= b * ln(2) for two outcomes.
+pub fn lmsr_buy(amount: u64, own_shares: u64, other_shares: u64, liquidity: u64) -> Result<LmsrTrade> {
+    let b = liquidity as i128;
+    let target = lmsr_cost(own_shares, other_shares, liquidity)? + amount as i128 * SCALE;
+
+    let headroom = (target - other_shares as i128 * SCALE) / b;
+    let own_after = target + b * ln(SCALE - exp_neg(headroom)?)?;
+
+    //a share never costs more than 1, the max only absorbs fixed-point rounding
+    let shares_out = ((own_after - own_shares as i128 * SCALE) / SCALE).max(amount as i128);
+    let shares_out = u64::try_from(shares_out).map_err(|_| MarketError::PayoutOverflow)?;
+
+    Ok(LmsrTrade {
+        shares_out,
+        own_shares: own_shares.checked_add(shares_out).ok_or(MarketError::PayoutOverflow)?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::math::fixed_point::LN_2;
+
+    const B: u64 = 1_000_000_000;
+
+    #[test]
+    fn prices_always_sum_to_one() {
+        for (own, other) in [(0, 0), (1, 0), (B, 0), (0, 3 * B), (7 * B, 2 * B), (50 * B, 0), (123_456, 654_321)] {
+            let (own_price, other_price) = lmsr_prices(own, other, B).unwrap();
+            let sum = own_price as i128 + other_price as i128;
+            assert!((SCALE - sum).abs() <= 1, "{own} {other} sum {sum}");
+        }
+    }
+
+    #[test]
+    fn balanced_market_prices_each_side_at_one_half() {
+        assert_eq!(lmsr_prices(B, B, B).unwrap(), (SCALE as u64 / 2, SCALE as u64 / 2));
+    }
+
+    #[test]
+    fn empty_market_costs_b_ln_2() {
+        let cost = lmsr_cost(0, 0, B).unwrap();
+        assert!((cost - B as i128 * LN_2).abs() <= B as i128 * 10);
+    }
+
+    #[test]
+    fn cost_is_symmetric_and_increasing() {
+        assert_eq!(lmsr_cost(3 * B, B, B).unwrap(), lmsr_cost(B, 3 * B, B).unwrap());
+        assert!(lmsr_cost(B + 1, B, B).unwrap() > lmsr_cost(B, B, B).unwrap());
+        assert!(lmsr_cost(B, B + 1, B).unwrap() > lmsr_cost(B, B, B).unwrap());
+    }
+
+    #[test]
+    fn trade_costs_at_most_the_amount_paid() {
+        for (amount, own, other) in [(1_000_000, 0, 0), (5 * B, 0, 0), (1, 3 * B, 0), (B, 0, 10 * B)] {
+            let trade = lmsr_buy(amount, own, other, B).unwrap();
+            let paid = lmsr_cost(trade.own_shares, other, B).unwrap() - lmsr_cost(own, other, B).unwrap();
+            assert!(paid <= amount as i128 * SCALE + SCALE, "{amount} {own} {other}");
+            assert!(paid >= (amount as i128 - 1) * SCALE, "{amount} {own} {other}");
+            assert!(trade.shares_out >= amount);
+        }
+    }
+
+    #[test]
+    fn buying_a_side_raises_its_price() {
+        let (before, _) = lmsr_prices(0, 0, B).unwrap();
+        let trade = lmsr_buy(B, 0, 0, B).unwrap();
+        let (after, _) = lmsr_prices(trade.own_shares, 0, B).unwrap();
+        assert!(after > before);
+    }
+
+    #[test]
+    fn creator_loss_is_bounded_by_b_ln_2() {
+        let (mut higher, mut lower, mut collected) = (0u64, 0u64, 0i128);
+        for step in 0..40u64 {
+            let amount = (step % 7 + 1) * B / 3;
+            if step % 5 == 0 {
+                lower = lmsr_buy(amount, lower, higher, B).unwrap().own_shares;
+            } else {
+                higher = lmsr_buy(amount, higher, lower, B).unwrap().own_shares;
+            }
+            collected += amount as i128;
+
+            //whichever side wins, the payout beyond what was collected stays within b * ln 2
+            let worst_loss = higher.max(lower) as i128 - collected;
+            assert!(worst_loss * SCALE <= B as i128 * LN_2, "step {step}");
+        }
+    }
+
+    #[test]
+    fn rejects_zero_liquidity() {
+        assert_eq!(lmsr_buy(1, 0, 0, 0), Err(MarketError::EmptyReserves.into()));
+        assert_eq!(lmsr_prices(0, 0, 0), Err(MarketError::EmptyReserves.into()));
+    }
+}
@@ -0,0 +1,37 @@
+//median of the values, the two middle values are averaged (rounding down) for an even count
+pub fn median(values: &mut [u64]) -> Option<u64> {
+    if values.is_empty() {
+        return None;
+    }
+    values.sort_unstable();
+
+    let middle = values.len() / 2;
+    if values.len() % 2 == 1 {
+        Some(values[middle])
+    } else {
+        let (lower, upper) = (values[middle - 1], values[middle]);
+        Some(lower + (upper - lower) / 2)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn empty_has_no_median() {
+        assert_eq!(median(&mut []), None);
+    }
+
+    #[test]
+    fn odd_count_takes_the_middle_value() {
+        assert_eq!(median(&mut [7]), Some(7));
+        assert_eq!(median(&mut [140, 1, 139]), Some(139));
+    }
+
+    #[test]
+    fn even_count_averages_the_middle_values() {
+        assert_eq!(median(&mut [100, 141, 139, 1_000_000]), Some(140));
+        assert_eq!(median(&mut [u64::MAX, u64::MAX - 1]), Some(u64::MAX - 1));
+    }
+}
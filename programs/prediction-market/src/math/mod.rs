@@ -0,0 +1,26 @@
+pub mod parimutuel;
+pub use parimutuel::*;
+
+pub mod fees;
+pub use fees::*;
+
+pub mod price;
+pub use price::*;
+
+pub mod median;
+pub use median::*;
+
+pub mod twap;
+pub use twap::*;
+
+pub mod cpmm;
+pub use cpmm::*;
+
+pub mod fixed_point;
+pub use fixed_point::*;
+
+pub mod lmsr;
+pub use lmsr::*;
+
+pub mod scalar;
+pub use scalar::*;
@@ -0,0 +1,165 @@
+use anchor_lang::prelude::*;
+
+use crate::constants::ODDS_FIXED_POINT_MULTIPLIER;
+use crate::states::Direction;
+use crate::MarketError;
+
+//implied odds of a direction from the current pool sizes in ODDS_FIXED_POINT_MULTIPLIER units
+pub fn pool_odds(direction: &Direction, higher_pool_amount: u64, lower_pool_amount: u64) -> u64 {
+    match direction {
+        Direction::Higher => outcome_odds(higher_pool_amount, lower_pool_amount),
+        Direction::Lower => outcome_odds(lower_pool_amount, higher_pool_amount),
+    }
+}
+
+//implied odds of a pool against everything staked on the other outcomes
+pub fn outcome_odds(own: u64, other: u64) -> u64 {
+    if other == 0 {
+        return ODDS_FIXED_POINT_MULTIPLIER; // 1.0 in fixed-point representation
+    }
+
+    ((own as u128 * ODDS_FIXED_POINT_MULTIPLIER as u128) / other as u128)
+        .try_into()
+        .unwrap_or(u64::MAX)
+}
+
+//stake-weighted average of the odds already held and the odds of a new stake
+pub fn weighted_odds(held_amount: u64, held_odds: u64, amount: u64, odds: u64) -> Result<u64> {
+    let total_amount = (held_amount as u128)
+        .checked_add(amount as u128)
+        .ok_or(MarketError::PayoutOverflow)?;
+    if total_amount == 0 {
+        return Ok(odds);
+    }
+
+    let weighted = (held_amount as u128 * held_odds as u128)
+        .checked_add(amount as u128 * odds as u128)
+        .ok_or(MarketError::PayoutOverflow)?
+        / total_amount;
+
+    u64::try_from(weighted).map_err(|_| MarketError::PayoutOverflow.into())
+}
+
+//pro-rata share of the losing pool owed to a winning stake, rounded down so the sum over
+//all winners never exceeds `losing_total`
+pub fn parimutuel_winnings(stake: u64, winning_total: u64, losing_total: u64) -> Result<u64> {
+    require_gte!(winning_total, stake, MarketError::PayoutOverflow);
+
+    let winnings = (stake as u128)
+        .checked_mul(losing_total as u128)
+        .ok_or(MarketError::PayoutOverflow)?
+        .checked_div(winning_total as u128)
+        .ok_or(MarketError::PayoutOverflow)?;
+
+    u64::try_from(winnings).map_err(|_| MarketError::PayoutOverflow.into())
+}
+
+//value of a stake bought back before expiry, stake * p_now / p_entry where p = odds / (1 + odds)
+//is the implied probability of the direction. Capped at the stake so a bet can not be sold for
+//more than it put into the pool
+pub fn exit_value(stake: u64, entry_odds: u64, current_odds: u64) -> Result<u64> {
+    if current_odds >= entry_odds {
+        return Ok(stake);
+    }
+    let one = ODDS_FIXED_POINT_MULTIPLIER as u128;
+
+    let value = (stake as u128)
+        .checked_mul(current_odds as u128)
+        .ok_or(MarketError::PayoutOverflow)?
+        / (current_odds as u128 + one)
+        * (entry_odds as u128 + one)
+        / entry_odds as u128;
+
+    Ok(value.min(stake as u128) as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn odds_are_the_pool_ratio() {
+        assert_eq!(pool_odds(&Direction::Higher, 300, 100), 3 * ODDS_FIXED_POINT_MULTIPLIER);
+        assert_eq!(pool_odds(&Direction::Lower, 300, 100), ODDS_FIXED_POINT_MULTIPLIER / 3);
+    }
+
+    #[test]
+    fn odds_against_an_empty_pool_are_one() {
+        assert_eq!(pool_odds(&Direction::Higher, 300, 0), ODDS_FIXED_POINT_MULTIPLIER);
+    }
+
+    #[test]
+    fn odds_do_not_overflow_on_large_pools() {
+        assert_eq!(outcome_odds(u64::MAX, u64::MAX), ODDS_FIXED_POINT_MULTIPLIER);
+        assert_eq!(outcome_odds(u64::MAX, 1), u64::MAX);
+    }
+
+    #[test]
+    fn winnings_are_a_pro_rata_share_of_the_losing_pool() {
+        assert_eq!(parimutuel_winnings(100, 400, 1_000).unwrap(), 250);
+        assert_eq!(parimutuel_winnings(400, 400, 1_000).unwrap(), 1_000);
+    }
+
+    #[test]
+    fn winnings_round_down_so_the_losing_pool_is_never_overdrawn() {
+        let stakes = [1, 1, 1];
+        let total: u64 = stakes
+            .iter()
+            .map(|stake| parimutuel_winnings(*stake, 3, 10).unwrap())
+            .sum();
+        assert_eq!(total, 9);
+    }
+
+    #[test]
+    fn winnings_do_not_overflow_on_large_pools() {
+        assert_eq!(parimutuel_winnings(u64::MAX, u64::MAX, u64::MAX).unwrap(), u64::MAX);
+    }
+
+    #[test]
+    fn rejects_a_stake_larger_than_its_pool() {
+        assert_eq!(parimutuel_winnings(101, 100, 1_000), Err(MarketError::PayoutOverflow.into()));
+    }
+
+    #[test]
+    fn categorical_winners_are_paid_from_the_folded_pool() {
+        //the middle bucket wins and the other two are folded into its pool at resolution
+        let totals = [5_000u64, 3_000, 7_001];
+        let folded_pool: u64 = totals.iter().sum();
+        let losing_total = folded_pool - totals[1];
+
+        let stakes = [1_000u64, 1_999, 1];
+        let paid: u64 = stakes
+            .iter()
+            .map(|stake| stake + parimutuel_winnings(*stake, totals[1], losing_total).unwrap())
+            .sum();
+        assert!(paid <= folded_pool);
+        //only rounding dust is left behind
+        assert!(folded_pool - paid < stakes.len() as u64);
+    }
+
+    #[test]
+    fn exit_value_follows_the_implied_probability() {
+        let one = ODDS_FIXED_POINT_MULTIPLIER;
+        //bought at 1:1 (p = 1/2), now 1:4 (p = 1/5)
+        assert_eq!(exit_value(1_000, one, one / 4).unwrap(), 400);
+        assert_eq!(exit_value(1_000, one, one).unwrap(), 1_000);
+    }
+
+    #[test]
+    fn exit_value_is_capped_at_the_stake() {
+        let one = ODDS_FIXED_POINT_MULTIPLIER;
+        assert_eq!(exit_value(1_000, one, 3 * one).unwrap(), 1_000);
+        assert_eq!(exit_value(1_000, 0, 0).unwrap(), 1_000);
+    }
+
+    #[test]
+    fn exit_value_does_not_overflow_on_large_stakes() {
+        assert!(exit_value(u64::MAX, u64::MAX, u64::MAX / 2).unwrap() < u64::MAX);
+    }
+
+    #[test]
+    fn weighted_odds_average_by_stake() {
+        assert_eq!(weighted_odds(100, 2_000_000, 300, 1_000_000).unwrap(), 1_250_000);
+        assert_eq!(weighted_odds(0, 0, 0, 1_000_000).unwrap(), 1_000_000);
+    }
+}
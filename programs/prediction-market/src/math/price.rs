@@ -0,0 +1,79 @@
+use anchor_lang::prelude::*;
+
+use crate::MarketError;
+
+//rescales an oracle price (price * 10^exponent) to the market's scale (10^target_exponent),
+//rounding down when the oracle is more precise than the market
+pub fn normalize_price(price: i64, exponent: i32, target_exponent: i32) -> Result<u64> {
+    require_gt!(price, 0, MarketError::NonPositivePrice);
+
+    let shift = exponent
+        .checked_sub(target_exponent)
+        .ok_or(MarketError::PriceAdjustmentOverflow)?;
+    let scale = 10_i128
+        .checked_pow(shift.unsigned_abs())
+        .ok_or(MarketError::PriceAdjustmentOverflow)?;
+
+    let normalized = if shift >= 0 {
+        (price as i128).checked_mul(scale)
+    } else {
+        (price as i128).checked_div(scale)
+    }
+    .ok_or(MarketError::PriceAdjustmentOverflow)?;
+
+    u64::try_from(normalized).map_err(|_| MarketError::PriceAdjustmentOverflow.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn same_exponent_is_unchanged() {
+        assert_eq!(normalize_price(6_543_210, -8, -8), Ok(6_543_210));
+        assert_eq!(normalize_price(140, 0, 0), Ok(140));
+    }
+
+    #[test]
+    fn more_precise_oracle_is_scaled_down() {
+        //$65_432.10 with 8 decimals to a market quoted in whole dollars
+        assert_eq!(normalize_price(6_543_210_000_000, -8, 0), Ok(65_432));
+        //to cents
+        assert_eq!(normalize_price(6_543_210_000_000, -8, -2), Ok(6_543_210));
+    }
+
+    #[test]
+    fn less_precise_oracle_is_scaled_up() {
+        assert_eq!(normalize_price(140, 0, -6), Ok(140_000_000));
+        assert_eq!(normalize_price(14, 1, 0), Ok(140));
+        assert_eq!(normalize_price(65_432, -2, -8), Ok(65_432_000_000));
+    }
+
+    #[test]
+    fn precision_below_the_market_scale_rounds_down() {
+        assert_eq!(normalize_price(999_999, -6, 0), Ok(0));
+        assert_eq!(normalize_price(1_999_999, -6, 0), Ok(1));
+    }
+
+    #[test]
+    fn wide_exponent_gaps() {
+        assert_eq!(normalize_price(i64::MAX, -18, 0), Ok(9));
+        assert_eq!(normalize_price(1, -38, 0), Ok(0));
+        assert_eq!(normalize_price(1, 0, -19), Ok(10_000_000_000_000_000_000));
+    }
+
+    #[test]
+    fn rejects_non_positive_prices() {
+        assert_eq!(normalize_price(0, -8, -8), Err(MarketError::NonPositivePrice.into()));
+        assert_eq!(normalize_price(-1, -8, -8), Err(MarketError::NonPositivePrice.into()));
+        assert_eq!(normalize_price(i64::MIN, 0, 0), Err(MarketError::NonPositivePrice.into()));
+    }
+
+    #[test]
+    fn rejects_overflow() {
+        assert_eq!(normalize_price(2, 0, -19), Err(MarketError::PriceAdjustmentOverflow.into()));
+        assert_eq!(normalize_price(i64::MAX, 0, -2), Err(MarketError::PriceAdjustmentOverflow.into()));
+        assert_eq!(normalize_price(1, 0, -39), Err(MarketError::PriceAdjustmentOverflow.into()));
+        assert_eq!(normalize_price(1, i32::MAX, i32::MIN), Err(MarketError::PriceAdjustmentOverflow.into()));
+    }
+}
@@ -0,0 +1,77 @@
+use anchor_lang::prelude::*;
+
+use crate::states::Direction;
+use crate::MarketError;
+
+//collateral paid for `amount` long (higher) or short (lower) tokens of a scalar market. A long token
+//pays the share of the range the final price landed above lower_bound and a short token the share
+//left below upper_bound, with the price clamped to the range. Both round down, so one of each never
+//pays out more than the unit of collateral their complete set was minted with
+pub fn scalar_payout(
+    amount: u64,
+    direction: &Direction,
+    final_price: u64,
+    lower_bound: u64,
+    upper_bound: u64,
+) -> Result<u64> {
+    require_gt!(upper_bound, lower_bound, MarketError::InvalidScalarBounds);
+
+    let price = final_price.clamp(lower_bound, upper_bound);
+    let share = match direction {
+        Direction::Higher => price - lower_bound,
+        Direction::Lower => upper_bound - price,
+    };
+
+    let payout = (amount as u128)
+        .checked_mul(share as u128)
+        .ok_or(MarketError::PayoutOverflow)?
+        / (upper_bound - lower_bound) as u128;
+
+    u64::try_from(payout).map_err(|_| MarketError::PayoutOverflow.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn payout_is_linear_between_the_bounds() {
+        assert_eq!(scalar_payout(1_000, &Direction::Higher, 125, 100, 200).unwrap(), 250);
+        assert_eq!(scalar_payout(1_000, &Direction::Lower, 125, 100, 200).unwrap(), 750);
+    }
+
+    #[test]
+    fn price_outside_the_range_pays_one_side_in_full() {
+        assert_eq!(scalar_payout(1_000, &Direction::Higher, 250, 100, 200).unwrap(), 1_000);
+        assert_eq!(scalar_payout(1_000, &Direction::Lower, 250, 100, 200).unwrap(), 0);
+        assert_eq!(scalar_payout(1_000, &Direction::Higher, 50, 100, 200).unwrap(), 0);
+        assert_eq!(scalar_payout(1_000, &Direction::Lower, 50, 100, 200).unwrap(), 1_000);
+    }
+
+    #[test]
+    fn complete_set_never_pays_more_than_its_collateral() {
+        for price in [100, 101, 133, 150, 199, 200] {
+            let long = scalar_payout(7, &Direction::Higher, price, 100, 200).unwrap();
+            let short = scalar_payout(7, &Direction::Lower, price, 100, 200).unwrap();
+            assert!(long + short <= 7);
+            //only rounding dust is left behind
+            assert!(7 - (long + short) <= 1);
+        }
+    }
+
+    #[test]
+    fn payout_does_not_overflow_on_large_amounts() {
+        assert_eq!(
+            scalar_payout(u64::MAX, &Direction::Higher, u64::MAX, 0, u64::MAX).unwrap(),
+            u64::MAX
+        );
+    }
+
+    #[test]
+    fn rejects_an_empty_range() {
+        assert_eq!(
+            scalar_payout(1_000, &Direction::Higher, 100, 100, 100),
+            Err(MarketError::InvalidScalarBounds.into())
+        );
+    }
+}
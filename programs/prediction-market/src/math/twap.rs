@@ -0,0 +1,101 @@
+use anchor_lang::prelude::*;
+
+use crate::MarketError;
+
+//average over [start_time, end_time] of chronologically ordered (publish_time, value) points, each
+//value holding until the next point and the last one until `end_time`. The first value also stands
+//for the time between `start_time` and its own publish time, so the points have to cover the whole
+//window: no stretch without a point, at either end included, may be longer than `max_gap`
+pub fn time_weighted_average(points: &[(i64, u64)], start_time: i64, end_time: i64, max_gap: i64) -> Result<u64> {
+    require!(!points.is_empty(), MarketError::InsufficientPriceSamples);
+    let total_time = end_time
+        .checked_sub(start_time)
+        .filter(|total_time| *total_time > 0)
+        .ok_or(MarketError::InsufficientPriceSamples)? as u128;
+
+    let mut weighted_sum: u128 = 0;
+    let mut previous_time = start_time;
+    for (i, (publish_time, value)) in points.iter().enumerate() {
+        let until = points.get(i + 1).map_or(end_time, |(next_time, _)| *next_time);
+        require_covered(previous_time, *publish_time, max_gap)?;
+        require_covered(*publish_time, until, max_gap)?;
+
+        let from = if i == 0 { start_time } else { *publish_time };
+        weighted_sum = weighted_sum
+            .checked_add((*value as u128) * (until - from) as u128)
+            .ok_or(MarketError::PriceAdjustmentOverflow)?;
+        previous_time = *publish_time;
+    }
+
+    u64::try_from(weighted_sum / total_time).map_err(|_| MarketError::PriceAdjustmentOverflow.into())
+}
+
+//a point out of order, or too long after the one before it, leaves part of the window uncovered
+fn require_covered(from: i64, until: i64, max_gap: i64) -> Result<()> {
+    until
+        .checked_sub(from)
+        .filter(|gap| (0..=max_gap).contains(gap))
+        .map(|_| ())
+        .ok_or(MarketError::InsufficientPriceSamples.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn single_point_is_its_own_average() {
+        assert_eq!(time_weighted_average(&[(100, 140)], 100, 160, 60), Ok(140));
+    }
+
+    #[test]
+    fn values_are_weighted_by_how_long_they_held() {
+        //140 for 30s, 200 for 10s
+        assert_eq!(time_weighted_average(&[(100, 140), (130, 200)], 100, 140, 30), Ok(155));
+        //a spike right before the end barely moves the average
+        assert_eq!(time_weighted_average(&[(0, 100), (99, 10_000)], 0, 100, 99), Ok(199));
+    }
+
+    #[test]
+    fn first_point_stands_for_the_start_of_the_window() {
+        //140 from the window start at 100 until 130, then 200 for 10s
+        assert_eq!(time_weighted_average(&[(110, 140), (130, 200)], 100, 140, 20), Ok(155));
+    }
+
+    #[test]
+    fn rejects_windows_the_points_do_not_cover() {
+        //nothing in the first 30s
+        assert_eq!(
+            time_weighted_average(&[(130, 140)], 100, 140, 20),
+            Err(MarketError::InsufficientPriceSamples.into())
+        );
+        //nothing between 100 and 160
+        assert_eq!(
+            time_weighted_average(&[(100, 140), (160, 150)], 100, 170, 50),
+            Err(MarketError::InsufficientPriceSamples.into())
+        );
+        //nothing in the last 60s
+        assert_eq!(
+            time_weighted_average(&[(100, 140)], 100, 160, 59),
+            Err(MarketError::InsufficientPriceSamples.into())
+        );
+    }
+
+    #[test]
+    fn rejects_empty_or_zero_length_windows() {
+        assert_eq!(time_weighted_average(&[], 100, 160, 60), Err(MarketError::InsufficientPriceSamples.into()));
+        assert_eq!(time_weighted_average(&[(100, 140)], 100, 100, 60), Err(MarketError::InsufficientPriceSamples.into()));
+    }
+
+    #[test]
+    fn rejects_points_outside_the_window() {
+        assert_eq!(
+            time_weighted_average(&[(100, 140), (120, 150)], 100, 110, 60),
+            Err(MarketError::InsufficientPriceSamples.into())
+        );
+        assert_eq!(
+            time_weighted_average(&[(90, 140)], 100, 110, 60),
+            Err(MarketError::InsufficientPriceSamples.into())
+        );
+    }
+}
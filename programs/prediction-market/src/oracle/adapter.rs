@@ -0,0 +1,25 @@
+use anchor_lang::prelude::*;
+
+use crate::states::OracleSource;
+use crate::oracle::{PythAdapter, SwitchboardAdapter};
+
+//a price as the oracle reported it, price and conf are scaled by 10^exponent
+pub struct OraclePrice {
+    pub price: i64,
+    pub conf: u64,
+    pub exponent: i32,
+    pub publish_time: i64,
+    pub prev_publish_time: Option<i64>, // publish time of the update before this one, if the feed reports it
+}
+
+pub trait OracleAdapter {
+    //reads the latest price held by the feed account, after checking the account really is `feed_id`
+    fn read_price(feed: &AccountInfo, feed_id: &[u8; 32]) -> Result<OraclePrice>;
+}
+
+pub fn read_oracle_price(source: OracleSource, feed: &AccountInfo, feed_id: &[u8; 32]) -> Result<OraclePrice> {
+    match source {
+        OracleSource::Pyth => PythAdapter::read_price(feed, feed_id),
+        OracleSource::Switchboard => SwitchboardAdapter::read_price(feed, feed_id),
+    }
+}
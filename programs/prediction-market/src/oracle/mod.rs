@@ -0,0 +1,8 @@
+pub mod adapter;
+pub use adapter::*;
+
+pub mod pyth;
+pub use pyth::*;
+
+pub mod switchboard;
+pub use switchboard::*;
@@ -0,0 +1,29 @@
+use anchor_lang::prelude::*;
+use pyth_solana_receiver_sdk::price_update::{PriceUpdateV2, VerificationLevel};
+
+use crate::oracle::{OracleAdapter, OraclePrice};
+use crate::MarketError;
+
+pub struct PythAdapter;
+
+impl OracleAdapter for PythAdapter {
+    fn read_price(feed: &AccountInfo, feed_id: &[u8; 32]) -> Result<OraclePrice> {
+        require_keys_eq!(*feed.owner, pyth_solana_receiver_sdk::ID, MarketError::InvalidOracleAccount);
+        let price_update = PriceUpdateV2::try_deserialize(&mut &feed.data.borrow()[..])?;
+
+        require!(
+            price_update.verification_level == VerificationLevel::Full,
+            MarketError::UnverifiedPriceUpdate
+        );
+        //staleness is checked by the caller against the market expiry, not against the current time
+        let price = price_update.get_price_unchecked(feed_id)?;
+
+        Ok(OraclePrice {
+            price: price.price,
+            conf: price.conf,
+            exponent: price.exponent,
+            publish_time: price.publish_time,
+            prev_publish_time: Some(price_update.price_message.prev_publish_time),
+        })
+    }
+}
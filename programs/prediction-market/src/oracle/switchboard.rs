@@ -0,0 +1,88 @@
+use anchor_lang::prelude::*;
+use switchboard_on_demand::{PullFeedAccountData, PRECISION, SWITCHBOARD_ON_DEMAND_PROGRAM_ID};
+
+use crate::oracle::{OracleAdapter, OraclePrice};
+use crate::MarketError;
+
+pub struct SwitchboardAdapter;
+
+impl OracleAdapter for SwitchboardAdapter {
+    //switchboard feeds are identified by their pull feed account, so the feed id is that address.
+    //The feed only keeps the time of its latest update, which is why it can back twap samples but
+    //not spot resolution
+    fn read_price(feed: &AccountInfo, feed_id: &[u8; 32]) -> Result<OraclePrice> {
+        require_keys_eq!(*feed.owner, *SWITCHBOARD_ON_DEMAND_PROGRAM_ID, MarketError::InvalidOracleAccount);
+        require_keys_eq!(feed.key(), Pubkey::new_from_array(*feed_id), MarketError::InvalidOracleAccount);
+
+        let feed_data = PullFeedAccountData::parse(feed.data.borrow())
+            .map_err(|_| MarketError::InvalidOracleAccount)?;
+        let result = feed_data.result;
+        require_neq!(result.slot, 0, MarketError::NoneFinalPrice);
+
+        let (price, conf, exponent) = scale_result(result.value, result.std_dev)?;
+
+        Ok(OraclePrice {
+            price,
+            conf,
+            exponent,
+            publish_time: feed_data.last_update_timestamp,
+            prev_publish_time: None,
+        })
+    }
+}
+
+//results carry 18 decimals, drop the extra precision until the value fits an i64.
+//Returns (price, conf, exponent)
+pub fn scale_result(value: i128, std_dev: i128) -> Result<(i64, u64, i32)> {
+    let mut value = value;
+    let mut std_dev = std_dev;
+    let mut exponent = -(PRECISION as i32);
+    while i64::try_from(value).is_err() {
+        value /= 10;
+        std_dev /= 10;
+        exponent += 1;
+    }
+
+    Ok((
+        value as i64,
+        u64::try_from(std_dev).map_err(|_| MarketError::ConfidenceTooWide)?,
+        exponent,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn value_that_fits_keeps_all_decimals() {
+        assert_eq!(
+            scale_result(1_500_000_000_000_000_000, 2_000_000_000_000_000),
+            Ok((1_500_000_000_000_000_000, 2_000_000_000_000_000, -18))
+        );
+    }
+
+    #[test]
+    fn large_value_drops_decimals_until_it_fits() {
+        //$65_432.10 with 18 decimals
+        assert_eq!(
+            scale_result(65_432_100_000_000_000_000_000, 1_000_000_000_000_000_000),
+            Ok((6_543_210_000_000_000_000, 100_000_000_000_000, -14))
+        );
+        assert_eq!(
+            scale_result(-65_432_100_000_000_000_000_000, 0),
+            Ok((-6_543_210_000_000_000_000, 0, -14))
+        );
+    }
+
+    #[test]
+    fn largest_value_still_fits() {
+        assert_eq!(scale_result(i128::MAX, 0), Ok((1_701_411_834_604_692_317, 0, 2)));
+    }
+
+    #[test]
+    fn rejects_std_dev_outside_u64() {
+        assert_eq!(scale_result(1, -1), Err(MarketError::ConfidenceTooWide.into()));
+        assert_eq!(scale_result(1, i128::from(u64::MAX) + 1), Err(MarketError::ConfidenceTooWide.into()));
+    }
+}
@@ -1,11 +1,19 @@
 use anchor_lang::prelude::*;
+use anchor_spl::token_interface::TokenAccount;
 use num_derive::*;
 
+use crate::constants::{
+    HIGHER_MINT_SEED, LOWER_MINT_SEED, MAX_COMMITTEE_SIZE, MAX_FEE_BPS, MAX_OUTCOMES,
+    MAX_PRICE_SAMPLES, MAX_SECONDARY_FEEDS, MIN_TWAP_SAMPLES,
+};
+use crate::MarketError;
+
 #[account]
 #[derive(InitSpace)]
 pub struct Market {
     pub creator: Pubkey,
     pub target_price: u64,
+    pub target_exponent: i32, // target_price = price * 10^target_exponent, oracle prices are normalized to it
     pub start_time: u64,
     pub market_duration: u64,
     pub bump: u8,
@@ -13,8 +21,288 @@ pub struct Market {
     pub higher_pool_bump: u8,
     pub final_price: Option<u64>,
     pub lower_pool_bump: u8,
-    pub feed_id: [u8; 66], // from https://pyth.network/developers/price-feed-ids#solana-stables
-    pub initialization: MarketInitialization,
+    pub feed_id: [u8; 66], // pyth price feed id, hex address of a switchboard pull feed, or hex hash of the event
+    pub oracle_source: OracleSource, // backend the feed_id is read from at resolution
+    pub status: MarketStatus,
+    pub higher_pool_total: u64, // snapshot of the higher pool taken at resolution
+    pub lower_pool_total: u64,  // snapshot of the lower pool taken at resolution
+    pub resolution_slot: u64,
+    pub resolution_publish_time: i64, // publish time of the oracle price used for resolution
+    pub expiry_timestamp: i64, // unix time betting closes at, market_duration slots converted once at initialization
+    pub resolution_window: u64, // seconds after expiry_timestamp in which a price update is accepted
+    pub open_bets: u64, // bets placed and not yet claimed, the market is settled once this reaches zero
+    pub seed_liquidity_amount: u64, // snapshot of the AllowedMint limits taken when the pools are funded
+    pub min_bet: u64,
+    pub max_bet: u64,
+    pub protocol_fee_bps: u16, // snapshot of the config fees taken at initialization
+    pub creator_fee_bps: u16,
+    pub exit_fee_bps: u16, // taken from a bet sold back before the betting period is over
+    pub void_grace_period: u64, // snapshot of the config grace period, in seconds
+    pub creator_fees: u64, // creator fees accrued in the treasury vault and not yet withdrawn
+    pub tie_band: u64, // a final price within target_price ± tie_band is a push
+    pub outcome: Option<Outcome>, // set at resolution
+    pub max_conf_bps: u16, // widest oracle confidence interval accepted at resolution, in bps of the price
+    #[max_len(MAX_SECONDARY_FEEDS)]
+    pub secondary_feeds: Vec<OracleFeed>, // with any listed, the market resolves to the median of all feeds
+    pub oracle_quorum: u8, // fresh, confidence-passing feeds needed to resolve
+    pub resolution_mode: ResolutionMode,
+    pub twap_window: u64, // seconds before expiry_timestamp in which twap samples are recorded
+    #[max_len(MAX_COMMITTEE_SIZE)]
+    pub committee: Vec<Pubkey>, // keys allowed to propose and approve the outcome of a committee market
+    pub committee_threshold: u8, // approvals needed before a proposed outcome resolves the market
+    pub proposed_outcome: Option<Outcome>, // outcome under challenge of an optimistic market
+    pub approvals: [u16; 3], // one per Outcome variant, bit i is set once committee[i] backed that outcome
+    pub bond_amount: u64, // bond posted by the proposer and by a disputer of an optimistic market
+    pub challenge_period: u64, // seconds after a proposal in which it can be disputed
+    pub challenge_deadline: i64,
+    pub proposer: Option<Pubkey>,
+    pub disputer: Option<Pubkey>,
+    pub bond_vault_bump: u8,
+    #[max_len(MAX_OUTCOMES)]
+    pub outcome_bounds: Vec<u64>, // ascending bucket edges of a categorical market, empty for higher/lower
+    pub outcome_pool_bumps: [u8; MAX_OUTCOMES as usize],
+    pub funded_outcome_pools: u16, // bit i is set while outcome pool i is funded and not closed
+    pub outcome_totals: [u64; MAX_OUTCOMES as usize], // categorical pool sizes, snapshot at resolution
+    pub winning_outcome: Option<u8>,
+    pub pricing_mode: PricingMode,
+    pub higher_reserve: u64, // higher shares held by the amm of a cpmm market
+    pub lower_reserve: u64,  // lower shares held by the amm of a cpmm market
+    pub liquidity_param: u64, // b of an lmsr market, the creator can lose at most b * ln(2)
+    pub higher_shares: u64,   // higher shares sold by the lmsr market maker
+    pub lower_shares: u64,
+    pub outcome_tokens: bool, // shares are minted as higher/lower spl tokens instead of Bet accounts
+    pub higher_mint_bump: u8,
+    pub lower_mint_bump: u8,
+    pub outstanding_outcome_tokens: u64, // supply of both outcome mints, the pools can not be swept under it
+    pub set_vault_bump: u8, // vault holding the collateral of minted complete sets
+    pub higher_token_cost: u64, // collateral paid for the outstanding higher tokens, refunded pro rata if voided
+    pub lower_token_cost: u64,
+    pub lower_bound: u64, // price range of a scalar market, quoted like target_price, both zero for higher/lower
+    pub upper_bound: u64,
+    pub higher_forfeited: u64, // stake left in the higher pool by bets sold before expiry, paid to the winners
+    pub lower_forfeited: u64,
+}
+
+impl Market {
+    //an open market whose betting duration is over is locked even before anyone touches it. Oracle
+    //prices are stamped with unix time, so betting closes on expiry_timestamp rather than on slots
+    pub fn status_at(&self, now: i64) -> MarketStatus {
+        if self.status == MarketStatus::Open && now > self.expiry_timestamp {
+            MarketStatus::Locked
+        } else {
+            self.status
+        }
+    }
+
+    //every status change goes through here so illegal transitions fail the same way everywhere
+    pub fn transition(&mut self, next: MarketStatus, now: i64) -> Result<()> {
+        let current = self.status_at(now);
+        current.validate_transition(next)?;
+        self.status = next;
+        Ok(())
+    }
+
+    //slot after which unclaimed bets can be swept, counted from the slot the market was created in
+    pub fn lock_period_end(&self, market_lock_period: u64) -> Option<u64> {
+        self.start_time
+            .checked_add(self.market_duration)?
+            .checked_add(market_lock_period)
+    }
+
+    //unix time after which a market nobody managed to resolve can be voided: the resolution window,
+    //the challenge period of a bond and the twap window all have to pass before the grace period starts
+    pub fn void_deadline(&self) -> Option<i64> {
+        let waiting = self.resolution_window.checked_add(self.void_grace_period)?;
+        let waiting = match self.resolution_mode {
+            ResolutionMode::Twap => waiting.checked_add(self.twap_window)?,
+            ResolutionMode::Optimistic => waiting.checked_add(self.challenge_period)?,
+            ResolutionMode::Spot | ResolutionMode::Committee => waiting,
+        };
+        self.expiry_timestamp.checked_add(i64::try_from(waiting).ok()?)
+    }
+
+    //twap_window is bounded by the market duration at initialization, so this never underflows
+    pub fn twap_window_start(&self) -> i64 {
+        self.expiry_timestamp - self.twap_window as i64
+    }
+
+    //samples at least this far apart never overwrite each other inside the window
+    pub fn twap_sample_spacing(&self) -> i64 {
+        self.twap_window.div_ceil(MAX_PRICE_SAMPLES as u64) as i64
+    }
+
+    //longest stretch of the window the average accepts without a sample
+    pub fn twap_max_gap(&self) -> i64 {
+        (self.twap_window / MIN_TWAP_SAMPLES as u64) as i64
+    }
+
+    pub fn outcome_for(&self, final_price: u64) -> Outcome {
+        if final_price > self.target_price.saturating_add(self.tie_band) {
+            Outcome::Higher
+        } else if final_price < self.target_price.saturating_sub(self.tie_band) {
+            Outcome::Lower
+        } else {
+            Outcome::Push
+        }
+    }
+
+    //if the confidence band around the price could land on either side, nobody is picked as the winner
+    pub fn determined_outcome(&self, price: u64, lowest_price: u64, highest_price: u64) -> Result<Outcome> {
+        let outcome = self.outcome_for(price);
+        require!(
+            self.outcome_for(lowest_price) == outcome && self.outcome_for(highest_price) == outcome,
+            MarketError::OutcomeUndetermined
+        );
+        Ok(outcome)
+    }
+
+    pub fn is_winning_direction(&self, direction: &Direction) -> Result<bool> {
+        let outcome = self.outcome.as_ref().ok_or(MarketError::NoneFinalPrice)?;
+        Ok(matches!(
+            (outcome, direction),
+            (Outcome::Higher, Direction::Higher) | (Outcome::Lower, Direction::Lower)
+        ))
+    }
+
+    //pool totals are frozen at resolution so every claim is settled against the same numbers. What
+    //sold bets left behind is not anyone's stake, so it is kept out of the totals
+    pub fn snapshot_pools(&mut self, higher_pool: &TokenAccount, lower_pool: &TokenAccount) {
+        self.higher_pool_total = higher_pool.amount.saturating_sub(self.higher_forfeited);
+        self.lower_pool_total = lower_pool.amount.saturating_sub(self.lower_forfeited);
+    }
+
+    pub fn snapshot_outcome_pool(&mut self, outcome_index: usize, outcome_pool: &TokenAccount) {
+        self.outcome_totals[outcome_index] = outcome_pool.amount;
+    }
+
+    //returns (winning_total, losing_total) of the pool snapshots for a winning direction, the
+    //forfeits of both pools are shared by the winners like the losing stakes
+    pub fn pool_totals(&self, direction: &Direction) -> Result<(u64, u64)> {
+        let (winning_total, losing_total) = match direction {
+            Direction::Higher => (self.higher_pool_total, self.lower_pool_total),
+            Direction::Lower => (self.lower_pool_total, self.higher_pool_total),
+        };
+        let losing_total = losing_total
+            .checked_add(self.higher_forfeited)
+            .and_then(|total| total.checked_add(self.lower_forfeited))
+            .ok_or(MarketError::PayoutOverflow)?;
+        Ok((winning_total, losing_total))
+    }
+
+    pub fn forfeited_mut(&mut self, direction: &Direction) -> &mut u64 {
+        match direction {
+            Direction::Higher => &mut self.higher_forfeited,
+            Direction::Lower => &mut self.lower_forfeited,
+        }
+    }
+
+    pub fn is_push(&self) -> bool {
+        self.outcome == Some(Outcome::Push)
+    }
+
+    //guard for instructions that act on the market without moving it to another status
+    pub fn require_status(&self, expected: MarketStatus, now: i64) -> Result<()> {
+        let current = self.status_at(now);
+        if current == expected {
+            return Ok(());
+        }
+        match (current, expected) {
+            (MarketStatus::Locked, MarketStatus::Open) => err!(MarketError::MarketDurationOver),
+            (_, MarketStatus::Open) => err!(MarketError::MarketNotOpen),
+            (MarketStatus::Open, MarketStatus::Locked) => err!(MarketError::MarketDurationNotOver),
+            (MarketStatus::Created, MarketStatus::Locked) => err!(MarketError::MarketNotOpen),
+            (_, MarketStatus::Locked) => err!(MarketError::MarketAlreadyResolved),
+            (_, MarketStatus::Resolved) => err!(MarketError::MarketNotResolved),
+            (_, MarketStatus::Voided) => err!(MarketError::MarketNotVoided),
+            (MarketStatus::Disputed, MarketStatus::Proposed) => err!(MarketError::AlreadyDisputed),
+            (_, MarketStatus::Proposed) => err!(MarketError::NoProposedOutcome),
+            (_, MarketStatus::Disputed) => err!(MarketError::MarketNotDisputed),
+            _ => err!(MarketError::InvalidStatusTransition),
+        }
+    }
+
+    //complete sets can be minted and merged until an outcome is final
+    pub fn require_unresolved(&self, now: i64) -> Result<()> {
+        match self.status_at(now) {
+            MarketStatus::Open | MarketStatus::Locked | MarketStatus::Proposed | MarketStatus::Disputed => Ok(()),
+            MarketStatus::Created => err!(MarketError::MarketNotOpen),
+            MarketStatus::Closed => err!(MarketError::MarketAlreadyClosed),
+            _ => err!(MarketError::MarketAlreadyResolved),
+        }
+    }
+
+    pub fn is_categorical(&self) -> bool {
+        !self.outcome_bounds.is_empty()
+    }
+
+    //higher tokens are the long side and lower tokens the short side of the range
+    pub fn is_scalar(&self) -> bool {
+        self.upper_bound > 0
+    }
+
+    pub fn outcome_count(&self) -> u8 {
+        self.outcome_bounds.len() as u8 + 1
+    }
+
+    //bucket i holds prices in [outcome_bounds[i - 1], outcome_bounds[i]), the last one is open ended
+    pub fn outcome_index_for(&self, price: u64) -> u8 {
+        self.outcome_bounds
+            .iter()
+            .position(|bound| price < *bound)
+            .unwrap_or(self.outcome_bounds.len()) as u8
+    }
+
+    //everything staked across the outcome pools of a categorical market
+    pub fn outcome_totals_sum(&self) -> Result<u64> {
+        self.outcome_totals
+            .iter()
+            .try_fold(0u64, |sum, total| sum.checked_add(*total))
+            .ok_or(MarketError::PayoutOverflow.into())
+    }
+
+    //an out of range index gets a bump no outcome pool was created with
+    pub fn outcome_pool_bump(&self, outcome_index: u8) -> u8 {
+        self.outcome_pool_bumps.get(outcome_index as usize).copied().unwrap_or_default()
+    }
+
+    //the bumps are stored by initialize_outcome_mints, until then the market has no set vault to close
+    pub fn has_outcome_mints(&self) -> bool {
+        self.outcome_tokens && self.set_vault_bump != 0
+    }
+
+    pub fn outcome_mint_bump(&self, direction: &Direction) -> u8 {
+        match direction {
+            Direction::Higher => self.higher_mint_bump,
+            Direction::Lower => self.lower_mint_bump,
+        }
+    }
+
+    pub fn token_cost_mut(&mut self, direction: &Direction) -> &mut u64 {
+        match direction {
+            Direction::Higher => &mut self.higher_token_cost,
+            Direction::Lower => &mut self.lower_token_cost,
+        }
+    }
+
+    pub fn committee_index(&self, member: &Pubkey) -> Result<usize> {
+        self.committee
+            .iter()
+            .position(|key| key == member)
+            .ok_or(MarketError::NotCommitteeMember.into())
+    }
+
+    //approvals are counted per outcome, so a wrong proposal only gathers its own votes instead of
+    //blocking the outcome the rest of the committee backs. Each member backs a single outcome
+    pub fn record_approval(&mut self, member: usize, outcome: Outcome) -> Result<()> {
+        let bit = 1 << member;
+        require!(self.approvals.iter().all(|approvals| approvals & bit == 0), MarketError::AlreadyApproved);
+        self.approvals[outcome as usize] |= bit;
+        Ok(())
+    }
+
+    pub fn approval_count(&self, outcome: Outcome) -> u32 {
+        self.approvals[outcome as usize].count_ones()
+    }
 }
 
 #[derive(
@@ -25,12 +313,109 @@ pub enum Direction {
     Lower,
 }
 
+impl Direction {
+    pub fn mint_seed(&self) -> &'static [u8] {
+        match self {
+            Direction::Higher => HIGHER_MINT_SEED.as_bytes(),
+            Direction::Lower => LOWER_MINT_SEED.as_bytes(),
+        }
+    }
+}
+
+#[derive(
+    AnchorSerialize, AnchorDeserialize, Clone, Copy, InitSpace, ToPrimitive, FromPrimitive, PartialEq, Eq,
+)]
+pub enum Outcome {
+    Higher,
+    Lower,
+    Push, // final price landed on the target, every stake is refunded
+}
+
+#[derive(
+    AnchorSerialize, AnchorDeserialize, Clone, Copy, InitSpace, ToPrimitive, FromPrimitive, PartialEq, Eq,
+)]
+pub enum OracleSource {
+    Pyth,        // PriceUpdateV2 account posted through the pyth receiver
+    Switchboard, // switchboard on-demand pull feed account
+}
+
+#[derive(
+    AnchorSerialize, AnchorDeserialize, Clone, Copy, InitSpace, ToPrimitive, FromPrimitive, PartialEq, Eq,
+)]
+pub enum ResolutionMode {
+    Spot, // price published right after expiry, or the median of several feeds
+    Twap, // time weighted average of the samples recorded before expiry
+    Committee, // event without a price feed, Higher is yes and Lower is no, decided by the committee
+    Optimistic, // like committee, but anyone proposes with a bond and disputes go to the config admin
+}
+
+#[derive(
+    AnchorSerialize, AnchorDeserialize, Clone, Copy, InitSpace, ToPrimitive, FromPrimitive, PartialEq, Eq,
+)]
+pub enum PricingMode {
+    Parimutuel, // odds follow the pool ratio, winners split the losing pool
+    Cpmm,       // bets buy outcome shares on a constant-product curve, a winning share pays 1
+    Lmsr,       // bets buy outcome shares from a logarithmic market scoring rule market maker
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, InitSpace, PartialEq, Eq)]
+pub struct OracleFeed {
+    pub source: OracleSource,
+    pub feed_id: [u8; 32], // pyth feed id or switchboard pull feed address
+}
+
 #[derive(
-    AnchorSerialize, AnchorDeserialize, Clone, InitSpace, ToPrimitive, FromPrimitive, PartialEq, Eq,
+    AnchorSerialize, AnchorDeserialize, Clone, Copy, InitSpace, ToPrimitive, FromPrimitive, PartialEq, Eq,
 )]
-pub enum MarketInitialization {
-    InitializedMarket,
-    InitializedPools,
+pub enum MarketStatus {
+    Created,  // market account exists, pools are not funded yet
+    Open,     // pools are funded and bets are accepted
+    Locked,   // betting duration is over, waiting for resolution
+    Proposed, // bonded outcome posted, can be disputed until the challenge deadline
+    Disputed, // proposal challenged with a matching bond, waiting for the admin ruling
+    Resolved, // final price is known, winners can claim
+    Voided,   // market could not be resolved, every bet is refundable
+    Settled,  // every bet is claimed or the claim period is over
+    Closed,   // pools are drained and closed
+}
+
+impl MarketStatus {
+    pub fn validate_transition(&self, next: MarketStatus) -> Result<()> {
+        use MarketStatus::*;
+
+        match (*self, next) {
+            (Created, Open)
+            | (Open, Locked)
+            | (Open, Closed)
+            | (Locked, Closed)
+            | (Locked, Resolved)
+            | (Locked, Proposed)
+            | (Proposed, Disputed)
+            | (Proposed, Resolved)
+            | (Disputed, Resolved)
+            | (Locked, Voided)
+            | (Proposed, Voided)
+            | (Disputed, Voided)
+            | (Resolved, Settled)
+            | (Voided, Settled)
+            | (Settled, Closed)
+            | (Created, Closed) => Ok(()),
+            (Closed, _) => err!(MarketError::MarketAlreadyClosed),
+            (_, Open) => err!(MarketError::MarketAlreadyOpen),
+            (Created, _) => err!(MarketError::MarketNotOpen),
+            (Open, _) => err!(MarketError::MarketDurationNotOver),
+            (_, Locked) => err!(MarketError::MarketAlreadyLocked),
+            (Resolved | Voided | Settled, Resolved | Voided | Proposed | Disputed) => {
+                err!(MarketError::MarketAlreadyResolved)
+            }
+            (Proposed | Disputed, Proposed) => err!(MarketError::OutcomeAlreadyProposed),
+            (Disputed, Disputed) => err!(MarketError::AlreadyDisputed),
+            (Locked, Disputed) => err!(MarketError::NoProposedOutcome),
+            (Locked, Settled) => err!(MarketError::MarketNotResolved),
+            (Resolved | Voided, Closed) => err!(MarketError::MarketNotSettled),
+            _ => err!(MarketError::InvalidStatusTransition),
+        }
+    }
 }
 #[account]
 #[derive(InitSpace)]
@@ -44,4 +429,165 @@ pub struct Bet {
     pub initialized: bool,
     pub odds: u64,
     pub is_won: bool,
+    pub id: u64, // nonce taken from UserMarketPosition, part of the bet seeds
+    pub outcome_index: u8, // outcome picked in a categorical market, where direction is unused
+    pub shares: u64, // outcome shares bought in a cpmm market, each pays 1 if the direction wins
+}
+
+//aggregated alternative to Bet, one account per (user, market) holding both directions
+#[account]
+#[derive(InitSpace)]
+pub struct Position {
+    pub user: Pubkey,
+    pub market: Pubkey,
+    pub higher_amount: u64,
+    pub lower_amount: u64,
+    pub higher_odds: u64, // stake-weighted odds of the higher stake
+    pub lower_odds: u64,  // stake-weighted odds of the lower stake
+    pub bump: u8,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct UserMarketPosition {
+    pub user: Pubkey,
+    pub market: Pubkey,
+    pub next_bet_id: u64,
+    pub bump: u8,
+}
+
+//singleton holding the protocol wide settings
+#[account]
+#[derive(InitSpace)]
+pub struct ProgramConfig {
+    pub admin: Pubkey,
+    pub pending_admin: Option<Pubkey>, // set by propose_admin, becomes admin once it calls accept_admin
+    pub protocol_fee_bps: u16,
+    pub creator_fee_bps: u16,
+    pub exit_fee_bps: u16,
+    pub min_market_duration: u64, // slots
+    pub market_lock_period: u64,  // slots after the betting period before unclaimed bets can be swept
+    pub max_resolution_window: u64, // seconds
+    pub void_grace_period: u64, // seconds after the resolution deadline before an unresolved market can be voided
+    pub bump: u8,
+}
+
+impl ProgramConfig {
+    pub fn apply(&mut self, params: &ConfigParams) -> Result<()> {
+        require_gte!(MAX_FEE_BPS, params.protocol_fee_bps, MarketError::FeeTooHigh);
+        require_gte!(MAX_FEE_BPS, params.creator_fee_bps, MarketError::FeeTooHigh);
+        require_gte!(MAX_FEE_BPS, params.exit_fee_bps, MarketError::FeeTooHigh);
+        require!(
+            params.min_market_duration > 0 && params.max_resolution_window > 0,
+            MarketError::InvalidConfig
+        );
+
+        self.protocol_fee_bps = params.protocol_fee_bps;
+        self.creator_fee_bps = params.creator_fee_bps;
+        self.exit_fee_bps = params.exit_fee_bps;
+        self.min_market_duration = params.min_market_duration;
+        self.market_lock_period = params.market_lock_period;
+        self.max_resolution_window = params.max_resolution_window;
+        self.void_grace_period = params.void_grace_period;
+        Ok(())
+    }
+}
+
+//creator chosen settings for how a market is priced and resolved
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct ResolutionParams {
+    pub oracle_source: OracleSource,
+    pub resolution_window: u64,
+    pub tie_band: u64,
+    pub max_conf_bps: u16,
+    pub target_exponent: i32, // target_price and tie_band are quoted in units of 10^target_exponent
+    pub secondary_feeds: Vec<OracleFeed>,
+    pub oracle_quorum: u8,
+    pub resolution_mode: ResolutionMode,
+    pub twap_window: u64,
+    pub committee: Vec<Pubkey>,
+    pub committee_threshold: u8,
+    pub bond_amount: u64,
+    pub challenge_period: u64,
+    pub outcome_bounds: Vec<u64>,
+    pub pricing_mode: PricingMode,
+    pub outcome_tokens: bool,
+    pub lower_bound: u64,
+    pub upper_bound: u64,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct ConfigParams {
+    pub protocol_fee_bps: u16,
+    pub creator_fee_bps: u16,
+    pub exit_fee_bps: u16,
+    pub min_market_duration: u64,
+    pub market_lock_period: u64,
+    pub max_resolution_window: u64,
+    pub void_grace_period: u64,
+}
+
+//registry entry, a mint can only back markets while its AllowedMint account exists
+#[account]
+#[derive(InitSpace)]
+pub struct AllowedMint {
+    pub mint: Pubkey,
+    pub min_bet: u64,
+    pub max_bet: u64,
+    pub seed_liquidity_amount: u64, // seeded by the creator into each pool
+    pub bump: u8,
+}
+
+//one per pool mint, owns the vault that protocol and creator fees are paid into
+#[account]
+#[derive(InitSpace)]
+pub struct Treasury {
+    pub mint: Pubkey,
+    pub protocol_fees: u64, // protocol fees accrued in the vault and not yet withdrawn
+    pub bump: u8,
+    pub vault_bump: u8,
+}
+
+//an oracle price in the market scale, with the confidence band around it
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, InitSpace)]
+pub struct PriceObservation {
+    pub price: u64,
+    pub lowest_price: u64,
+    pub highest_price: u64,
+    pub publish_time: i64,
+}
+
+//ring buffer of the samples recorded for a twap market, the oldest is overwritten once full
+#[account]
+#[derive(InitSpace)]
+pub struct PriceHistory {
+    pub market: Pubkey,
+    pub samples: [PriceObservation; MAX_PRICE_SAMPLES as usize],
+    pub head: u8, // index the next sample is written to
+    pub len: u8,
+    pub bump: u8,
+}
+
+impl PriceHistory {
+    pub fn push(&mut self, sample: PriceObservation) {
+        self.samples[self.head as usize] = sample;
+        self.head = (self.head + 1) % MAX_PRICE_SAMPLES;
+        self.len = (self.len + 1).min(MAX_PRICE_SAMPLES);
+    }
+
+    pub fn latest(&self) -> Option<&PriceObservation> {
+        if self.len == 0 {
+            return None;
+        }
+        let index = (self.head + MAX_PRICE_SAMPLES - 1) % MAX_PRICE_SAMPLES;
+        Some(&self.samples[index as usize])
+    }
+
+    //samples from oldest to newest
+    pub fn observations(&self) -> Vec<PriceObservation> {
+        let oldest = (self.head + MAX_PRICE_SAMPLES - self.len) % MAX_PRICE_SAMPLES;
+        (0..self.len)
+            .map(|i| self.samples[((oldest + i) % MAX_PRICE_SAMPLES) as usize])
+            .collect()
+    }
 }
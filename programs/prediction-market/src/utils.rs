@@ -1,6 +1,44 @@
+use anchor_lang::prelude::*;
 use anchor_lang::solana_program::hash::hash;
+use anchor_spl::token_2022::spl_token_2022::{
+    self,
+    extension::{BaseStateWithExtensions, ExtensionType, StateWithExtensions},
+};
+
+use crate::MarketError;
 
 //helper function to hash long string into the max seed length of 32
 pub fn hash_to_bytes(data: &[u8]) -> [u8; 32] {
     hash(data).to_bytes()
 }
+
+//the AllowedMint and Treasury of a mint are loaded by hand, so a mint the admin never registered is
+//reported as an invalid pool mint rather than as a missing account
+pub fn load_registered<T: AccountDeserialize + Owner>(account: &AccountInfo) -> Result<T> {
+    require!(
+        *account.owner == T::owner() && !account.data_is_empty(),
+        MarketError::InvalidPoolMint
+    );
+    T::try_deserialize(&mut &account.data.borrow()[..])
+}
+
+//pool accounting assumes the amount sent is the amount received and that nobody else can move
+//pool funds, so token-2022 mints with fees, hooks or a permanent delegate are rejected
+pub fn validate_mint_extensions(mint: &AccountInfo) -> Result<()> {
+    if mint.owner != &spl_token_2022::ID {
+        return Ok(());
+    }
+
+    let mint_data = mint.try_borrow_data()?;
+    let mint_with_extensions = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&mint_data)?;
+    for extension in mint_with_extensions.get_extension_types()? {
+        match extension {
+            ExtensionType::TransferFeeConfig
+            | ExtensionType::TransferHook
+            | ExtensionType::PermanentDelegate => return err!(MarketError::UnsupportedMintExtension),
+            _ => {}
+        }
+    }
+
+    Ok(())
+}
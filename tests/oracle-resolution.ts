@@ -0,0 +1,1539 @@
+import * as anchor from "@coral-xyz/anchor";
+import { PublicKey, Keypair, SystemProgram, ComputeBudgetProgram } from "@solana/web3.js";
+import { assert } from "chai";
+import crypto from "crypto";
+import * as token from "@solana/spl-token";
+import { BankrunProvider, startAnchor } from "anchor-bankrun";
+import { Clock, ProgramTestContext } from "solana-bankrun";
+import { PredictionMarket } from "../target/types/prediction_market";
+
+const IDL = require("../target/idl/prediction_market.json");
+
+//the local validator has no pyth receiver to post prices through, so the flows that need an
+//oracle price run on bankrun, where price updates are written as plain accounts and the clock can
+//be moved past expiry
+const PYTH_RECEIVER = new PublicKey("rec5EKMGg6MxZYaMdyBfgwp4d5rB9T1VQH5pJv5LtFJ");
+
+const BET_SEED = "prediction_bet";
+const USER_POSITION_SEED = "user_position";
+const POSITION_SEED = "position";
+const PRICE_HISTORY_SEED = "price_history";
+const CONFIG_SEED = "config";
+const ALLOWED_MINT_SEED = "allowed_mint";
+const HIGHER_POOL_SEED = "higher_pool";
+const LOWER_POOL_SEED = "lower_pool";
+const TREASURY_SEED = "treasury";
+const TREASURY_VAULT_SEED = "treasury_vault";
+const HIGHER_MINT_SEED = "higher_mint";
+const LOWER_MINT_SEED = "lower_mint";
+const SET_VAULT_SEED = "set_vault";
+const BOND_VAULT_SEED = "bond_vault";
+const SEED_LIQUIDITY_AMOUNT = new anchor.BN(1000000);
+const TO_MINT = BigInt(30000000);
+
+const configParams = {
+  protocolFeeBps: 100,
+  creatorFeeBps: 50,
+  exitFeeBps: 200,
+  minMarketDuration: new anchor.BN(1),
+  marketLockPeriod: new anchor.BN(1000),
+  maxResolutionWindow: new anchor.BN(3600),
+  voidGracePeriod: new anchor.BN(600),
+};
+
+const resolutionParams = {
+  resolutionWindow: new anchor.BN(60),
+  tieBand: new anchor.BN(0),
+  maxConfBps: 100,
+  targetExponent: 0,
+  oracleSource: { pyth: {} },
+  secondaryFeeds: [],
+  oracleQuorum: 1,
+  resolutionMode: { spot: {} },
+  twapWindow: new anchor.BN(0),
+  committee: [],
+  committeeThreshold: 0,
+  bondAmount: new anchor.BN(0),
+  challengePeriod: new anchor.BN(0),
+  outcomeBounds: [],
+  pricingMode: { parimutuel: {} },
+  outcomeTokens: false,
+  lowerBound: new anchor.BN(0),
+  upperBound: new anchor.BN(0),
+};
+
+//prices are published with two decimals against a target of 140
+const HIGHER_PRICE = { price: 15000, conf: 10, exponent: -2 };
+const LOWER_PRICE = { price: 13000, conf: 10, exponent: -2 };
+const TARGET_PRICE = { price: 14000, conf: 0, exponent: -2 };
+
+type Harness = {
+  context: ProgramTestContext;
+  program: anchor.Program<PredictionMarket>;
+  mint: PublicKey;
+};
+
+type MarketAccounts = {
+  marketAddress: PublicKey;
+  higherPool: PublicKey;
+  lowerPool: PublicKey;
+  feedId: Buffer;
+};
+
+describe("oracle_resolution", () => {
+  const h = {} as Harness;
+  const creator = Keypair.generate();
+
+  before(async () => {
+    h.context = await startAnchor(".", [], []);
+    const provider = new BankrunProvider(h.context);
+    h.program = new anchor.Program<PredictionMarket>(IDL, provider);
+    await writeConfig(h, configParams);
+
+    h.mint = await createMint(h.context);
+    await allowMint(h);
+    await fundUser(h, creator);
+  });
+
+  describe("Settlement", () => {
+    it("Pays a winner its stake and a pro-rata share of the losing pool, net of fees", async () => {
+      const winner = Keypair.generate();
+      const loser = Keypair.generate();
+      await fundUser(h, winner);
+      await fundUser(h, loser);
+
+      const market = await createMarket(h, creator, resolutionParams);
+      const winningBet = await placeBet(h, market, winner, 100000, { higher: {} });
+      const losingBet = await placeBet(h, market, loser, 300000, { lower: {} });
+
+      await resolveWithPrice(h, market, HIGHER_PRICE);
+      let marketData = await h.program.account.market.fetch(market.marketAddress);
+      assert.deepEqual(marketData.outcome, { higher: {} });
+      assert.strictEqual(marketData.higherPoolTotal.toString(), "1100000");
+      assert.strictEqual(marketData.lowerPoolTotal.toString(), "1300000");
+
+      const vaultBefore = await tokenBalance(h.context, treasuryVaultAddress(h));
+      await claimBet(h, market, winner, winningBet);
+
+      //100000 * 1300000 / 1100000, 1% protocol and 0.5% creator fee on the winnings
+      const winnings = BigInt(118181);
+      const protocolFee = BigInt(1181);
+      const creatorFee = BigInt(590);
+      assert.strictEqual(
+        await tokenBalance(h.context, userAta(h, winner)),
+        TO_MINT + winnings - protocolFee - creatorFee
+      );
+      assert.strictEqual(
+        (await tokenBalance(h.context, treasuryVaultAddress(h))) - vaultBefore,
+        protocolFee + creatorFee
+      );
+      marketData = await h.program.account.market.fetch(market.marketAddress);
+      assert.strictEqual(marketData.creatorFees.toString(), creatorFee.toString());
+      assert.isNull(await h.program.account.bet.fetchNullable(winningBet));
+      assert.deepEqual(marketData.status, { resolved: {} });
+
+      //the losing bet is closed without a payout and settles the market
+      await claimBet(h, market, loser, losingBet);
+      assert.strictEqual(await tokenBalance(h.context, userAta(h, loser)), TO_MINT - BigInt(300000));
+      assert.isNull(await h.program.account.bet.fetchNullable(losingBet));
+      marketData = await h.program.account.market.fetch(market.marketAddress);
+      assert.deepEqual(marketData.status, { settled: {} });
+    });
+
+    it("Refunds both sides without fees on a push", async () => {
+      const higherBettor = Keypair.generate();
+      const lowerBettor = Keypair.generate();
+      await fundUser(h, higherBettor);
+      await fundUser(h, lowerBettor);
+
+      const market = await createMarket(h, creator, resolutionParams);
+      const higherBet = await placeBet(h, market, higherBettor, 100000, { higher: {} });
+      const lowerBet = await placeBet(h, market, lowerBettor, 200000, { lower: {} });
+
+      await resolveWithPrice(h, market, TARGET_PRICE);
+      const marketData = await h.program.account.market.fetch(market.marketAddress);
+      assert.deepEqual(marketData.outcome, { push: {} });
+
+      await claimBet(h, market, higherBettor, higherBet);
+      await claimBet(h, market, lowerBettor, lowerBet);
+      assert.strictEqual(await tokenBalance(h.context, userAta(h, higherBettor)), TO_MINT);
+      assert.strictEqual(await tokenBalance(h.context, userAta(h, lowerBettor)), TO_MINT);
+    });
+
+    it("Pays an amm bet one unit per share, fees taken on what it won", async () => {
+      const bettor = Keypair.generate();
+      await fundUser(h, bettor);
+
+      const market = await createMarket(h, creator, {
+        ...resolutionParams,
+        pricingMode: { cpmm: {} },
+      });
+      const bet = await buyShares(h, market, bettor, 100000, { lower: {} });
+      const { shares } = await h.program.account.bet.fetch(bet);
+      assert.isTrue(shares.gtn(100000));
+
+      await resolveWithPrice(h, market, LOWER_PRICE);
+      await claimBet(h, market, bettor, bet);
+
+      const winnings = BigInt(shares.subn(100000).toString());
+      const fees = (winnings * BigInt(100)) / BigInt(10000) + (winnings * BigInt(50)) / BigInt(10000);
+      assert.strictEqual(
+        await tokenBalance(h.context, userAta(h, bettor)),
+        TO_MINT + winnings - fees
+      );
+    });
+
+    it("Refunds the stakes of a market voided for lack of a price", async () => {
+      const bettor = Keypair.generate();
+      await fundUser(h, bettor);
+
+      const market = await createMarket(h, creator, resolutionParams);
+      const bet = await placeBet(h, market, bettor, 100000, { higher: {} });
+
+      const { expiryTimestamp, resolutionWindow, voidGracePeriod } =
+        await h.program.account.market.fetch(market.marketAddress);
+      await setClock(
+        h.context,
+        expiryTimestamp.add(resolutionWindow).add(voidGracePeriod).toNumber() + 1
+      );
+      await h.program.methods
+        .voidMarket()
+        .accountsStrict({
+          market: market.marketAddress,
+          bondVault: null,
+          proposer: null,
+          proposerAta: null,
+          disputerAta: null,
+          mint: null,
+          caller: h.context.payer.publicKey,
+          tokenProgram: null,
+        })
+        .rpc();
+
+      await h.program.methods
+        .refundBet()
+        .accountsStrict({
+          market: market.marketAddress,
+          higherPool: market.higherPool,
+          lowerPool: market.lowerPool,
+          userAta: userAta(h, bettor),
+          user: bettor.publicKey,
+          bet,
+          mint: h.mint,
+          tokenProgram: token.TOKEN_PROGRAM_ID,
+        })
+        .signers([bettor])
+        .rpc();
+
+      assert.strictEqual(await tokenBalance(h.context, userAta(h, bettor)), TO_MINT);
+      const marketData = await h.program.account.market.fetch(market.marketAddress);
+      assert.deepEqual(marketData.status, { settled: {} });
+    });
+
+    it("Voids a disputed market nobody ruled on and refunds both bonds", async () => {
+      const bettor = Keypair.generate();
+      const proposer = Keypair.generate();
+      const disputer = Keypair.generate();
+      await fundUser(h, bettor);
+      await fundUser(h, proposer);
+      await fundUser(h, disputer);
+
+      const market = await createMarket(h, creator, {
+        ...resolutionParams,
+        resolutionMode: { optimistic: {} },
+        bondAmount: new anchor.BN(1000),
+        challengePeriod: new anchor.BN(60),
+      });
+      const bet = await placeBet(h, market, bettor, 100000, { higher: {} });
+
+      const { expiryTimestamp, resolutionWindow, voidGracePeriod, challengePeriod } =
+        await h.program.account.market.fetch(market.marketAddress);
+      await setClock(h.context, expiryTimestamp.toNumber() + 1);
+
+      const [bondVault] = PublicKey.findProgramAddressSync(
+        [anchor.utils.bytes.utf8.encode(BOND_VAULT_SEED), market.marketAddress.toBuffer()],
+        h.program.programId
+      );
+      await h.program.methods
+        .proposeBondedOutcome({ higher: {} })
+        .accountsStrict({
+          market: market.marketAddress,
+          bondVault,
+          proposerAta: userAta(h, proposer),
+          proposer: proposer.publicKey,
+          mint: h.mint,
+          tokenProgram: token.TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([proposer])
+        .rpc();
+      await h.program.methods
+        .disputeOutcome()
+        .accountsStrict({
+          market: market.marketAddress,
+          bondVault,
+          disputerAta: userAta(h, disputer),
+          disputer: disputer.publicKey,
+          mint: h.mint,
+          tokenProgram: token.TOKEN_PROGRAM_ID,
+        })
+        .signers([disputer])
+        .rpc();
+
+      //the admin never rules, so the market is given up on once every window has passed
+      await setClock(
+        h.context,
+        expiryTimestamp.add(resolutionWindow).add(voidGracePeriod).add(challengePeriod).toNumber() + 1
+      );
+      await h.program.methods
+        .voidMarket()
+        .accountsStrict({
+          market: market.marketAddress,
+          bondVault,
+          proposer: proposer.publicKey,
+          proposerAta: userAta(h, proposer),
+          disputerAta: userAta(h, disputer),
+          mint: h.mint,
+          caller: h.context.payer.publicKey,
+          tokenProgram: token.TOKEN_PROGRAM_ID,
+        })
+        .rpc();
+
+      assert.strictEqual(await tokenBalance(h.context, userAta(h, proposer)), TO_MINT);
+      assert.strictEqual(await tokenBalance(h.context, userAta(h, disputer)), TO_MINT);
+      assert.isNull(await h.context.banksClient.getAccount(bondVault));
+
+      await h.program.methods
+        .refundBet()
+        .accountsStrict({
+          market: market.marketAddress,
+          higherPool: market.higherPool,
+          lowerPool: market.lowerPool,
+          userAta: userAta(h, bettor),
+          user: bettor.publicKey,
+          bet,
+          mint: h.mint,
+          tokenProgram: token.TOKEN_PROGRAM_ID,
+        })
+        .signers([bettor])
+        .rpc();
+      assert.strictEqual(await tokenBalance(h.context, userAta(h, bettor)), TO_MINT);
+    });
+
+    it("Only accepts the first price published after expiry", async () => {
+      const market = await createMarket(h, creator, resolutionParams);
+      const { expiryTimestamp } = await h.program.account.market.fetch(market.marketAddress);
+      const expiry = expiryTimestamp.toNumber();
+      await setClock(h.context, expiry + 30);
+
+      //the feed had already updated after expiry, so this is a later print picked by the resolver
+      const priceUpdate = Keypair.generate().publicKey;
+      await writePriceUpdate(h.context, priceUpdate, market.feedId, {
+        ...HIGHER_PRICE,
+        publishTime: expiry + 20,
+        prevPublishTime: expiry + 10,
+      });
+      await expectAnchorError(resolveMarket(h, market, priceUpdate), "PriceUpdateNotFirstAfterExpiry");
+
+      const earlyUpdate = Keypair.generate().publicKey;
+      await writePriceUpdate(h.context, earlyUpdate, market.feedId, {
+        ...HIGHER_PRICE,
+        publishTime: expiry - 1,
+        prevPublishTime: expiry - 5,
+      });
+      await expectAnchorError(resolveMarket(h, market, earlyUpdate), "PriceUpdateTooEarly");
+    });
+  });
+
+  describe("Resolution", () => {
+    it("Pushes a market whose price lands inside the tie band", async () => {
+      const bettor = Keypair.generate();
+      await fundUser(h, bettor);
+
+      //target and band are quoted in cents, 140.00 +/- 1.00
+      const market = await createMarket(
+        h,
+        creator,
+        { ...resolutionParams, targetExponent: -2, tieBand: new anchor.BN(100) },
+        new anchor.BN(14000)
+      );
+      const bet = await placeBet(h, market, bettor, 100000, { higher: {} });
+
+      await resolveWithPrice(h, market, { price: 14080, conf: 10, exponent: -2 });
+      const marketData = await h.program.account.market.fetch(market.marketAddress);
+      assert.deepEqual(marketData.outcome, { push: {} });
+      assert.strictEqual(marketData.finalPrice.toString(), "14080");
+
+      await claimBet(h, market, bettor, bet);
+      assert.strictEqual(await tokenBalance(h.context, userAta(h, bettor)), TO_MINT);
+    });
+
+    it("Rejects a price whose confidence interval is too wide", async () => {
+      const market = await createMarket(h, creator, resolutionParams);
+      //2.00 of confidence on 150.00 is 1.33%, above the 1% the market accepts
+      const priceUpdate = await writeExpiryPrice(h, market, { price: 15000, conf: 200, exponent: -2 });
+      await expectAnchorError(resolveMarket(h, market, priceUpdate), "ConfidenceTooWide");
+    });
+
+    it("Rejects a price whose confidence interval straddles the target", async () => {
+      const market = await createMarket(h, creator, resolutionParams);
+      //140.50 +/- 1.00 could be either side of 140
+      const priceUpdate = await writeExpiryPrice(h, market, { price: 14050, conf: 100, exponent: -2 });
+      await expectAnchorError(resolveMarket(h, market, priceUpdate), "OutcomeUndetermined");
+    });
+  });
+
+  describe("Multiple Feeds", () => {
+    const secondaryFeedIds = [crypto.randomBytes(32), crypto.randomBytes(32)];
+    const medianParams = {
+      ...resolutionParams,
+      secondaryFeeds: secondaryFeedIds.map((feedId) => ({
+        source: { pyth: {} },
+        feedId: Array.from(feedId),
+      })),
+      oracleQuorum: 2,
+    };
+
+    it("Resolves to the median of the primary and secondary feeds", async () => {
+      const market = await createMarket(h, creator, medianParams);
+      const primary = await writeExpiryPrice(h, market, { price: 13900, conf: 10, exponent: -2 });
+      const secondaries = [
+        await writeExpiryPrice(h, market, { price: 15000, conf: 10, exponent: -2 }, secondaryFeedIds[0]),
+        await writeExpiryPrice(h, market, { price: 14500, conf: 10, exponent: -2 }, secondaryFeedIds[1]),
+      ];
+      await resolveMarket(h, market, primary, secondaries);
+
+      //the primary alone would have resolved Lower
+      const marketData = await h.program.account.market.fetch(market.marketAddress);
+      assert.strictEqual(marketData.finalPrice.toString(), "145");
+      assert.deepEqual(marketData.outcome, { higher: {} });
+    });
+
+    it("Leaves out a stale feed as long as the quorum is met", async () => {
+      const market = await createMarket(h, creator, medianParams);
+      const primary = await writeExpiryPrice(h, market, { price: 15000, conf: 10, exponent: -2 });
+      const fresh = await writeExpiryPrice(
+        h,
+        market,
+        { price: 15200, conf: 10, exponent: -2 },
+        secondaryFeedIds[0]
+      );
+      //this feed had printed after expiry before, so its update is not the one at expiry
+      const { expiryTimestamp } = await h.program.account.market.fetch(market.marketAddress);
+      const stale = Keypair.generate().publicKey;
+      await writePriceUpdate(h.context, stale, secondaryFeedIds[1], {
+        price: 10000,
+        conf: 10,
+        exponent: -2,
+        publishTime: expiryTimestamp.toNumber() + 3,
+        prevPublishTime: expiryTimestamp.toNumber() + 1,
+      });
+      await resolveMarket(h, market, primary, [fresh, stale]);
+
+      //the median of the two samples left
+      const marketData = await h.program.account.market.fetch(market.marketAddress);
+      assert.strictEqual(marketData.finalPrice.toString(), "151");
+    });
+
+    it("Fails when too few feeds give a usable price", async () => {
+      const market = await createMarket(h, creator, medianParams);
+      const primary = await writeExpiryPrice(h, market, { price: 15000, conf: 10, exponent: -2 });
+      const wide = await writeExpiryPrice(
+        h,
+        market,
+        { price: 15000, conf: 500, exponent: -2 },
+        secondaryFeedIds[0]
+      );
+      const early = Keypair.generate().publicKey;
+      const { expiryTimestamp } = await h.program.account.market.fetch(market.marketAddress);
+      await writePriceUpdate(h.context, early, secondaryFeedIds[1], {
+        price: 15000,
+        conf: 10,
+        exponent: -2,
+        publishTime: expiryTimestamp.toNumber() - 2,
+        prevPublishTime: expiryTimestamp.toNumber() - 3,
+      });
+      await expectAnchorError(resolveMarket(h, market, primary, [wide, early]), "OracleQuorumNotMet");
+    });
+
+    it("Requires every secondary feed, in the order the market lists them", async () => {
+      const market = await createMarket(h, creator, medianParams);
+      const primary = await writeExpiryPrice(h, market, { price: 15000, conf: 10, exponent: -2 });
+      const secondaries = [
+        await writeExpiryPrice(h, market, { price: 15000, conf: 10, exponent: -2 }, secondaryFeedIds[0]),
+        await writeExpiryPrice(h, market, { price: 15000, conf: 10, exponent: -2 }, secondaryFeedIds[1]),
+      ];
+
+      await expectAnchorError(resolveMarket(h, market, primary, secondaries.slice(0, 1)), "MissingOracleFeeds");
+      //each account is read against the feed id listed at its position
+      await expectAnchorError(
+        resolveMarket(h, market, primary, [secondaries[1], secondaries[0]]),
+        "MismatchedFeedId"
+      );
+    });
+  });
+
+  describe("TWAP", () => {
+    //a 60 second window at the end of 120 seconds of betting: samples at least 2 seconds apart, no
+    //gap in the window longer than 20 seconds
+    const twapParams = {
+      ...resolutionParams,
+      resolutionMode: { twap: {} },
+      twapWindow: new anchor.BN(60),
+    };
+    const twapDuration = new anchor.BN(300);
+
+    it("Resolves on the time-weighted average of the recorded samples", async () => {
+      const market = await createMarket(h, creator, twapParams, new anchor.BN(140), twapDuration);
+      const expiry = (await h.program.account.market.fetch(market.marketAddress)).expiryTimestamp.toNumber();
+      for (const [secondsLeft, price] of [
+        [60, 15000],
+        [40, 15000],
+        [20, 13000],
+        [5, 13000],
+      ]) {
+        await recordSample(h, market, expiry - secondsLeft, price);
+      }
+
+      await setClock(h.context, expiry + 1);
+      await resolveMarketTwap(h, market);
+
+      //150 for 40 seconds and 130 for 20, a spot price at expiry would have resolved Lower
+      const marketData = await h.program.account.market.fetch(market.marketAddress);
+      assert.strictEqual(marketData.finalPrice.toString(), "143");
+      assert.deepEqual(marketData.outcome, { higher: {} });
+    });
+
+    it("Rejects a sample recorded too soon after the previous one", async () => {
+      const market = await createMarket(h, creator, twapParams, new anchor.BN(140), twapDuration);
+      const expiry = (await h.program.account.market.fetch(market.marketAddress)).expiryTimestamp.toNumber();
+      await recordSample(h, market, expiry - 60, 15000);
+      await expectAnchorError(recordSample(h, market, expiry - 59, 15000), "StalePriceSample");
+    });
+
+    it("Rejects a sample published outside the window", async () => {
+      const market = await createMarket(h, creator, twapParams, new anchor.BN(140), twapDuration);
+      const expiry = (await h.program.account.market.fetch(market.marketAddress)).expiryTimestamp.toNumber();
+      await expectAnchorError(recordSample(h, market, expiry - 61, 15000), "PriceUpdateTooEarly");
+    });
+
+    it("Refuses to resolve on samples that leave part of the window uncovered", async () => {
+      const market = await createMarket(h, creator, twapParams, new anchor.BN(140), twapDuration);
+      const expiry = (await h.program.account.market.fetch(market.marketAddress)).expiryTimestamp.toNumber();
+      //nothing was recorded for the 45 seconds before the last sample
+      for (const secondsLeft of [60, 50, 5]) {
+        await recordSample(h, market, expiry - secondsLeft, 15000);
+      }
+
+      await setClock(h.context, expiry + 1);
+      await expectAnchorError(resolveMarketTwap(h, market), "InsufficientPriceSamples");
+    });
+  });
+
+  describe("Positions", () => {
+    it("Claims both sides of a topped up position in one go", async () => {
+      const trader = Keypair.generate();
+      const loser = Keypair.generate();
+      await fundUser(h, trader);
+      await fundUser(h, loser);
+
+      const market = await createMarket(h, creator, resolutionParams);
+      await topUpPosition(h, market, trader, 60000, { higher: {} });
+      await topUpPosition(h, market, trader, 40000, { higher: {} });
+      await topUpPosition(h, market, trader, 50000, { lower: {} });
+      await placeBet(h, market, loser, 300000, { lower: {} });
+
+      const position = await h.program.account.position.fetch(positionAddress(h, market, trader));
+      assert.strictEqual(position.higherAmount.toString(), "100000");
+      assert.strictEqual(position.lowerAmount.toString(), "50000");
+      //the position counts as one open bet however often it was topped up
+      const { openBets } = await h.program.account.market.fetch(market.marketAddress);
+      assert.strictEqual(openBets.toString(), "2");
+
+      await resolveWithPrice(h, market, HIGHER_PRICE);
+      await claimPosition(h, market, trader);
+
+      //the lower stake is lost, the higher one wins 100000 * 1350000 / 1100000 less 1227 and 613 in fees
+      assert.strictEqual(
+        await tokenBalance(h.context, userAta(h, trader)),
+        TO_MINT - BigInt(150000) + BigInt(100000) + BigInt(122727) - BigInt(1227) - BigInt(613)
+      );
+      assert.isNull(
+        await h.program.account.position.fetchNullable(positionAddress(h, market, trader))
+      );
+      const marketData = await h.program.account.market.fetch(market.marketAddress);
+      assert.strictEqual(marketData.openBets.toString(), "1");
+      assert.strictEqual(marketData.creatorFees.toString(), "613");
+    });
+
+    it("Shares the stake left behind by a sold bet with the winners", async () => {
+      const seller = Keypair.generate();
+      const loser = Keypair.generate();
+      const winner = Keypair.generate();
+      await fundUser(h, seller);
+      await fundUser(h, loser);
+      await fundUser(h, winner);
+
+      const market = await createMarket(h, creator, resolutionParams);
+      const soldBet = await placeBet(h, market, seller, 100000, { higher: {} });
+      await placeBet(h, market, loser, 1000000, { lower: {} });
+
+      //the odds of higher halved, so the seller only gets 66666 of the 100000 back
+      await h.program.methods
+        .sellBet()
+        .accountsStrict({
+          market: market.marketAddress,
+          higherPool: market.higherPool,
+          lowerPool: market.lowerPool,
+          userAta: userAta(h, seller),
+          user: seller.publicKey,
+          bet: soldBet,
+          treasury: treasuryAddress(h),
+          treasuryVault: treasuryVaultAddress(h),
+          mint: h.mint,
+          tokenProgram: token.TOKEN_PROGRAM_ID,
+        })
+        .signers([seller])
+        .rpc();
+      let marketData = await h.program.account.market.fetch(market.marketAddress);
+      assert.strictEqual(marketData.higherForfeited.toString(), "33334");
+
+      const winningBet = await placeBet(h, market, winner, 100000, { higher: {} });
+      await resolveWithPrice(h, market, HIGHER_PRICE);
+      marketData = await h.program.account.market.fetch(market.marketAddress);
+      assert.strictEqual(marketData.higherPoolTotal.toString(), "1100000");
+      assert.strictEqual(marketData.lowerPoolTotal.toString(), "2000000");
+
+      await claimBet(h, market, winner, winningBet);
+
+      //100000 * (2000000 + 33334) / 1100000, 1% protocol and 0.5% creator fee on the winnings
+      const winnings = BigInt(184848);
+      assert.strictEqual(
+        await tokenBalance(h.context, userAta(h, winner)),
+        TO_MINT + winnings - BigInt(1848) - BigInt(924)
+      );
+
+      //what is left is the seed liquidity and its own share of the winnings, the forfeit is not
+      //left over for the creator to sweep
+      const seedShare = SEED_LIQUIDITY_AMOUNT.add(
+        SEED_LIQUIDITY_AMOUNT.muln(2033334).divn(1100000)
+      );
+      const residual =
+        (await tokenBalance(h.context, market.higherPool)) +
+        (await tokenBalance(h.context, market.lowerPool));
+      //plus 1 of rounding dust
+      assert.strictEqual(residual, BigInt(seedShare.toString()) + BigInt(1));
+    });
+
+    it("Refunds both sides of a position in a voided market", async () => {
+      const trader = Keypair.generate();
+      await fundUser(h, trader);
+
+      const market = await createMarket(h, creator, resolutionParams);
+      await topUpPosition(h, market, trader, 70000, { higher: {} });
+      await topUpPosition(h, market, trader, 30000, { lower: {} });
+
+      const { expiryTimestamp, resolutionWindow, voidGracePeriod } =
+        await h.program.account.market.fetch(market.marketAddress);
+      await setClock(
+        h.context,
+        expiryTimestamp.add(resolutionWindow).add(voidGracePeriod).toNumber() + 1
+      );
+      await h.program.methods
+        .voidMarket()
+        .accountsStrict({
+          market: market.marketAddress,
+          bondVault: null,
+          proposer: null,
+          proposerAta: null,
+          disputerAta: null,
+          mint: null,
+          caller: h.context.payer.publicKey,
+          tokenProgram: null,
+        })
+        .rpc();
+      await claimPosition(h, market, trader);
+
+      assert.strictEqual(await tokenBalance(h.context, userAta(h, trader)), TO_MINT);
+      const marketData = await h.program.account.market.fetch(market.marketAddress);
+      assert.deepEqual(marketData.status, { settled: {} });
+    });
+
+    it("Can not top up a position and open a bet in the same call", async () => {
+      const trader = Keypair.generate();
+      await fundUser(h, trader);
+
+      const market = await createMarket(h, creator, resolutionParams);
+      const bet = await nextBetAddress(h, market, trader);
+      await expectAnchorError(
+        h.program.methods
+          .placeBet(new anchor.BN(10000), { higher: {} })
+          .accountsStrict({
+            bet,
+            userPosition: userPositionAddress(h, market, trader),
+            position: positionAddress(h, market, trader),
+            market: market.marketAddress,
+            user: trader.publicKey,
+            higherPool: market.higherPool,
+            lowerPool: market.lowerPool,
+            userAta: userAta(h, trader),
+            mint: h.mint,
+            systemProgram: SystemProgram.programId,
+            tokenProgram: token.TOKEN_PROGRAM_ID,
+          })
+          .signers([trader])
+          .rpc(),
+        "InvalidBetAccounts"
+      );
+    });
+  });
+
+  describe("Scalar", () => {
+    it("Pays long and short tokens linearly between the bounds", async () => {
+      const holder = Keypair.generate();
+      await fundUser(h, holder);
+
+      //the range runs from 120 to 170, a final price of 150 pays 60% to long and 40% to short
+      const market = await createMarket(h, creator, {
+        ...resolutionParams,
+        outcomeTokens: true,
+        lowerBound: new anchor.BN(120),
+        upperBound: new anchor.BN(170),
+      });
+      await expectAnchorError(placeBet(h, market, holder, 10000, { higher: {} }), "WrongMarketKind");
+
+      const tokens = await initializeOutcomeMints(h, market, creator);
+      await mintCompleteSet(h, market, tokens, holder, 50000);
+      await resolveWithPrice(h, market, HIGHER_PRICE);
+
+      const before = await tokenBalance(h.context, userAta(h, holder));
+      await redeem(h, market, tokens, holder, 50000, { higher: {} });
+      const afterLong = await tokenBalance(h.context, userAta(h, holder));
+      assert.strictEqual(afterLong - before, BigInt(30000));
+
+      await redeem(h, market, tokens, holder, 50000, { lower: {} });
+      assert.strictEqual(await tokenBalance(h.context, userAta(h, holder)) - afterLong, BigInt(20000));
+
+      //the set collateral paid both sides and the seed liquidity of the creator was not touched
+      assert.strictEqual(await tokenBalance(h.context, tokens.setVault), BigInt(0));
+      assert.strictEqual(
+        await tokenBalance(h.context, market.higherPool),
+        BigInt(SEED_LIQUIDITY_AMOUNT.toString())
+      );
+      assert.strictEqual(
+        await tokenBalance(h.context, market.lowerPool),
+        BigInt(SEED_LIQUIDITY_AMOUNT.toString())
+      );
+    });
+
+    it("Pays the short side in full below the lower bound", async () => {
+      const holder = Keypair.generate();
+      await fundUser(h, holder);
+
+      const market = await createMarket(h, creator, {
+        ...resolutionParams,
+        outcomeTokens: true,
+        lowerBound: new anchor.BN(135),
+        upperBound: new anchor.BN(170),
+      });
+      const tokens = await initializeOutcomeMints(h, market, creator);
+      await mintCompleteSet(h, market, tokens, holder, 50000);
+      await resolveWithPrice(h, market, LOWER_PRICE);
+
+      await redeem(h, market, tokens, holder, 50000, { higher: {} });
+      await redeem(h, market, tokens, holder, 50000, { lower: {} });
+      assert.strictEqual(await tokenBalance(h.context, userAta(h, holder)), TO_MINT);
+    });
+
+    it("Rejects bounds that do not form a range", async () => {
+      await expectAnchorError(
+        createMarket(h, creator, {
+          ...resolutionParams,
+          outcomeTokens: true,
+          lowerBound: new anchor.BN(170),
+          upperBound: new anchor.BN(120),
+        }),
+        "InvalidScalarBounds"
+      );
+    });
+  });
+
+  describe("Finalize", () => {
+    it("Sweeps the unclaimed pools to the creator once fees are withdrawn and the lock period is over", async () => {
+      const winner = Keypair.generate();
+      const loser = Keypair.generate();
+      await fundUser(h, winner);
+      await fundUser(h, loser);
+
+      const market = await createMarket(h, creator, resolutionParams);
+      const winningBet = await placeBet(h, market, winner, 100000, { higher: {} });
+      await placeBet(h, market, loser, 300000, { lower: {} });
+      await resolveWithPrice(h, market, HIGHER_PRICE);
+      await claimBet(h, market, winner, winningBet);
+
+      await expectAnchorError(finalizeMarket(h, market, creator), "UnwithdrawnCreatorFees");
+
+      await h.program.methods
+        .withdrawCreatorFees()
+        .accountsStrict({
+          market: market.marketAddress,
+          treasury: treasuryAddress(h),
+          treasuryVault: treasuryVaultAddress(h),
+          creatorAta: userAta(h, creator),
+          creator: creator.publicKey,
+          mint: h.mint,
+          tokenProgram: token.TOKEN_PROGRAM_ID,
+        })
+        .signers([creator])
+        .rpc();
+
+      //the losing bet was never claimed, so the market is not settled until the lock period is over
+      await expectAnchorError(finalizeMarket(h, market, creator), "MarketLockPeriodNotOver");
+
+      await passLockPeriod(h, market);
+      const pools =
+        (await tokenBalance(h.context, market.higherPool)) +
+        (await tokenBalance(h.context, market.lowerPool));
+      const creatorBefore = await tokenBalance(h.context, userAta(h, creator));
+      await finalizeMarket(h, market, creator);
+
+      assert.strictEqual(await tokenBalance(h.context, userAta(h, creator)), creatorBefore + pools);
+      assert.isNull(await h.program.account.market.fetchNullable(market.marketAddress));
+      assert.isNull(await h.context.banksClient.getAccount(market.higherPool));
+      assert.isNull(await h.context.banksClient.getAccount(market.lowerPool));
+    });
+
+    it("Closes the set vault when a market with outcome tokens is cancelled", async () => {
+      const holder = Keypair.generate();
+      await fundUser(h, holder);
+
+      const market = await createMarket(h, creator, {
+        ...resolutionParams,
+        pricingMode: { cpmm: {} },
+        outcomeTokens: true,
+      });
+      const tokens = await initializeOutcomeMints(h, market, creator);
+      await mintCompleteSet(h, market, tokens, holder, 50000);
+      await h.program.methods
+        .mergeCompleteSet(new anchor.BN(50000))
+        .accountsStrict({
+          market: market.marketAddress,
+          setVault: tokens.setVault,
+          higherMint: tokens.higherMint,
+          lowerMint: tokens.lowerMint,
+          userHigherAta: token.getAssociatedTokenAddressSync(tokens.higherMint, holder.publicKey),
+          userLowerAta: token.getAssociatedTokenAddressSync(tokens.lowerMint, holder.publicKey),
+          userAta: userAta(h, holder),
+          user: holder.publicKey,
+          mint: h.mint,
+          tokenProgram: token.TOKEN_PROGRAM_ID,
+        })
+        .signers([holder])
+        .rpc();
+
+      const cancel = (setVault: PublicKey | null) =>
+        h.program.methods
+          .cancelMarket()
+          .accountsStrict({
+            market: market.marketAddress,
+            marketCreator: creator.publicKey,
+            higherPool: market.higherPool,
+            lowerPool: market.lowerPool,
+            setVault,
+            creatorAta: userAta(h, creator),
+            mint: h.mint,
+            systemProgram: SystemProgram.programId,
+            tokenProgram: token.TOKEN_PROGRAM_ID,
+          })
+          .signers([creator])
+          .rpc();
+      await expectAnchorError(cancel(null), "MissingSetVault");
+      await cancel(tokens.setVault);
+
+      assert.strictEqual(await tokenBalance(h.context, userAta(h, holder)), TO_MINT);
+      assert.isNull(await h.program.account.market.fetchNullable(market.marketAddress));
+      assert.isNull(await h.context.banksClient.getAccount(tokens.setVault));
+    });
+
+    it("Sweeps the collateral of unredeemed complete sets with the pools", async () => {
+      const holder = Keypair.generate();
+      await fundUser(h, holder);
+
+      const market = await createMarket(h, creator, {
+        ...resolutionParams,
+        pricingMode: { cpmm: {} },
+        outcomeTokens: true,
+      });
+      const tokens = await initializeOutcomeMints(h, market, creator);
+      const { setVault } = tokens;
+      await mintCompleteSet(h, market, tokens, holder, 50000);
+
+      await resolveWithPrice(h, market, LOWER_PRICE);
+      //no bet is open, but the sets are still outstanding so the lock period applies
+      await expectAnchorError(finalizeMarket(h, market, creator, setVault), "MarketLockPeriodNotOver");
+
+      await passLockPeriod(h, market);
+      const creatorBefore = await tokenBalance(h.context, userAta(h, creator));
+      await finalizeMarket(h, market, creator, setVault);
+
+      assert.strictEqual(
+        await tokenBalance(h.context, userAta(h, creator)),
+        creatorBefore + BigInt(SEED_LIQUIDITY_AMOUNT.muln(2).toString()) + BigInt(50000)
+      );
+      assert.isNull(await h.context.banksClient.getAccount(setVault));
+    });
+  });
+});
+
+async function writeConfig(h: Harness, params: typeof configParams) {
+  const [configAddress] = PublicKey.findProgramAddressSync(
+    [anchor.utils.bytes.utf8.encode(CONFIG_SEED)],
+    h.program.programId
+  );
+  //bankrun deploys the program without a program data account, so the config initialize_config
+  //would create is written directly with the payer as admin
+  const data = await h.program.coder.accounts.encode("programConfig", {
+    admin: h.context.payer.publicKey,
+    pendingAdmin: null,
+    ...params,
+    bump: PublicKey.findProgramAddressSync(
+      [anchor.utils.bytes.utf8.encode(CONFIG_SEED)],
+      h.program.programId
+    )[1],
+  });
+  h.context.setAccount(configAddress, {
+    lamports: 1_000_000_000,
+    data,
+    owner: h.program.programId,
+    executable: false,
+  });
+}
+
+async function createMint(context: ProgramTestContext) {
+  const mint = Keypair.generate().publicKey;
+  const data = Buffer.alloc(token.MINT_SIZE);
+  token.MintLayout.encode(
+    {
+      mintAuthorityOption: 1,
+      mintAuthority: context.payer.publicKey,
+      supply: BigInt(0),
+      decimals: 6,
+      isInitialized: true,
+      freezeAuthorityOption: 0,
+      freezeAuthority: PublicKey.default,
+    },
+    data
+  );
+  context.setAccount(mint, {
+    lamports: 1_000_000_000,
+    data,
+    owner: token.TOKEN_PROGRAM_ID,
+    executable: false,
+  });
+  return mint;
+}
+
+//gives the user sol for rent and TO_MINT tokens in their associated token account
+async function fundUser(h: Harness, user: Keypair) {
+  h.context.setAccount(user.publicKey, {
+    lamports: 10_000_000_000,
+    data: Buffer.alloc(0),
+    owner: SystemProgram.programId,
+    executable: false,
+  });
+  const data = Buffer.alloc(token.ACCOUNT_SIZE);
+  token.AccountLayout.encode(
+    {
+      mint: h.mint,
+      owner: user.publicKey,
+      amount: TO_MINT,
+      delegateOption: 0,
+      delegate: PublicKey.default,
+      state: token.AccountState.Initialized,
+      isNativeOption: 0,
+      isNative: BigInt(0),
+      delegatedAmount: BigInt(0),
+      closeAuthorityOption: 0,
+      closeAuthority: PublicKey.default,
+    },
+    data
+  );
+  h.context.setAccount(userAta(h, user), {
+    lamports: 1_000_000_000,
+    data,
+    owner: token.TOKEN_PROGRAM_ID,
+    executable: false,
+  });
+}
+
+async function tokenBalance(context: ProgramTestContext, address: PublicKey) {
+  const account = await context.banksClient.getAccount(address);
+  return token.AccountLayout.decode(account.data).amount;
+}
+
+async function setClock(context: ProgramTestContext, unixTimestamp: number, slot?: bigint) {
+  const clock = await context.banksClient.getClock();
+  context.setClock(
+    new Clock(
+      slot ?? clock.slot,
+      clock.epochStartTimestamp,
+      clock.epoch,
+      clock.leaderScheduleEpoch,
+      BigInt(unixTimestamp)
+    )
+  );
+}
+
+//writes a fully verified PriceUpdateV2 owned by the pyth receiver
+async function writePriceUpdate(
+  context: ProgramTestContext,
+  address: PublicKey,
+  feedId: Buffer,
+  update: {
+    price: number;
+    conf: number;
+    exponent: number;
+    publishTime: number;
+    prevPublishTime: number;
+  }
+) {
+  const data = Buffer.alloc(134);
+  crypto.createHash("sha256").update("account:PriceUpdateV2").digest().copy(data, 0, 0, 8);
+  //write_authority is left zeroed, verification_level Full is variant 1
+  data.writeUInt8(1, 40);
+  feedId.copy(data, 41);
+  data.writeBigInt64LE(BigInt(update.price), 73);
+  data.writeBigUInt64LE(BigInt(update.conf), 81);
+  data.writeInt32LE(update.exponent, 89);
+  data.writeBigInt64LE(BigInt(update.publishTime), 93);
+  data.writeBigInt64LE(BigInt(update.prevPublishTime), 101);
+  data.writeBigInt64LE(BigInt(update.price), 109);
+  data.writeBigUInt64LE(BigInt(update.conf), 117);
+  context.setAccount(address, {
+    lamports: 1_000_000_000,
+    data,
+    owner: PYTH_RECEIVER,
+    executable: false,
+  });
+}
+
+//bankrun rejects a failed transaction with the program logs attached under a different key than
+//the rpc connection, so both are read before falling back to the custom error code in the message
+async function expectAnchorError(call: Promise<unknown>, code: string) {
+  try {
+    await call;
+  } catch (e) {
+    const logs = e.logs ?? e.meta?.logMessages ?? [];
+    const anchorErr = e instanceof anchor.AnchorError ? e : anchor.AnchorError.parse(logs);
+    if (anchorErr) {
+      assert.strictEqual(anchorErr.error.errorCode.code, code, "Unexpected Error Code");
+    } else {
+      const idlError = IDL.errors.find((error) => error.name.toLowerCase() === code.toLowerCase());
+      assert.include(String(e), "0x" + idlError.code.toString(16), "Unexpected Error Code");
+    }
+    return;
+  }
+  assert.fail(`expected the call to fail with ${code}`);
+}
+
+async function allowMint(h: Harness) {
+  await h.program.methods
+    .addAllowedMint(new anchor.BN(1), new anchor.BN(1000000000), SEED_LIQUIDITY_AMOUNT)
+    .accountsStrict({
+      config: configAddress(h),
+      allowedMint: allowedMintAddress(h),
+      mint: h.mint,
+      admin: h.context.payer.publicKey,
+      systemProgram: SystemProgram.programId,
+    })
+    .rpc();
+
+  await h.program.methods
+    .initializeTreasury()
+    .accountsStrict({
+      config: configAddress(h),
+      treasury: treasuryAddress(h),
+      treasuryVault: treasuryVaultAddress(h),
+      mint: h.mint,
+      admin: h.context.payer.publicKey,
+      systemProgram: SystemProgram.programId,
+      tokenProgram: token.TOKEN_PROGRAM_ID,
+    })
+    .rpc();
+}
+
+//creates and funds a market on a fresh feed id, betting is open for 100 slots
+async function createMarket(
+  h: Harness,
+  marketCreator: Keypair,
+  params: any,
+  targetPrice = new anchor.BN(140),
+  duration = new anchor.BN(100)
+): Promise<MarketAccounts> {
+  const feedId = crypto.randomBytes(32);
+  const feedIdHex = "0x" + feedId.toString("hex");
+  const [marketAddress] = PublicKey.findProgramAddressSync(
+    [
+      marketCreator.publicKey.toBuffer(),
+      crypto.createHash("sha256").update(feedIdHex, "utf-8").digest(),
+      targetPrice.toArrayLike(Buffer, "le", 8),
+      duration.toArrayLike(Buffer, "le", 8),
+    ],
+    h.program.programId
+  );
+  const [higherPool] = PublicKey.findProgramAddressSync(
+    [anchor.utils.bytes.utf8.encode(HIGHER_POOL_SEED), marketAddress.toBuffer()],
+    h.program.programId
+  );
+  const [lowerPool] = PublicKey.findProgramAddressSync(
+    [anchor.utils.bytes.utf8.encode(LOWER_POOL_SEED), marketAddress.toBuffer()],
+    h.program.programId
+  );
+
+  await h.program.methods
+    .initializeMarket(targetPrice, feedIdHex, duration, params)
+    .accountsStrict({
+      market: marketAddress,
+      config: configAddress(h),
+      marketCreator: marketCreator.publicKey,
+      systemProgram: SystemProgram.programId,
+    })
+    .signers([marketCreator])
+    .rpc();
+
+  await h.program.methods
+    .initializePools()
+    .accountsStrict({
+      market: marketAddress,
+      allowedMint: allowedMintAddress(h),
+      treasury: treasuryAddress(h),
+      higherPool,
+      lowerPool,
+      userAta: userAta(h, marketCreator),
+      poolTokenMint: h.mint,
+      marketCreator: marketCreator.publicKey,
+      systemProgram: SystemProgram.programId,
+      tokenProgram: token.TOKEN_PROGRAM_ID,
+    })
+    .signers([marketCreator])
+    .rpc();
+
+  return { marketAddress, higherPool, lowerPool, feedId };
+}
+
+//the next bet id of the user is read from their position, so one user can bet several times
+async function nextBetAddress(h: Harness, market: MarketAccounts, user: Keypair) {
+  const position = await h.program.account.userMarketPosition.fetchNullable(
+    userPositionAddress(h, market, user)
+  );
+  const betId = position ? position.nextBetId : new anchor.BN(0);
+  return PublicKey.findProgramAddressSync(
+    [
+      anchor.utils.bytes.utf8.encode(BET_SEED),
+      user.publicKey.toBuffer(),
+      market.marketAddress.toBuffer(),
+      betId.toArrayLike(Buffer, "le", 8),
+    ],
+    h.program.programId
+  )[0];
+}
+
+async function placeBet(
+  h: Harness,
+  market: MarketAccounts,
+  user: Keypair,
+  amount: number,
+  direction: any
+) {
+  const bet = await nextBetAddress(h, market, user);
+  await h.program.methods
+    .placeBet(new anchor.BN(amount), direction)
+    .accountsStrict({
+      bet,
+      userPosition: userPositionAddress(h, market, user),
+      position: null,
+      market: market.marketAddress,
+      user: user.publicKey,
+      higherPool: market.higherPool,
+      lowerPool: market.lowerPool,
+      userAta: userAta(h, user),
+      mint: h.mint,
+      systemProgram: SystemProgram.programId,
+      tokenProgram: token.TOKEN_PROGRAM_ID,
+    })
+    .signers([user])
+    .rpc();
+  return bet;
+}
+
+async function buyShares(
+  h: Harness,
+  market: MarketAccounts,
+  user: Keypair,
+  amount: number,
+  direction: any
+) {
+  const bet = await nextBetAddress(h, market, user);
+  await h.program.methods
+    .buyShares(new anchor.BN(amount), direction, new anchor.BN(0))
+    .accountsStrict({
+      market: market.marketAddress,
+      higherPool: market.higherPool,
+      lowerPool: market.lowerPool,
+      userAta: userAta(h, user),
+      user: user.publicKey,
+      userPosition: userPositionAddress(h, market, user),
+      bet,
+      systemProgram: SystemProgram.programId,
+      mint: h.mint,
+      tokenProgram: token.TOKEN_PROGRAM_ID,
+    })
+    .signers([user])
+    .rpc();
+  return bet;
+}
+
+//tops up the aggregated position of the user, no Bet is created
+async function topUpPosition(
+  h: Harness,
+  market: MarketAccounts,
+  user: Keypair,
+  amount: number,
+  direction: any
+) {
+  await h.program.methods
+    .placeBet(new anchor.BN(amount), direction)
+    .accountsStrict({
+      bet: null,
+      userPosition: null,
+      position: positionAddress(h, market, user),
+      market: market.marketAddress,
+      user: user.publicKey,
+      higherPool: market.higherPool,
+      lowerPool: market.lowerPool,
+      userAta: userAta(h, user),
+      mint: h.mint,
+      systemProgram: SystemProgram.programId,
+      tokenProgram: token.TOKEN_PROGRAM_ID,
+    })
+    .signers([user])
+    .rpc();
+}
+
+async function claimPosition(h: Harness, market: MarketAccounts, user: Keypair) {
+  await h.program.methods
+    .claimPosition()
+    .accountsStrict({
+      market: market.marketAddress,
+      higherPool: market.higherPool,
+      lowerPool: market.lowerPool,
+      userAta: userAta(h, user),
+      user: user.publicKey,
+      position: positionAddress(h, market, user),
+      treasury: treasuryAddress(h),
+      treasuryVault: treasuryVaultAddress(h),
+      systemProgram: SystemProgram.programId,
+      mint: h.mint,
+      tokenProgram: token.TOKEN_PROGRAM_ID,
+    })
+    .signers([user])
+    .rpc();
+}
+
+function resolveMarket(
+  h: Harness,
+  market: MarketAccounts,
+  priceFeed: PublicKey,
+  secondaryFeeds: PublicKey[] = []
+) {
+  return h.program.methods
+    .resolveMarket()
+    .accountsStrict({
+      market: market.marketAddress,
+      higherPool: market.higherPool,
+      lowerPool: market.lowerPool,
+      priceFeed,
+      resolver: h.context.payer.publicKey,
+    })
+    .remainingAccounts(
+      secondaryFeeds.map((pubkey) => ({ pubkey, isWritable: false, isSigner: false }))
+    )
+    .rpc();
+}
+
+//records a price of the market feed published at `publishTime`, with the clock just after it
+async function recordSample(
+  h: Harness,
+  market: MarketAccounts,
+  publishTime: number,
+  price: number
+) {
+  await setClock(h.context, publishTime + 1);
+  const priceUpdate = Keypair.generate().publicKey;
+  await writePriceUpdate(h.context, priceUpdate, market.feedId, {
+    price,
+    conf: 10,
+    exponent: -2,
+    publishTime,
+    prevPublishTime: publishTime - 1,
+  });
+  await h.program.methods
+    .recordPriceSample()
+    .accountsStrict({
+      market: market.marketAddress,
+      priceHistory: priceHistoryAddress(h, market),
+      priceFeed: priceUpdate,
+      cranker: h.context.payer.publicKey,
+      systemProgram: SystemProgram.programId,
+    })
+    .rpc();
+}
+
+function resolveMarketTwap(h: Harness, market: MarketAccounts) {
+  return h.program.methods
+    .resolveMarketTwap()
+    .accountsStrict({
+      market: market.marketAddress,
+      higherPool: market.higherPool,
+      lowerPool: market.lowerPool,
+      priceHistory: priceHistoryAddress(h, market),
+      resolver: h.context.payer.publicKey,
+    })
+    .rpc();
+}
+
+type Price = { price: number; conf: number; exponent: number };
+
+//moves the clock past expiry and writes the first price of `feedId` published after it
+async function writeExpiryPrice(
+  h: Harness,
+  market: MarketAccounts,
+  price: Price,
+  feedId = market.feedId
+) {
+  const { expiryTimestamp } = await h.program.account.market.fetch(market.marketAddress);
+  const expiry = expiryTimestamp.toNumber();
+  await setClock(h.context, expiry + 5);
+
+  const priceUpdate = Keypair.generate().publicKey;
+  await writePriceUpdate(h.context, priceUpdate, feedId, {
+    ...price,
+    publishTime: expiry + 1,
+    prevPublishTime: expiry - 1,
+  });
+  return priceUpdate;
+}
+
+async function resolveWithPrice(h: Harness, market: MarketAccounts, price: Price) {
+  await resolveMarket(h, market, await writeExpiryPrice(h, market, price));
+}
+
+async function claimBet(h: Harness, market: MarketAccounts, user: Keypair, bet: PublicKey) {
+  await h.program.methods
+    .claimBet()
+    .accountsStrict({
+      market: market.marketAddress,
+      higherPool: market.higherPool,
+      lowerPool: market.lowerPool,
+      userAta: userAta(h, user),
+      user: user.publicKey,
+      bet,
+      treasury: treasuryAddress(h),
+      treasuryVault: treasuryVaultAddress(h),
+      systemProgram: SystemProgram.programId,
+      mint: h.mint,
+      tokenProgram: token.TOKEN_PROGRAM_ID,
+    })
+    .signers([user])
+    .rpc();
+}
+
+//bankrun keeps the blockhash until the slot moves, so a retried finalize is given a distinct compute
+//limit to not be dropped as an already processed transaction
+let finalizeAttempts = 0;
+
+type OutcomeTokens = {
+  higherMint: PublicKey;
+  lowerMint: PublicKey;
+  setVault: PublicKey;
+};
+
+async function initializeOutcomeMints(
+  h: Harness,
+  market: MarketAccounts,
+  marketCreator: Keypair
+): Promise<OutcomeTokens> {
+  const [higherMint, lowerMint, setVault] = [HIGHER_MINT_SEED, LOWER_MINT_SEED, SET_VAULT_SEED].map(
+    (seed) =>
+      PublicKey.findProgramAddressSync(
+        [anchor.utils.bytes.utf8.encode(seed), market.marketAddress.toBuffer()],
+        h.program.programId
+      )[0]
+  );
+  await h.program.methods
+    .initializeOutcomeMints()
+    .accountsStrict({
+      market: market.marketAddress,
+      higherMint,
+      lowerMint,
+      setVault,
+      mint: h.mint,
+      marketCreator: marketCreator.publicKey,
+      systemProgram: SystemProgram.programId,
+      tokenProgram: token.TOKEN_PROGRAM_ID,
+    })
+    .signers([marketCreator])
+    .rpc();
+  return { higherMint, lowerMint, setVault };
+}
+
+async function mintCompleteSet(
+  h: Harness,
+  market: MarketAccounts,
+  tokens: OutcomeTokens,
+  user: Keypair,
+  amount: number
+) {
+  await h.program.methods
+    .mintCompleteSet(new anchor.BN(amount))
+    .accountsStrict({
+      market: market.marketAddress,
+      setVault: tokens.setVault,
+      higherMint: tokens.higherMint,
+      lowerMint: tokens.lowerMint,
+      userHigherAta: token.getAssociatedTokenAddressSync(tokens.higherMint, user.publicKey),
+      userLowerAta: token.getAssociatedTokenAddressSync(tokens.lowerMint, user.publicKey),
+      userAta: userAta(h, user),
+      user: user.publicKey,
+      mint: h.mint,
+      systemProgram: SystemProgram.programId,
+      tokenProgram: token.TOKEN_PROGRAM_ID,
+      associatedTokenProgram: token.ASSOCIATED_TOKEN_PROGRAM_ID,
+    })
+    .signers([user])
+    .rpc();
+}
+
+async function redeem(
+  h: Harness,
+  market: MarketAccounts,
+  tokens: OutcomeTokens,
+  user: Keypair,
+  amount: number,
+  direction: any
+) {
+  const outcomeMint = direction.higher ? tokens.higherMint : tokens.lowerMint;
+  await h.program.methods
+    .redeem(new anchor.BN(amount), direction)
+    .accountsStrict({
+      market: market.marketAddress,
+      higherPool: market.higherPool,
+      lowerPool: market.lowerPool,
+      setVault: tokens.setVault,
+      outcomeMint,
+      userOutcomeAta: token.getAssociatedTokenAddressSync(outcomeMint, user.publicKey),
+      userAta: userAta(h, user),
+      user: user.publicKey,
+      mint: h.mint,
+      tokenProgram: token.TOKEN_PROGRAM_ID,
+    })
+    .signers([user])
+    .rpc();
+}
+
+function finalizeMarket(
+  h: Harness,
+  market: MarketAccounts,
+  marketCreator: Keypair,
+  setVault: PublicKey | null = null
+) {
+  finalizeAttempts += 1;
+  return h.program.methods
+    .finalizeMarket()
+    .accountsStrict({
+      market: market.marketAddress,
+      config: configAddress(h),
+      higherPool: market.higherPool,
+      lowerPool: market.lowerPool,
+      setVault,
+      creatorAta: userAta(h, marketCreator),
+      marketCreator: marketCreator.publicKey,
+      systemProgram: SystemProgram.programId,
+      mint: h.mint,
+      tokenProgram: token.TOKEN_PROGRAM_ID,
+    })
+    .preInstructions([
+      ComputeBudgetProgram.setComputeUnitLimit({ units: 400000 + finalizeAttempts }),
+    ])
+    .signers([marketCreator])
+    .rpc();
+}
+
+//the lock period is counted in slots from the slot the market was created in
+async function passLockPeriod(h: Harness, market: MarketAccounts) {
+  const { startTime, marketDuration } = await h.program.account.market.fetch(market.marketAddress);
+  const clock = await h.context.banksClient.getClock();
+  const lockEnd = startTime.add(marketDuration).add(configParams.marketLockPeriod);
+  await setClock(h.context, Number(clock.unixTimestamp), BigInt(lockEnd.toString()) + BigInt(1));
+}
+
+function configAddress(h: Harness) {
+  return PublicKey.findProgramAddressSync(
+    [anchor.utils.bytes.utf8.encode(CONFIG_SEED)],
+    h.program.programId
+  )[0];
+}
+
+function allowedMintAddress(h: Harness) {
+  return PublicKey.findProgramAddressSync(
+    [anchor.utils.bytes.utf8.encode(ALLOWED_MINT_SEED), h.mint.toBuffer()],
+    h.program.programId
+  )[0];
+}
+
+function treasuryAddress(h: Harness) {
+  return PublicKey.findProgramAddressSync(
+    [anchor.utils.bytes.utf8.encode(TREASURY_SEED), h.mint.toBuffer()],
+    h.program.programId
+  )[0];
+}
+
+function treasuryVaultAddress(h: Harness) {
+  return PublicKey.findProgramAddressSync(
+    [anchor.utils.bytes.utf8.encode(TREASURY_VAULT_SEED), h.mint.toBuffer()],
+    h.program.programId
+  )[0];
+}
+
+function userPositionAddress(h: Harness, market: MarketAccounts, user: Keypair) {
+  return PublicKey.findProgramAddressSync(
+    [
+      anchor.utils.bytes.utf8.encode(USER_POSITION_SEED),
+      user.publicKey.toBuffer(),
+      market.marketAddress.toBuffer(),
+    ],
+    h.program.programId
+  )[0];
+}
+
+function priceHistoryAddress(h: Harness, market: MarketAccounts) {
+  return PublicKey.findProgramAddressSync(
+    [anchor.utils.bytes.utf8.encode(PRICE_HISTORY_SEED), market.marketAddress.toBuffer()],
+    h.program.programId
+  )[0];
+}
+
+function positionAddress(h: Harness, market: MarketAccounts, user: Keypair) {
+  return PublicKey.findProgramAddressSync(
+    [
+      anchor.utils.bytes.utf8.encode(POSITION_SEED),
+      user.publicKey.toBuffer(),
+      market.marketAddress.toBuffer(),
+    ],
+    h.program.programId
+  )[0];
+}
+
+function userAta(h: Harness, user: Keypair) {
+  return token.getAssociatedTokenAddressSync(h.mint, user.publicKey);
+}
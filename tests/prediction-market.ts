@@ -7,9 +7,21 @@ import crypto, { generateKey, getCipherInfo, Sign } from "crypto";
 import * as token from "@solana/spl-token";
 import { PythSolanaReceiver } from "@pythnetwork/pyth-solana-receiver";
 
-const BET_SEED = "bet";
+const BET_SEED = "prediction_bet";
+const USER_POSITION_SEED = "user_position";
+const POSITION_SEED = "position";
+const CONFIG_SEED = "config";
+const ALLOWED_MINT_SEED = "allowed_mint";
+const SEED_LIQUIDITY_AMOUNT = new anchor.BN(1000000);
 const HIGHER_POOL_SEED = "higher_pool";
 const LOWER_POOL_SEED = "lower_pool";
+const TREASURY_SEED = "treasury";
+const TREASURY_VAULT_SEED = "treasury_vault";
+const BOND_VAULT_SEED = "bond_vault";
+const OUTCOME_POOL_SEED = "outcome_pool";
+const HIGHER_MINT_SEED = "higher_mint";
+const LOWER_MINT_SEED = "lower_mint";
+const SET_VAULT_SEED = "set_vault";
 const USDC_MINT = "4zMMC9srt5Ri5X14GAgXhaHii3GnPAEERYPJgZJDncDU"; // Example USDC Mint address
 
 describe("prediction_market", () => {
@@ -25,6 +37,26 @@ describe("prediction_market", () => {
 
   const targetPrice: anchor.BN = new anchor.BN(140);
   const marketDuration: anchor.BN = new anchor.BN(1300);
+  const resolutionParams = {
+    resolutionWindow: new anchor.BN(60),
+    tieBand: new anchor.BN(0),
+    maxConfBps: 100,
+    targetExponent: 0,
+    oracleSource: { pyth: {} },
+    secondaryFeeds: [],
+    oracleQuorum: 1,
+    resolutionMode: { spot: {} },
+    twapWindow: new anchor.BN(0),
+    committee: [],
+    committeeThreshold: 0,
+    bondAmount: new anchor.BN(0),
+    challengePeriod: new anchor.BN(0),
+    outcomeBounds: [],
+    pricingMode: { parimutuel: {} },
+    outcomeTokens: false,
+    lowerBound: new anchor.BN(0),
+    upperBound: new anchor.BN(0),
+  };
   const marketCreator1 = anchor.web3.Keypair.generate();
 
   const hema = anchor.web3.Keypair.generate();
@@ -32,9 +64,106 @@ describe("prediction_market", () => {
 
   const to_mint = new anchor.BN(30000000);
 
-  const INITIAL_USDC_AMOUNT = program.idl.constants.find(
-    (el) => el.name == "initialUsdcPoolAmount"
-  ).value;
+  const configParams = {
+    protocolFeeBps: 100,
+    creatorFeeBps: 50,
+    exitFeeBps: 200,
+    minMarketDuration: new anchor.BN(1200),
+    marketLockPeriod: new anchor.BN(576000),
+    maxResolutionWindow: new anchor.BN(3600),
+    voidGracePeriod: new anchor.BN(86400),
+  };
+
+  const [configAddress] = PublicKey.findProgramAddressSync(
+    [anchor.utils.bytes.utf8.encode(CONFIG_SEED)],
+    program.programId
+  );
+
+  describe("Config Initialization", () => {
+    it("Initializes the program config", async () => {
+      const [programDataAddress] = PublicKey.findProgramAddressSync(
+        [program.programId.toBuffer()],
+        new PublicKey("BPFLoaderUpgradeab1e11111111111111111111111")
+      );
+
+      await program.methods
+        .initializeConfig(configParams)
+        .accountsStrict({
+          config: configAddress,
+          program: program.programId,
+          programData: programDataAddress,
+          admin: provider.wallet.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .rpc()
+        .then(confirmTx);
+
+      const config = await program.account.programConfig.fetch(configAddress);
+      assert.strictEqual(
+        config.admin.toString(),
+        provider.wallet.publicKey.toString()
+      );
+      assert.strictEqual(config.protocolFeeBps, 100);
+      assert.strictEqual(config.creatorFeeBps, 50);
+      assert.strictEqual(config.exitFeeBps, 200);
+      assert.strictEqual(
+        config.minMarketDuration.toString(),
+        configParams.minMarketDuration.toString()
+      );
+    });
+
+    it("Transfers the admin in two steps", async () => {
+      const newAdmin = anchor.web3.Keypair.generate();
+
+      await program.methods
+        .proposeAdmin(newAdmin.publicKey)
+        .accountsStrict({
+          config: configAddress,
+          admin: provider.wallet.publicKey,
+        })
+        .rpc()
+        .then(confirmTx);
+
+      let config = await program.account.programConfig.fetch(configAddress);
+      assert.strictEqual(
+        config.admin.toString(),
+        provider.wallet.publicKey.toString()
+      );
+
+      await program.methods
+        .acceptAdmin()
+        .accountsStrict({
+          config: configAddress,
+          newAdmin: newAdmin.publicKey,
+        })
+        .signers([newAdmin])
+        .rpc()
+        .then(confirmTx);
+
+      config = await program.account.programConfig.fetch(configAddress);
+      assert.strictEqual(config.admin.toString(), newAdmin.publicKey.toString());
+      assert.isNull(config.pendingAdmin);
+
+      // hand it back so the rest of the suite can keep using the provider wallet
+      await program.methods
+        .proposeAdmin(provider.wallet.publicKey)
+        .accountsStrict({
+          config: configAddress,
+          admin: newAdmin.publicKey,
+        })
+        .signers([newAdmin])
+        .rpc()
+        .then(confirmTx);
+      await program.methods
+        .acceptAdmin()
+        .accountsStrict({
+          config: configAddress,
+          newAdmin: provider.wallet.publicKey,
+        })
+        .rpc()
+        .then(confirmTx);
+    });
+  });
 
   describe("Market Initialization", () => {
     it("Initializes a market", async () => {
@@ -49,9 +178,10 @@ describe("prediction_market", () => {
       );
 
       await program.methods
-        .initializeMarket(targetPrice, feedIdString, marketDuration)
+        .initializeMarket(targetPrice, feedIdString, marketDuration, resolutionParams)
         .accountsStrict({
           market: marketAddress,
+          config: configAddress,
           marketCreator: marketCreator1.publicKey,
           systemProgram: anchor.web3.SystemProgram.programId,
         })
@@ -67,27 +197,75 @@ describe("prediction_market", () => {
         targetPrice,
         marketDuration,
         marketBump,
-        { initializedMarket: {} }
+        { created: {} }
       );
     });
-    it("Can not initialize with invalid FeedId", async () => {
-      await airdrop(provider.connection, marketCreator1.publicKey);
+    it("Initializes a TWAP market on a Switchboard feed", async () => {
+      //switchboard markets use the pull feed address as their feed id
+      const switchboardFeed = anchor.web3.Keypair.generate().publicKey;
+      const switchboardFeedId = "0x" + switchboardFeed.toBuffer().toString("hex");
+
+      const [marketAddress, marketBump] = getMarketAddress(
+        marketCreator1.publicKey,
+        switchboardFeedId,
+        targetPrice,
+        marketDuration,
+        program.programId
+      );
+
+      await program.methods
+        .initializeMarket(targetPrice, switchboardFeedId, marketDuration, {
+          ...resolutionParams,
+          oracleSource: { switchboard: {} },
+          resolutionMode: { twap: {} },
+          twapWindow: new anchor.BN(300),
+        })
+        .accountsStrict({
+          market: marketAddress,
+          config: configAddress,
+          marketCreator: marketCreator1.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([marketCreator1])
+        .rpc()
+        .then(confirmTx);
+
+      await checkMarket(
+        program,
+        marketAddress,
+        marketCreator1.publicKey,
+        switchboardFeedId,
+        targetPrice,
+        marketDuration,
+        marketBump,
+        { created: {} }
+      );
+      const marketData = await program.account.market.fetch(marketAddress);
+      assert.deepEqual(marketData.oracleSource, { switchboard: {} });
+    });
+    it("Rejects spot resolution on a Switchboard feed", async () => {
+      const switchboardFeed = anchor.web3.Keypair.generate().publicKey;
+      const switchboardFeedId = "0x" + switchboardFeed.toBuffer().toString("hex");
+
+      const [marketAddress] = getMarketAddress(
+        marketCreator1.publicKey,
+        switchboardFeedId,
+        targetPrice,
+        marketDuration,
+        program.programId
+      );
 
       let should_fail = "This Should Fail";
       try {
-        const [marketAddress, marketBump] = getMarketAddress(
-          marketCreator1.publicKey,
-          feedIdString2,
-          targetPrice,
-          marketDuration,
-          program.programId
-        );
-
         await program.methods
-          .initializeMarket(targetPrice, feedIdString2, marketDuration)
+          .initializeMarket(targetPrice, switchboardFeedId, marketDuration, {
+            ...resolutionParams,
+            oracleSource: { switchboard: {} },
+          })
           .accountsStrict({
-            marketCreator: marketCreator1.publicKey,
             market: marketAddress,
+            config: configAddress,
+            marketCreator: marketCreator1.publicKey,
             systemProgram: anchor.web3.SystemProgram.programId,
           })
           .signers([marketCreator1])
@@ -97,203 +275,425 @@ describe("prediction_market", () => {
         const anchorErr = anchor.AnchorError.parse(e.logs);
         assert.strictEqual(
           anchorErr.error.errorCode.code,
-          "IncorrectFeedIDLength",
+          "UnsupportedOracleSource",
           "Unexpected Error Code"
         );
         should_fail = "Failed";
       }
       assert.strictEqual(should_fail, "Failed");
     });
-  });
-  describe("Pool Initialization", () => {
-    it("Initialize pool mint and token accounts", async () => {
-      await airdrop(provider.connection, marketCreator1.publicKey);
-      await airdrop(provider.connection, mint_authority.publicKey);
-
-      const mint = await token.createMint(
-        provider.connection,
-        mint_authority,
-        mint_authority.publicKey,
-        null,
-        6
-      );
-
-      const userAta = await token.getOrCreateAssociatedTokenAccount(
-        provider.connection,
-        marketCreator1,
-        mint,
-        marketCreator1.publicKey
-      );
-
-      await token.mintTo(
-        provider.connection,
-        mint_authority,
-        mint,
-        userAta.address,
-        mint_authority,
-        to_mint.toNumber()
-      );
-
-      const [marketAddress, marketBump] = getMarketAddress(
+    it("Initializes a market resolved by the median of several feeds", async () => {
+      const secondaryFeeds = [
+        {
+          source: { pyth: {} },
+          feedId: Array.from(crypto.randomBytes(32)),
+        },
+        {
+          source: { pyth: {} },
+          feedId: Array.from(crypto.randomBytes(32)),
+        },
+      ];
+      const medianDuration = marketDuration.add(new anchor.BN(1));
+
+      const [marketAddress] = getMarketAddress(
         marketCreator1.publicKey,
         feedIdString,
         targetPrice,
-        marketDuration,
-        program.programId
-      );
-
-      const [higherPoolAddress, higherPoolBump] = getPoolAddress(
-        HIGHER_POOL_SEED,
-        marketAddress,
-        program.programId
-      );
-
-      const [lowerPoolAddress, lowerPoolBump] = getPoolAddress(
-        LOWER_POOL_SEED,
-        marketAddress,
+        medianDuration,
         program.programId
       );
 
       await program.methods
-        .initializePools()
+        .initializeMarket(targetPrice, feedIdString, medianDuration, {
+          ...resolutionParams,
+          secondaryFeeds,
+          oracleQuorum: 2,
+        })
         .accountsStrict({
           market: marketAddress,
+          config: configAddress,
           marketCreator: marketCreator1.publicKey,
-          poolTokenMint: mint,
-          higherPool: higherPoolAddress,
-          lowerPool: lowerPoolAddress,
-          userAta: userAta.address,
           systemProgram: anchor.web3.SystemProgram.programId,
-          tokenProgram: token.TOKEN_PROGRAM_ID,
         })
         .signers([marketCreator1])
         .rpc()
         .then(confirmTx);
 
-      await checkMarket(
-        program,
-        marketAddress,
-        marketCreator1.publicKey,
-        feedIdString,
-        targetPrice,
-        marketDuration,
-        marketBump,
-        { initializedPools: {} },
-        higherPoolBump,
-        lowerPoolBump,
-        mint
-      );
+      const marketData = await program.account.market.fetch(marketAddress);
+      assert.strictEqual(marketData.secondaryFeeds.length, 2);
+      assert.deepEqual(marketData.secondaryFeeds[1].source, { pyth: {} });
+      assert.strictEqual(marketData.oracleQuorum, 2);
     });
-  });
-
-  describe("Place Bet", () => {
-    const betAmount = new anchor.BN(10000);
-    const betDirection = { higher: {} };
-    it("Placed Bet", async () => {
-      await airdrop(provider.connection, hema.publicKey);
-      await airdrop(provider.connection, marketCreator1.publicKey);
+    it("Initializes a market resolved on a TWAP", async () => {
+      const twapDuration = marketDuration.add(new anchor.BN(2));
 
-      const [marketAddress, marketBump] = getMarketAddress(
+      const [marketAddress] = getMarketAddress(
         marketCreator1.publicKey,
         feedIdString,
         targetPrice,
-        marketDuration,
+        twapDuration,
         program.programId
       );
 
-      const [higherPoolAddress, higherPoolBump] = getPoolAddress(
-        HIGHER_POOL_SEED,
-        marketAddress,
-        program.programId
-      );
+      await program.methods
+        .initializeMarket(targetPrice, feedIdString, twapDuration, {
+          ...resolutionParams,
+          resolutionMode: { twap: {} },
+          twapWindow: new anchor.BN(300),
+        })
+        .accountsStrict({
+          market: marketAddress,
+          config: configAddress,
+          marketCreator: marketCreator1.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([marketCreator1])
+        .rpc()
+        .then(confirmTx);
 
-      const [lowerPoolAddress, lowerPoolBump] = getPoolAddress(
-        LOWER_POOL_SEED,
-        marketAddress,
+      const marketData = await program.account.market.fetch(marketAddress);
+      assert.deepEqual(marketData.resolutionMode, { twap: {} });
+      assert.strictEqual(marketData.twapWindow.toNumber(), 300);
+    });
+    it("Initializes an event market resolved by a committee", async () => {
+      //event markets are keyed by the hash of the question instead of a feed id
+      const eventId =
+        "0x" +
+        crypto.createHash("sha256").update("Will the launch happen this year?").digest("hex");
+      const committee = [hema.publicKey, marketCreator1.publicKey, mint_authority.publicKey];
+
+      const [marketAddress] = getMarketAddress(
+        marketCreator1.publicKey,
+        eventId,
+        targetPrice,
+        marketDuration,
         program.programId
       );
 
-      const market = await program.account.market.fetch(marketAddress);
-
-      const userAta = await token.getOrCreateAssociatedTokenAccount(
-        provider.connection,
-        hema,
-        market.mint,
-        hema.publicKey
-      );
+      await program.methods
+        .initializeMarket(targetPrice, eventId, marketDuration, {
+          ...resolutionParams,
+          resolutionMode: { committee: {} },
+          committee,
+          committeeThreshold: 2,
+        })
+        .accountsStrict({
+          market: marketAddress,
+          config: configAddress,
+          marketCreator: marketCreator1.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([marketCreator1])
+        .rpc()
+        .then(confirmTx);
 
-      await token.mintTo(
-        provider.connection,
-        mint_authority,
-        market.mint,
-        userAta.address,
-        mint_authority,
-        to_mint.toNumber()
-      );
+      const marketData = await program.account.market.fetch(marketAddress);
+      assert.deepEqual(marketData.resolutionMode, { committee: {} });
+      assert.strictEqual(marketData.committee.length, 3);
+      assert.strictEqual(marketData.committeeThreshold, 2);
+      assert.strictEqual(marketData.proposedOutcome, null);
+    });
+    it("Initializes an event market resolved optimistically", async () => {
+      const eventId =
+        "0x" + crypto.createHash("sha256").update("Will the proposal pass?").digest("hex");
 
-      const [betAddress, betBump] = getBetAddress(
-        marketAddress,
-        hema.publicKey,
-        betAmount,
-        betDirection,
+      const [marketAddress] = getMarketAddress(
+        marketCreator1.publicKey,
+        eventId,
+        targetPrice,
+        marketDuration,
         program.programId
       );
 
       await program.methods
-        .placeBet(betAmount, betDirection)
+        .initializeMarket(targetPrice, eventId, marketDuration, {
+          ...resolutionParams,
+          resolutionMode: { optimistic: {} },
+          bondAmount: new anchor.BN(1_000_000),
+          challengePeriod: new anchor.BN(7200),
+        })
         .accountsStrict({
-          bet: betAddress,
           market: marketAddress,
-          user: hema.publicKey,
-          higherPool: higherPoolAddress,
-          lowerPool: lowerPoolAddress,
-          userAta: userAta.address,
+          config: configAddress,
+          marketCreator: marketCreator1.publicKey,
           systemProgram: anchor.web3.SystemProgram.programId,
-          tokenProgram: token.TOKEN_PROGRAM_ID,
         })
-        .signers([hema])
+        .signers([marketCreator1])
         .rpc()
         .then(confirmTx);
-    });
-  });
 
-  describe("Cancel Market", () => {
-    it("Initializes market again", async () => {
-      await airdrop(provider.connection, hema.publicKey);
+      const marketData = await program.account.market.fetch(marketAddress);
+      assert.deepEqual(marketData.resolutionMode, { optimistic: {} });
+      assert.strictEqual(marketData.bondAmount.toNumber(), 1_000_000);
+      assert.strictEqual(marketData.challengePeriod.toNumber(), 7200);
+      assert.strictEqual(marketData.proposer, null);
+    });
+    it("Initializes a categorical market with one bucket per price range", async () => {
+      const categoricalDuration = marketDuration.add(new anchor.BN(3));
+      const outcomeBounds = [new anchor.BN(100), new anchor.BN(140), new anchor.BN(180)];
 
-      const [marketAddress, marketBump] = getMarketAddress(
-        hema.publicKey,
+      const [marketAddress] = getMarketAddress(
+        marketCreator1.publicKey,
         feedIdString,
         targetPrice,
-        marketDuration,
+        categoricalDuration,
         program.programId
       );
 
       await program.methods
-        .initializeMarket(targetPrice, feedIdString, marketDuration)
+        .initializeMarket(targetPrice, feedIdString, categoricalDuration, {
+          ...resolutionParams,
+          outcomeBounds,
+        })
         .accountsStrict({
           market: marketAddress,
-          marketCreator: hema.publicKey,
+          config: configAddress,
+          marketCreator: marketCreator1.publicKey,
           systemProgram: anchor.web3.SystemProgram.programId,
         })
-        .signers([hema])
+        .signers([marketCreator1])
         .rpc()
         .then(confirmTx);
 
-      await checkMarket(
-        program,
-        marketAddress,
-        hema.publicKey,
-        feedIdString,
-        targetPrice,
-        marketDuration,
-        marketBump,
-        { initializedMarket: {} }
+      const marketData = await program.account.market.fetch(marketAddress);
+      assert.deepEqual(
+        marketData.outcomeBounds.map((bound) => bound.toNumber()),
+        [100, 140, 180]
       );
+      assert.strictEqual(marketData.fundedOutcomePools, 0);
+      assert.strictEqual(marketData.winningOutcome, null);
     });
+    it("Initializes a market priced by a constant-product AMM", async () => {
+      const cpmmDuration = marketDuration.add(new anchor.BN(4));
 
-    it("Initialize pool again", async () => {
-      await airdrop(provider.connection, hema.publicKey);
+      const [marketAddress] = getMarketAddress(
+        marketCreator1.publicKey,
+        feedIdString,
+        targetPrice,
+        cpmmDuration,
+        program.programId
+      );
+
+      await program.methods
+        .initializeMarket(targetPrice, feedIdString, cpmmDuration, {
+          ...resolutionParams,
+          pricingMode: { cpmm: {} },
+        })
+        .accountsStrict({
+          market: marketAddress,
+          config: configAddress,
+          marketCreator: marketCreator1.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([marketCreator1])
+        .rpc()
+        .then(confirmTx);
+
+      const marketData = await program.account.market.fetch(marketAddress);
+      assert.deepEqual(marketData.pricingMode, { cpmm: {} });
+      //reserves are seeded once the pools are funded
+      assert.strictEqual(marketData.higherReserve.toNumber(), 0);
+      assert.strictEqual(marketData.lowerReserve.toNumber(), 0);
+    });
+    it("Initializes a market priced by an LMSR market maker", async () => {
+      const lmsrDuration = marketDuration.add(new anchor.BN(5));
+
+      const [marketAddress] = getMarketAddress(
+        marketCreator1.publicKey,
+        feedIdString,
+        targetPrice,
+        lmsrDuration,
+        program.programId
+      );
+
+      await program.methods
+        .initializeMarket(targetPrice, feedIdString, lmsrDuration, {
+          ...resolutionParams,
+          pricingMode: { lmsr: {} },
+        })
+        .accountsStrict({
+          market: marketAddress,
+          config: configAddress,
+          marketCreator: marketCreator1.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([marketCreator1])
+        .rpc()
+        .then(confirmTx);
+
+      const marketData = await program.account.market.fetch(marketAddress);
+      assert.deepEqual(marketData.pricingMode, { lmsr: {} });
+      //b is the seed liquidity, set once the pools are funded
+      assert.strictEqual(marketData.liquidityParam.toNumber(), 0);
+    });
+    it("Initializes a market that mints outcome tokens", async () => {
+      const tokenizedDuration = marketDuration.add(new anchor.BN(6));
+
+      const [marketAddress] = getMarketAddress(
+        marketCreator1.publicKey,
+        feedIdString,
+        targetPrice,
+        tokenizedDuration,
+        program.programId
+      );
+
+      await program.methods
+        .initializeMarket(targetPrice, feedIdString, tokenizedDuration, {
+          ...resolutionParams,
+          pricingMode: { cpmm: {} },
+          outcomeTokens: true,
+        })
+        .accountsStrict({
+          market: marketAddress,
+          config: configAddress,
+          marketCreator: marketCreator1.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([marketCreator1])
+        .rpc()
+        .then(confirmTx);
+
+      const marketData = await program.account.market.fetch(marketAddress);
+      assert.isTrue(marketData.outcomeTokens);
+      assert.strictEqual(marketData.outstandingOutcomeTokens.toNumber(), 0);
+    });
+    it("Can not mint outcome tokens on a parimutuel market", async () => {
+      const tokenizedDuration = marketDuration.add(new anchor.BN(7));
+
+      const [marketAddress] = getMarketAddress(
+        marketCreator1.publicKey,
+        feedIdString,
+        targetPrice,
+        tokenizedDuration,
+        program.programId
+      );
+
+      let should_fail = "This Should Fail";
+      try {
+        await program.methods
+          .initializeMarket(targetPrice, feedIdString, tokenizedDuration, {
+            ...resolutionParams,
+            outcomeTokens: true,
+          })
+          .accountsStrict({
+            market: marketAddress,
+            config: configAddress,
+            marketCreator: marketCreator1.publicKey,
+            systemProgram: anchor.web3.SystemProgram.programId,
+          })
+          .signers([marketCreator1])
+          .rpc()
+          .then(confirmTx);
+      } catch (e) {
+        const anchorErr = anchor.AnchorError.parse(e.logs);
+        assert.strictEqual(
+          anchorErr.error.errorCode.code,
+          "WrongPricingMode",
+          "Unexpected Error Code"
+        );
+        should_fail = "Failed";
+      }
+      assert.strictEqual(should_fail, "Failed");
+    });
+    it("Can not initialize with invalid FeedId", async () => {
+      await airdrop(provider.connection, marketCreator1.publicKey);
+
+      let should_fail = "This Should Fail";
+      try {
+        const [marketAddress, marketBump] = getMarketAddress(
+          marketCreator1.publicKey,
+          feedIdString2,
+          targetPrice,
+          marketDuration,
+          program.programId
+        );
+
+        await program.methods
+          .initializeMarket(targetPrice, feedIdString2, marketDuration, resolutionParams)
+          .accountsStrict({
+            marketCreator: marketCreator1.publicKey,
+            market: marketAddress,
+            config: configAddress,
+            systemProgram: anchor.web3.SystemProgram.programId,
+          })
+          .signers([marketCreator1])
+          .rpc()
+          .then(confirmTx);
+      } catch (e) {
+        const anchorErr = anchor.AnchorError.parse(e.logs);
+        assert.strictEqual(
+          anchorErr.error.errorCode.code,
+          "IncorrectFeedIDLength",
+          "Unexpected Error Code"
+        );
+        should_fail = "Failed";
+      }
+      assert.strictEqual(should_fail, "Failed");
+    });
+  });
+  describe("Pool Initialization", () => {
+    //funds the market of marketCreator1 with a mint that is not, or no longer, in the registry
+    async function initializePoolsWithMint(mint: PublicKey) {
+      const userAta = await token.getOrCreateAssociatedTokenAccount(
+        provider.connection,
+        marketCreator1,
+        mint,
+        marketCreator1.publicKey
+      );
+      await token.mintTo(
+        provider.connection,
+        mint_authority,
+        mint,
+        userAta.address,
+        mint_authority,
+        to_mint.toNumber()
+      );
+
+      const [marketAddress] = getMarketAddress(
+        marketCreator1.publicKey,
+        feedIdString,
+        targetPrice,
+        marketDuration,
+        program.programId
+      );
+      const [allowedMintAddress] = PublicKey.findProgramAddressSync(
+        [anchor.utils.bytes.utf8.encode(ALLOWED_MINT_SEED), mint.toBuffer()],
+        program.programId
+      );
+
+      let should_fail = "This Should Fail";
+      try {
+        await program.methods
+          .initializePools()
+          .accountsStrict({
+            market: marketAddress,
+            allowedMint: allowedMintAddress,
+            treasury: getTreasuryAddress(TREASURY_SEED, mint, program.programId)[0],
+            marketCreator: marketCreator1.publicKey,
+            poolTokenMint: mint,
+            higherPool: getPoolAddress(HIGHER_POOL_SEED, marketAddress, program.programId)[0],
+            lowerPool: getPoolAddress(LOWER_POOL_SEED, marketAddress, program.programId)[0],
+            userAta: userAta.address,
+            systemProgram: anchor.web3.SystemProgram.programId,
+            tokenProgram: token.TOKEN_PROGRAM_ID,
+          })
+          .signers([marketCreator1])
+          .rpc()
+          .then(confirmTx);
+      } catch (e) {
+        const anchorErr = anchor.AnchorError.parse(e.logs);
+        assert.strictEqual(
+          anchorErr.error.errorCode.code,
+          "InvalidPoolMint",
+          "Unexpected Error Code"
+        );
+        should_fail = "Failed";
+      }
+      assert.strictEqual(should_fail, "Failed");
+    }
+
+    it("Can not fund pools with an unregistered mint", async () => {
+      await airdrop(provider.connection, marketCreator1.publicKey);
       await airdrop(provider.connection, mint_authority.publicKey);
 
       const mint = await token.createMint(
@@ -301,14 +701,54 @@ describe("prediction_market", () => {
         mint_authority,
         mint_authority.publicKey,
         null,
-        9
+        6
+      );
+      await initializePoolsWithMint(mint);
+    });
+
+    it("Can not fund pools with a mint removed from the registry", async () => {
+      const mint = await token.createMint(
+        provider.connection,
+        mint_authority,
+        mint_authority.publicKey,
+        null,
+        6
+      );
+      const allowedMintAddress = await allowMint(program, mint);
+
+      await program.methods
+        .removeAllowedMint()
+        .accountsStrict({
+          config: configAddress,
+          allowedMint: allowedMintAddress,
+          admin: provider.publicKey,
+        })
+        .rpc()
+        .then(confirmTx);
+      assert.isNull(await program.account.allowedMint.fetchNullable(allowedMintAddress));
+
+      //the treasury of the mint is still there, the missing registry entry alone rejects it
+      await initializePoolsWithMint(mint);
+    });
+
+    it("Initialize pool mint and token accounts", async () => {
+      await airdrop(provider.connection, marketCreator1.publicKey);
+      await airdrop(provider.connection, mint_authority.publicKey);
+
+      const mint = await token.createMint(
+        provider.connection,
+        mint_authority,
+        mint_authority.publicKey,
+        null,
+        6
       );
+      const allowedMintAddress = await allowMint(program, mint);
 
       const userAta = await token.getOrCreateAssociatedTokenAccount(
         provider.connection,
-        hema,
+        marketCreator1,
         mint,
-        hema.publicKey
+        marketCreator1.publicKey
       );
 
       await token.mintTo(
@@ -321,7 +761,7 @@ describe("prediction_market", () => {
       );
 
       const [marketAddress, marketBump] = getMarketAddress(
-        hema.publicKey,
+        marketCreator1.publicKey,
         feedIdString,
         targetPrice,
         marketDuration,
@@ -344,7 +784,9 @@ describe("prediction_market", () => {
         .initializePools()
         .accountsStrict({
           market: marketAddress,
-          marketCreator: hema.publicKey,
+          allowedMint: allowedMintAddress,
+          treasury: getTreasuryAddress(TREASURY_SEED, mint, program.programId)[0],
+          marketCreator: marketCreator1.publicKey,
           poolTokenMint: mint,
           higherPool: higherPoolAddress,
           lowerPool: lowerPoolAddress,
@@ -352,61 +794,96 @@ describe("prediction_market", () => {
           systemProgram: anchor.web3.SystemProgram.programId,
           tokenProgram: token.TOKEN_PROGRAM_ID,
         })
-        .signers([hema])
+        .signers([marketCreator1])
         .rpc()
         .then(confirmTx);
 
       await checkMarket(
         program,
         marketAddress,
-        hema.publicKey,
+        marketCreator1.publicKey,
         feedIdString,
         targetPrice,
         marketDuration,
         marketBump,
-        { initializedPools: {} },
+        { open: {} },
         higherPoolBump,
         lowerPoolBump,
         mint
       );
     });
-    it("Market Canceled", async () => {
+  });
+
+  describe("Place Bet", () => {
+    const betAmount = new anchor.BN(10000);
+    const betDirection = { higher: {} };
+    it("Placed Bet", async () => {
       await airdrop(provider.connection, hema.publicKey);
+      await airdrop(provider.connection, marketCreator1.publicKey);
 
       const [marketAddress, marketBump] = getMarketAddress(
-        hema.publicKey,
+        marketCreator1.publicKey,
         feedIdString,
         targetPrice,
         marketDuration,
         program.programId
       );
-      const market = await program.account.market.fetch(marketAddress);
 
       const [higherPoolAddress, higherPoolBump] = getPoolAddress(
         HIGHER_POOL_SEED,
         marketAddress,
         program.programId
       );
+
       const [lowerPoolAddress, lowerPoolBump] = getPoolAddress(
         LOWER_POOL_SEED,
         marketAddress,
         program.programId
       );
-      const creatorAta = await token.getOrCreateAssociatedTokenAccount(
+
+      const market = await program.account.market.fetch(marketAddress);
+
+      const userAta = await token.getOrCreateAssociatedTokenAccount(
         provider.connection,
         hema,
         market.mint,
         hema.publicKey
       );
 
+      await token.mintTo(
+        provider.connection,
+        mint_authority,
+        market.mint,
+        userAta.address,
+        mint_authority,
+        to_mint.toNumber()
+      );
+
+      const [userPositionAddress] = getUserPositionAddress(
+        marketAddress,
+        hema.publicKey,
+        program.programId
+      );
+
+      const [betAddress, betBump] = getBetAddress(
+        marketAddress,
+        hema.publicKey,
+        new anchor.BN(0),
+        program.programId
+      );
+
       await program.methods
-        .cancelMarket()
+        .placeBet(betAmount, betDirection)
         .accountsStrict({
+          bet: betAddress,
+          userPosition: userPositionAddress,
+          position: null,
           market: marketAddress,
-          marketCreator: hema.publicKey,
+          user: hema.publicKey,
           higherPool: higherPoolAddress,
           lowerPool: lowerPoolAddress,
-          creatorAta: creatorAta.address,
+          userAta: userAta.address,
+          mint: market.mint,
           systemProgram: anchor.web3.SystemProgram.programId,
           tokenProgram: token.TOKEN_PROGRAM_ID,
         })
@@ -414,23 +891,141 @@ describe("prediction_market", () => {
         .rpc()
         .then(confirmTx);
 
-      const cancelledMarket = await program.account.market.fetchNullable(
-        marketAddress
+      const bet = await program.account.bet.fetch(betAddress);
+      assert.strictEqual(bet.id.toString(), "0");
+      assert.strictEqual(bet.amount.toString(), betAmount.toString());
+    });
+
+    it("Placed the same bet again", async () => {
+      const [marketAddress] = getMarketAddress(
+        marketCreator1.publicKey,
+        feedIdString,
+        targetPrice,
+        marketDuration,
+        program.programId
+      );
+      const [higherPoolAddress] = getPoolAddress(
+        HIGHER_POOL_SEED,
+        marketAddress,
+        program.programId
+      );
+      const [lowerPoolAddress] = getPoolAddress(
+        LOWER_POOL_SEED,
+        marketAddress,
+        program.programId
+      );
+      const market = await program.account.market.fetch(marketAddress);
+      const userAta = await token.getOrCreateAssociatedTokenAccount(
+        provider.connection,
+        hema,
+        market.mint,
+        hema.publicKey
+      );
+      const [userPositionAddress] = getUserPositionAddress(
+        marketAddress,
+        hema.publicKey,
+        program.programId
+      );
+      const [betAddress] = getBetAddress(
+        marketAddress,
+        hema.publicKey,
+        new anchor.BN(1),
+        program.programId
       );
-      assert.isNull(cancelledMarket);
 
-      const higherPool = await program.account.market.fetchNullable(
-        higherPoolAddress
+      await program.methods
+        .placeBet(betAmount, betDirection)
+        .accountsStrict({
+          bet: betAddress,
+          userPosition: userPositionAddress,
+          position: null,
+          market: marketAddress,
+          user: hema.publicKey,
+          higherPool: higherPoolAddress,
+          lowerPool: lowerPoolAddress,
+          userAta: userAta.address,
+          mint: market.mint,
+          systemProgram: anchor.web3.SystemProgram.programId,
+          tokenProgram: token.TOKEN_PROGRAM_ID,
+        })
+        .signers([hema])
+        .rpc()
+        .then(confirmTx);
+
+      const userPosition = await program.account.userMarketPosition.fetch(
+        userPositionAddress
       );
-      assert.isNull(higherPool);
+      assert.strictEqual(userPosition.nextBetId.toString(), "2");
+    });
 
-      const lowerPool = await program.account.market.fetchNullable(
-        lowerPoolAddress
+    it("Topped up an aggregated position on both sides", async () => {
+      const [marketAddress] = getMarketAddress(
+        marketCreator1.publicKey,
+        feedIdString,
+        targetPrice,
+        marketDuration,
+        program.programId
+      );
+      const [higherPoolAddress] = getPoolAddress(
+        HIGHER_POOL_SEED,
+        marketAddress,
+        program.programId
+      );
+      const [lowerPoolAddress] = getPoolAddress(
+        LOWER_POOL_SEED,
+        marketAddress,
+        program.programId
+      );
+      const market = await program.account.market.fetch(marketAddress);
+      const userAta = await token.getOrCreateAssociatedTokenAccount(
+        provider.connection,
+        hema,
+        market.mint,
+        hema.publicKey
+      );
+      const [positionAddress] = PublicKey.findProgramAddressSync(
+        [
+          anchor.utils.bytes.utf8.encode(POSITION_SEED),
+          hema.publicKey.toBuffer(),
+          marketAddress.toBuffer(),
+        ],
+        program.programId
+      );
+
+      for (const direction of [{ higher: {} }, { higher: {} }, { lower: {} }]) {
+        await program.methods
+          .placeBet(betAmount, direction)
+          .accountsStrict({
+            market: marketAddress,
+            higherPool: higherPoolAddress,
+            lowerPool: lowerPoolAddress,
+            userAta: userAta.address,
+            mint: market.mint,
+            user: hema.publicKey,
+            bet: null,
+            userPosition: null,
+            position: positionAddress,
+            systemProgram: anchor.web3.SystemProgram.programId,
+            tokenProgram: token.TOKEN_PROGRAM_ID,
+          })
+          .signers([hema])
+          .rpc()
+          .then(confirmTx);
+      }
+
+      const position = await program.account.position.fetch(positionAddress);
+      assert.strictEqual(
+        position.higherAmount.toString(),
+        betAmount.muln(2).toString()
+      );
+      assert.strictEqual(
+        position.lowerAmount.toString(),
+        betAmount.toString()
       );
-      assert.isNull(lowerPool);
     });
   });
-  describe("Finalize Market", () => {
+
+  describe("Cancel Market", () => {
     it("Initializes market again", async () => {
       await airdrop(provider.connection, hema.publicKey);
 
@@ -443,9 +1038,10 @@ describe("prediction_market", () => {
       );
 
       await program.methods
-        .initializeMarket(targetPrice, feedIdString, marketDuration)
+        .initializeMarket(targetPrice, feedIdString, marketDuration, resolutionParams)
         .accountsStrict({
           market: marketAddress,
+          config: configAddress,
           marketCreator: hema.publicKey,
           systemProgram: anchor.web3.SystemProgram.programId,
         })
@@ -461,7 +1057,7 @@ describe("prediction_market", () => {
         targetPrice,
         marketDuration,
         marketBump,
-        { initializedMarket: {} }
+        { created: {} }
       );
     });
 
@@ -476,6 +1072,7 @@ describe("prediction_market", () => {
         null,
         9
       );
+      const allowedMintAddress = await allowMint(program, mint);
 
       const userAta = await token.getOrCreateAssociatedTokenAccount(
         provider.connection,
@@ -517,6 +1114,8 @@ describe("prediction_market", () => {
         .initializePools()
         .accountsStrict({
           market: marketAddress,
+          allowedMint: allowedMintAddress,
+          treasury: getTreasuryAddress(TREASURY_SEED, mint, program.programId)[0],
           marketCreator: hema.publicKey,
           poolTokenMint: mint,
           higherPool: higherPoolAddress,
@@ -537,14 +1136,13 @@ describe("prediction_market", () => {
         targetPrice,
         marketDuration,
         marketBump,
-        { initializedPools: {} },
+        { open: {} },
         higherPoolBump,
         lowerPoolBump,
         mint
       );
     });
-
-    it("Market Finalized", async () => {
+    it("Market Canceled", async () => {
       await airdrop(provider.connection, hema.publicKey);
 
       const [marketAddress, marketBump] = getMarketAddress(
@@ -573,30 +1171,16 @@ describe("prediction_market", () => {
         hema.publicKey
       );
 
-      await checkMarket(
-        program,
-        marketAddress,
-        hema.publicKey,
-        feedIdString,
-        targetPrice,
-        marketDuration,
-        marketBump,
-        { initializedPools: {} },
-        higherPoolBump,
-        lowerPoolBump,
-        market.mint
-      );
-
-      const creatorBalanceBefore = Number(creatorAta.amount);
-
       await program.methods
-        .finalizeMarket()
+        .cancelMarket()
         .accountsStrict({
           market: marketAddress,
           marketCreator: hema.publicKey,
           higherPool: higherPoolAddress,
           lowerPool: lowerPoolAddress,
+          setVault: null,
           creatorAta: creatorAta.address,
+          mint: market.mint,
           systemProgram: anchor.web3.SystemProgram.programId,
           tokenProgram: token.TOKEN_PROGRAM_ID,
         })
@@ -604,17 +1188,6 @@ describe("prediction_market", () => {
         .rpc()
         .then(confirmTx);
 
-      const creatorAtaAfter = await token.getAccount(
-        provider.connection,
-        creatorAta.address,
-        "confirmed"
-      );
-
-      assert.strictEqual(
-        Number(creatorAtaAfter.amount),
-        creatorBalanceBefore + Number(INITIAL_USDC_AMOUNT) * 2
-      );
-
       const cancelledMarket = await program.account.market.fetchNullable(
         marketAddress
       );
@@ -631,70 +1204,1746 @@ describe("prediction_market", () => {
       assert.isNull(lowerPool);
     });
   });
-});
+  describe("Finalize Market", () => {
+    it("Initializes market again", async () => {
+      await airdrop(provider.connection, hema.publicKey);
 
-async function airdrop(
-  connection: anchor.web3.Connection,
-  address: PublicKey,
-  amount = 10 * LAMPORTS_PER_SOL
-) {
-  await connection.requestAirdrop(address, amount).then(confirmTx);
-}
+      const [marketAddress, marketBump] = getMarketAddress(
+        hema.publicKey,
+        feedIdString,
+        targetPrice,
+        marketDuration,
+        program.programId
+      );
 
-function getMarketAddress(
-  creator: PublicKey,
-  feedId: string,
-  targetPrice: anchor.BN,
-  marketDuration: anchor.BN,
-  programID: PublicKey
-) {
-  let hexString = crypto
-    .createHash("sha256")
-    .update(feedId, "utf-8")
-    .digest("hex");
-  let feed_seed = Uint8Array.from(Buffer.from(hexString, "hex"));
+      await program.methods
+        .initializeMarket(targetPrice, feedIdString, marketDuration, resolutionParams)
+        .accountsStrict({
+          market: marketAddress,
+          config: configAddress,
+          marketCreator: hema.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([hema])
+        .rpc()
+        .then(confirmTx);
 
-  return PublicKey.findProgramAddressSync(
-    [
-      creator.toBuffer(),
-      feed_seed,
-      targetPrice.toArrayLike(Buffer, "le", 8),
-      marketDuration.toArrayLike(Buffer, "le", 8),
-    ],
-    programID
-  );
-}
+      await checkMarket(
+        program,
+        marketAddress,
+        hema.publicKey,
+        feedIdString,
+        targetPrice,
+        marketDuration,
+        marketBump,
+        { created: {} }
+      );
+    });
 
-function getPoolAddress(
-  poolStringSeed: string,
-  marketAddress: PublicKey,
-  programId: PublicKey
-) {
-  return PublicKey.findProgramAddressSync(
-    [anchor.utils.bytes.utf8.encode(poolStringSeed), marketAddress.toBuffer()],
-    programId
-  );
-}
+    it("Initialize pool again", async () => {
+      await airdrop(provider.connection, hema.publicKey);
+      await airdrop(provider.connection, mint_authority.publicKey);
 
-function getBetAddress(
-  marketAddress: PublicKey,
-  userAddress: PublicKey,
-  betAmount: anchor.BN,
-  betDirection: Object,
-  programId: PublicKey,
-  betStringSeed = "prediction_bet"
-) {
-  const directionString = Object.keys(betDirection)[0];
-  const directionNum = directionString == "higher" ? 0 : 1;
-  const directionBN = new anchor.BN(directionNum);
+      const mint = await token.createMint(
+        provider.connection,
+        mint_authority,
+        mint_authority.publicKey,
+        null,
+        9
+      );
+      const allowedMintAddress = await allowMint(program, mint);
+
+      const userAta = await token.getOrCreateAssociatedTokenAccount(
+        provider.connection,
+        hema,
+        mint,
+        hema.publicKey
+      );
+
+      await token.mintTo(
+        provider.connection,
+        mint_authority,
+        mint,
+        userAta.address,
+        mint_authority,
+        to_mint.toNumber()
+      );
+
+      const [marketAddress, marketBump] = getMarketAddress(
+        hema.publicKey,
+        feedIdString,
+        targetPrice,
+        marketDuration,
+        program.programId
+      );
+
+      const [higherPoolAddress, higherPoolBump] = getPoolAddress(
+        HIGHER_POOL_SEED,
+        marketAddress,
+        program.programId
+      );
+
+      const [lowerPoolAddress, lowerPoolBump] = getPoolAddress(
+        LOWER_POOL_SEED,
+        marketAddress,
+        program.programId
+      );
+
+      await program.methods
+        .initializePools()
+        .accountsStrict({
+          market: marketAddress,
+          allowedMint: allowedMintAddress,
+          treasury: getTreasuryAddress(TREASURY_SEED, mint, program.programId)[0],
+          marketCreator: hema.publicKey,
+          poolTokenMint: mint,
+          higherPool: higherPoolAddress,
+          lowerPool: lowerPoolAddress,
+          userAta: userAta.address,
+          systemProgram: anchor.web3.SystemProgram.programId,
+          tokenProgram: token.TOKEN_PROGRAM_ID,
+        })
+        .signers([hema])
+        .rpc()
+        .then(confirmTx);
 
+      await checkMarket(
+        program,
+        marketAddress,
+        hema.publicKey,
+        feedIdString,
+        targetPrice,
+        marketDuration,
+        marketBump,
+        { open: {} },
+        higherPoolBump,
+        lowerPoolBump,
+        mint
+      );
+    });
+
+    it("Can not finalize an open market", async () => {
+      await airdrop(provider.connection, hema.publicKey);
+
+      const [marketAddress, marketBump] = getMarketAddress(
+        hema.publicKey,
+        feedIdString,
+        targetPrice,
+        marketDuration,
+        program.programId
+      );
+      const market = await program.account.market.fetch(marketAddress);
+
+      const [higherPoolAddress, higherPoolBump] = getPoolAddress(
+        HIGHER_POOL_SEED,
+        marketAddress,
+        program.programId
+      );
+      const [lowerPoolAddress, lowerPoolBump] = getPoolAddress(
+        LOWER_POOL_SEED,
+        marketAddress,
+        program.programId
+      );
+      const creatorAta = await token.getOrCreateAssociatedTokenAccount(
+        provider.connection,
+        hema,
+        market.mint,
+        hema.publicKey
+      );
+
+      await checkMarket(
+        program,
+        marketAddress,
+        hema.publicKey,
+        feedIdString,
+        targetPrice,
+        marketDuration,
+        marketBump,
+        { open: {} },
+        higherPoolBump,
+        lowerPoolBump,
+        market.mint
+      );
+
+      let should_fail = "This Should Fail";
+      try {
+        await program.methods
+          .finalizeMarket()
+          .accountsStrict({
+            market: marketAddress,
+            config: configAddress,
+            marketCreator: hema.publicKey,
+            higherPool: higherPoolAddress,
+            lowerPool: lowerPoolAddress,
+            setVault: null,
+            creatorAta: creatorAta.address,
+            mint: market.mint,
+            systemProgram: anchor.web3.SystemProgram.programId,
+            tokenProgram: token.TOKEN_PROGRAM_ID,
+          })
+          .signers([hema])
+          .rpc()
+          .then(confirmTx);
+      } catch (e) {
+        const anchorErr = anchor.AnchorError.parse(e.logs);
+        assert.strictEqual(
+          anchorErr.error.errorCode.code,
+          "MarketDurationNotOver",
+          "Unexpected Error Code"
+        );
+        should_fail = "Failed";
+      }
+      assert.strictEqual(should_fail, "Failed");
+
+      await checkMarket(
+        program,
+        marketAddress,
+        hema.publicKey,
+        feedIdString,
+        targetPrice,
+        marketDuration,
+        marketBump,
+        { open: {} },
+        higherPoolBump,
+        lowerPoolBump,
+        market.mint
+      );
+    });
+  });
+
+  describe("Optimistic Resolution", () => {
+    const proposer = anchor.web3.Keypair.generate();
+    const disputer = anchor.web3.Keypair.generate();
+
+    before(async () => {
+      await setMinMarketDuration(program, configAddress, configParams, new anchor.BN(1));
+    });
+
+    it("Refuses to settle a disputed proposal", async () => {
+      await airdrop(provider.connection, marketCreator1.publicKey);
+      await airdrop(provider.connection, proposer.publicKey);
+      await airdrop(provider.connection, disputer.publicKey);
+      await airdrop(provider.connection, mint_authority.publicKey);
+
+      const mint = await token.createMint(
+        provider.connection,
+        mint_authority,
+        mint_authority.publicKey,
+        null,
+        6
+      );
+      await allowMint(program, mint);
+      await mintTokens(provider.connection, mint_authority, mint, marketCreator1, to_mint);
+      const proposerAta = await mintTokens(provider.connection, mint_authority, mint, proposer, to_mint);
+      const disputerAta = await mintTokens(provider.connection, mint_authority, mint, disputer, to_mint);
+
+      const { marketAddress, higherPoolAddress, lowerPoolAddress } = await createShortMarket(
+        program,
+        marketCreator1,
+        mint,
+        {
+          ...resolutionParams,
+          resolutionMode: { optimistic: {} },
+          bondAmount: new anchor.BN(1000),
+          challengePeriod: new anchor.BN(3600),
+        }
+      );
+      await waitForExpiry(program, marketAddress);
+
+      const [bondVaultAddress] = getPoolAddress(
+        BOND_VAULT_SEED,
+        marketAddress,
+        program.programId
+      );
+
+      await program.methods
+        .proposeBondedOutcome({ higher: {} })
+        .accountsStrict({
+          market: marketAddress,
+          bondVault: bondVaultAddress,
+          proposerAta,
+          proposer: proposer.publicKey,
+          mint,
+          tokenProgram: token.TOKEN_PROGRAM_ID,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([proposer])
+        .rpc()
+        .then(confirmTx);
+
+      await program.methods
+        .disputeOutcome()
+        .accountsStrict({
+          market: marketAddress,
+          bondVault: bondVaultAddress,
+          disputerAta,
+          disputer: disputer.publicKey,
+          mint,
+          tokenProgram: token.TOKEN_PROGRAM_ID,
+        })
+        .signers([disputer])
+        .rpc()
+        .then(confirmTx);
+
+      //only the admin ruling can resolve a disputed market, however long ago it was proposed
+      let should_fail = "This Should Fail";
+      try {
+        await program.methods
+          .settleProposal()
+          .accountsStrict({
+            market: marketAddress,
+            higherPool: higherPoolAddress,
+            lowerPool: lowerPoolAddress,
+            bondVault: bondVaultAddress,
+            proposerAta,
+            proposer: proposer.publicKey,
+            mint,
+            caller: provider.wallet.publicKey,
+            tokenProgram: token.TOKEN_PROGRAM_ID,
+          })
+          .rpc()
+          .then(confirmTx);
+      } catch (e) {
+        const anchorErr = anchor.AnchorError.parse(e.logs);
+        assert.strictEqual(
+          anchorErr.error.errorCode.code,
+          "AlreadyDisputed",
+          "Unexpected Error Code"
+        );
+        should_fail = "Failed";
+      }
+      assert.strictEqual(should_fail, "Failed");
+
+      const market = await program.account.market.fetch(marketAddress);
+      assert.deepEqual(market.status, { disputed: {} });
+      const bondVault = await token.getAccount(provider.connection, bondVaultAddress);
+      assert.strictEqual(bondVault.amount.toString(), "2000");
+    });
+  });
+
+  describe("Categorical Markets", () => {
+    const categoricalCreator = anchor.web3.Keypair.generate();
+    const bettor = anchor.web3.Keypair.generate();
+    const betAmount = new anchor.BN(10000);
+    let mint: PublicKey;
+    let marketAddress: PublicKey;
+    let bettorAta: PublicKey;
+    const outcomePools = () =>
+      [0, 1, 2].map((index) => getOutcomePoolAddress(marketAddress, index, program.programId)[0]);
+
+    before(async () => {
+      //short markets that can be voided right after their resolution window
+      await program.methods
+        .updateConfig({
+          ...configParams,
+          minMarketDuration: new anchor.BN(1),
+          voidGracePeriod: new anchor.BN(0),
+        })
+        .accountsStrict({
+          config: configAddress,
+          admin: provider.wallet.publicKey,
+        })
+        .rpc()
+        .then(confirmTx);
+
+      await airdrop(provider.connection, categoricalCreator.publicKey);
+      await airdrop(provider.connection, bettor.publicKey);
+      await airdrop(provider.connection, mint_authority.publicKey);
+      mint = await token.createMint(
+        provider.connection,
+        mint_authority,
+        mint_authority.publicKey,
+        null,
+        6
+      );
+      await allowMint(program, mint);
+      await mintTokens(provider.connection, mint_authority, mint, categoricalCreator, to_mint);
+      bettorAta = await mintTokens(provider.connection, mint_authority, mint, bettor, to_mint);
+    });
+
+    it("Funds one pool per outcome and opens the market", async () => {
+      ({ marketAddress } = await createShortMarket(
+        program,
+        categoricalCreator,
+        mint,
+        {
+          ...resolutionParams,
+          resolutionWindow: new anchor.BN(1),
+          outcomeBounds: [new anchor.BN(100), new anchor.BN(200)],
+        },
+        new anchor.BN(25)
+      ));
+      const [allowedMintAddress] = PublicKey.findProgramAddressSync(
+        [anchor.utils.bytes.utf8.encode(ALLOWED_MINT_SEED), mint.toBuffer()],
+        program.programId
+      );
+
+      for (const [index, outcomePool] of outcomePools().entries()) {
+        await program.methods
+          .initializeOutcomePool(index)
+          .accountsStrict({
+            market: marketAddress,
+            allowedMint: allowedMintAddress,
+            treasury: getTreasuryAddress(TREASURY_SEED, mint, program.programId)[0],
+            outcomePool,
+            userAta: token.getAssociatedTokenAddressSync(mint, categoricalCreator.publicKey),
+            poolTokenMint: mint,
+            marketCreator: categoricalCreator.publicKey,
+            systemProgram: anchor.web3.SystemProgram.programId,
+            tokenProgram: token.TOKEN_PROGRAM_ID,
+          })
+          .signers([categoricalCreator])
+          .rpc()
+          .then(confirmTx);
+      }
+
+      const market = await program.account.market.fetch(marketAddress);
+      assert.deepEqual(market.status, { open: {} });
+      assert.strictEqual(market.fundedOutcomePools, 0b111);
+      assert.strictEqual(
+        market.outcomeTotals[2].toString(),
+        SEED_LIQUIDITY_AMOUNT.toString()
+      );
+    });
+
+    it("Places a bet on an outcome", async () => {
+      const [userPositionAddress] = getUserPositionAddress(
+        marketAddress,
+        bettor.publicKey,
+        program.programId
+      );
+      const [betAddress] = getBetAddress(
+        marketAddress,
+        bettor.publicKey,
+        new anchor.BN(0),
+        program.programId
+      );
+
+      await program.methods
+        .placeOutcomeBet(betAmount, 1)
+        .accountsStrict({
+          market: marketAddress,
+          outcomePool: outcomePools()[1],
+          userAta: bettorAta,
+          user: bettor.publicKey,
+          userPosition: userPositionAddress,
+          bet: betAddress,
+          systemProgram: anchor.web3.SystemProgram.programId,
+          mint,
+          tokenProgram: token.TOKEN_PROGRAM_ID,
+        })
+        .signers([bettor])
+        .rpc()
+        .then(confirmTx);
+
+      const bet = await program.account.bet.fetch(betAddress);
+      assert.strictEqual(bet.outcomeIndex, 1);
+      const market = await program.account.market.fetch(marketAddress);
+      assert.strictEqual(
+        market.outcomeTotals[1].toString(),
+        SEED_LIQUIDITY_AMOUNT.add(betAmount).toString()
+      );
+      assert.strictEqual(market.openBets.toString(), "1");
+    });
+
+    it("Rejects outcome pools passed out of order at resolution", async () => {
+      await waitForExpiry(program, marketAddress);
+      const [pool0, pool1, pool2] = outcomePools();
+
+      let should_fail = "This Should Fail";
+      try {
+        await program.methods
+          .resolveCategoricalMarket()
+          .accountsStrict({
+            market: marketAddress,
+            priceFeed: mint,
+            mint,
+            resolver: provider.wallet.publicKey,
+            tokenProgram: token.TOKEN_PROGRAM_ID,
+          })
+          .remainingAccounts(
+            [pool1, pool0, pool2].map((pubkey) => ({
+              pubkey,
+              isWritable: true,
+              isSigner: false,
+            }))
+          )
+          .rpc()
+          .then(confirmTx);
+      } catch (e) {
+        const anchorErr = anchor.AnchorError.parse(e.logs);
+        assert.strictEqual(
+          anchorErr.error.errorCode.code,
+          "InvalidOutcomePool",
+          "Unexpected Error Code"
+        );
+        should_fail = "Failed";
+      }
+      assert.strictEqual(should_fail, "Failed");
+    });
+
+    it("Refunds the bet once the unresolved market is voided", async () => {
+      const [betAddress] = getBetAddress(
+        marketAddress,
+        bettor.publicKey,
+        new anchor.BN(0),
+        program.programId
+      );
+
+      await program.methods
+        .voidMarket()
+        .accountsStrict({
+          market: marketAddress,
+          bondVault: null,
+          proposer: null,
+          proposerAta: null,
+          disputerAta: null,
+          mint: null,
+          caller: provider.wallet.publicKey,
+          tokenProgram: null,
+        })
+        .rpc()
+        .then(confirmTx);
+
+      await program.methods
+        .claimOutcomeBet()
+        .accountsStrict({
+          market: marketAddress,
+          outcomePool: outcomePools()[1],
+          userAta: bettorAta,
+          user: bettor.publicKey,
+          bet: betAddress,
+          treasury: getTreasuryAddress(TREASURY_SEED, mint, program.programId)[0],
+          treasuryVault: getTreasuryAddress(TREASURY_VAULT_SEED, mint, program.programId)[0],
+          systemProgram: anchor.web3.SystemProgram.programId,
+          mint,
+          tokenProgram: token.TOKEN_PROGRAM_ID,
+        })
+        .signers([bettor])
+        .rpc()
+        .then(confirmTx);
+
+      const userAta = await token.getAccount(provider.connection, bettorAta);
+      assert.strictEqual(userAta.amount.toString(), to_mint.toString());
+      assert.isNull(await program.account.bet.fetchNullable(betAddress));
+      const market = await program.account.market.fetch(marketAddress);
+      assert.deepEqual(market.status, { settled: {} });
+    });
+
+    it("Closes every outcome pool and the market with the last one", async () => {
+      for (const [index, outcomePool] of outcomePools().entries()) {
+        await program.methods
+          .closeOutcomePool(index)
+          .accountsStrict({
+            market: marketAddress,
+            config: configAddress,
+            outcomePool,
+            creatorAta: token.getAssociatedTokenAddressSync(mint, categoricalCreator.publicKey),
+            marketCreator: categoricalCreator.publicKey,
+            mint,
+            tokenProgram: token.TOKEN_PROGRAM_ID,
+          })
+          .signers([categoricalCreator])
+          .rpc()
+          .then(confirmTx);
+      }
+
+      assert.isNull(await program.account.market.fetchNullable(marketAddress));
+      const creatorAta = await token.getAccount(
+        provider.connection,
+        token.getAssociatedTokenAddressSync(mint, categoricalCreator.publicKey)
+      );
+      assert.strictEqual(creatorAta.amount.toString(), to_mint.toString());
+    });
+  });
+
+  describe("Outcome Tokens", () => {
+    const tokenCreator = anchor.web3.Keypair.generate();
+    const trader = anchor.web3.Keypair.generate();
+    const committee = [anchor.web3.Keypair.generate(), anchor.web3.Keypair.generate()];
+    const betAmount = new anchor.BN(100000);
+    //committee markets can be resolved without an oracle on the local validator
+    const tokenizedParams = {
+      ...resolutionParams,
+      resolutionWindow: new anchor.BN(1),
+      resolutionMode: { committee: {} },
+      committee: committee.map((member) => member.publicKey),
+      committeeThreshold: 2,
+      pricingMode: { cpmm: {} },
+      outcomeTokens: true,
+    };
+    let mint: PublicKey;
+    let traderAta: PublicKey;
+
+    before(async () => {
+      await program.methods
+        .updateConfig({
+          ...configParams,
+          minMarketDuration: new anchor.BN(1),
+          voidGracePeriod: new anchor.BN(0),
+        })
+        .accountsStrict({
+          config: configAddress,
+          admin: provider.wallet.publicKey,
+        })
+        .rpc()
+        .then(confirmTx);
+
+      await airdrop(provider.connection, tokenCreator.publicKey);
+      await airdrop(provider.connection, trader.publicKey);
+      await airdrop(provider.connection, mint_authority.publicKey);
+      mint = await token.createMint(
+        provider.connection,
+        mint_authority,
+        mint_authority.publicKey,
+        null,
+        6
+      );
+      await allowMint(program, mint);
+      await mintTokens(provider.connection, mint_authority, mint, tokenCreator, to_mint);
+      traderAta = await mintTokens(provider.connection, mint_authority, mint, trader, to_mint);
+    });
+
+    it("Mints, buys and redeems the winning tokens of a resolved market", async () => {
+      const { marketAddress, higherPoolAddress, lowerPoolAddress } = await createShortMarket(
+        program,
+        tokenCreator,
+        mint,
+        tokenizedParams,
+        new anchor.BN(15)
+      );
+      const { higherMint } = await initializeOutcomeMints(program, tokenCreator, marketAddress, mint);
+
+      const mintData = await token.getMint(provider.connection, higherMint);
+      assert.strictEqual(mintData.decimals, 6);
+      assert.isTrue(mintData.mintAuthority.equals(marketAddress));
+
+      const [treasuryVaultAddress] = getTreasuryAddress(TREASURY_VAULT_SEED, mint, program.programId);
+      const vaultBefore = await token.getAccount(provider.connection, treasuryVaultAddress);
+      const higherTokens = await buyOutcomeTokens(
+        program,
+        trader,
+        marketAddress,
+        mint,
+        betAmount,
+        { higher: {} }
+      );
+
+      const market = await program.account.market.fetch(marketAddress);
+      const fees = [market.protocolFeeBps, market.creatorFeeBps]
+        .map((bps) => betAmount.muln(bps).divn(10000))
+        .reduce((total, fee) => total.add(fee));
+      const vaultAfter = await token.getAccount(provider.connection, treasuryVaultAddress);
+      assert.strictEqual(
+        (vaultAfter.amount - vaultBefore.amount).toString(),
+        fees.toString()
+      );
+      assert.isTrue(higherTokens.gt(betAmount.sub(fees)));
+      assert.strictEqual(market.outstandingOutcomeTokens.toString(), higherTokens.toString());
+      assert.strictEqual(market.higherTokenCost.toString(), betAmount.sub(fees).toString());
+
+      await waitForExpiry(program, marketAddress);
+      await program.methods
+        .proposeOutcome({ higher: {} })
+        .accountsStrict({
+          market: marketAddress,
+          higherPool: higherPoolAddress,
+          lowerPool: lowerPoolAddress,
+          proposer: committee[0].publicKey,
+        })
+        .signers([committee[0]])
+        .rpc()
+        .then(confirmTx);
+      await program.methods
+        .approveOutcome({ higher: {} })
+        .accountsStrict({
+          market: marketAddress,
+          higherPool: higherPoolAddress,
+          lowerPool: lowerPoolAddress,
+          approver: committee[1].publicKey,
+        })
+        .signers([committee[1]])
+        .rpc()
+        .then(confirmTx);
+
+      const balanceBefore = await token.getAccount(provider.connection, traderAta);
+      await redeemOutcomeTokens(program, trader, marketAddress, mint, higherTokens, { higher: {} });
+      const balanceAfter = await token.getAccount(provider.connection, traderAta);
+      assert.strictEqual(
+        (balanceAfter.amount - balanceBefore.amount).toString(),
+        higherTokens.toString()
+      );
+      const resolved = await program.account.market.fetch(marketAddress);
+      assert.deepEqual(resolved.outcome, { higher: {} });
+      assert.strictEqual(resolved.outstandingOutcomeTokens.toString(), "0");
+    });
+
+    it("Refunds what was paid for the tokens of a voided market", async () => {
+      const { marketAddress } = await createShortMarket(
+        program,
+        tokenCreator,
+        mint,
+        tokenizedParams,
+        new anchor.BN(15)
+      );
+      await initializeOutcomeMints(program, tokenCreator, marketAddress, mint);
+
+      const balanceBefore = await token.getAccount(provider.connection, traderAta);
+      const lowerTokens = await buyOutcomeTokens(
+        program,
+        trader,
+        marketAddress,
+        mint,
+        betAmount,
+        { lower: {} }
+      );
+      const { lowerTokenCost } = await program.account.market.fetch(marketAddress);
+
+      await waitForExpiry(program, marketAddress);
+      await program.methods
+        .voidMarket()
+        .accountsStrict({
+          market: marketAddress,
+          bondVault: null,
+          proposer: null,
+          proposerAta: null,
+          disputerAta: null,
+          mint: null,
+          caller: provider.wallet.publicKey,
+          tokenProgram: null,
+        })
+        .rpc()
+        .then(confirmTx);
+      await redeemOutcomeTokens(program, trader, marketAddress, mint, lowerTokens, { lower: {} });
+
+      //only the fees are lost, not half of the tokens
+      const balanceAfter = await token.getAccount(provider.connection, traderAta);
+      assert.strictEqual(
+        (balanceBefore.amount - balanceAfter.amount).toString(),
+        betAmount.sub(lowerTokenCost).toString()
+      );
+      const market = await program.account.market.fetch(marketAddress);
+      assert.strictEqual(market.lowerTokenCost.toString(), "0");
+    });
+
+    it("Mints and merges a complete set", async () => {
+      const setAmount = new anchor.BN(50000);
+      const { marketAddress } = await createShortMarket(
+        program,
+        tokenCreator,
+        mint,
+        tokenizedParams,
+        new anchor.BN(15)
+      );
+      const { higherMint, lowerMint, setVault } = await initializeOutcomeMints(
+        program,
+        tokenCreator,
+        marketAddress,
+        mint
+      );
+      const userHigherAta = token.getAssociatedTokenAddressSync(higherMint, trader.publicKey);
+      const userLowerAta = token.getAssociatedTokenAddressSync(lowerMint, trader.publicKey);
+      const balanceBefore = await token.getAccount(provider.connection, traderAta);
+
+      await mintCompleteSet(program, trader, marketAddress, mint, setAmount);
+
+      for (const ata of [userHigherAta, userLowerAta, setVault]) {
+        const account = await token.getAccount(provider.connection, ata);
+        assert.strictEqual(account.amount.toString(), setAmount.toString());
+      }
+      let market = await program.account.market.fetch(marketAddress);
+      assert.strictEqual(market.outstandingOutcomeTokens.toString(), setAmount.muln(2).toString());
+
+      await program.methods
+        .mergeCompleteSet(setAmount)
+        .accountsStrict({
+          market: marketAddress,
+          setVault,
+          higherMint,
+          lowerMint,
+          userHigherAta,
+          userLowerAta,
+          userAta: traderAta,
+          user: trader.publicKey,
+          mint,
+          tokenProgram: token.TOKEN_PROGRAM_ID,
+        })
+        .signers([trader])
+        .rpc()
+        .then(confirmTx);
+
+      for (const ata of [userHigherAta, userLowerAta, setVault]) {
+        const account = await token.getAccount(provider.connection, ata);
+        assert.strictEqual(account.amount.toString(), "0");
+      }
+      const balanceAfter = await token.getAccount(provider.connection, traderAta);
+      assert.strictEqual(balanceAfter.amount.toString(), balanceBefore.amount.toString());
+      market = await program.account.market.fetch(marketAddress);
+      assert.strictEqual(market.outstandingOutcomeTokens.toString(), "0");
+    });
+
+    it("Can not merge bought tokens the set vault does not back", async () => {
+      const { marketAddress } = await createShortMarket(
+        program,
+        tokenCreator,
+        mint,
+        tokenizedParams,
+        new anchor.BN(15)
+      );
+      const { higherMint, lowerMint, setVault } = await initializeOutcomeMints(
+        program,
+        tokenCreator,
+        marketAddress,
+        mint
+      );
+      //the market maker shares are backed by the pools, the set vault stays empty
+      await buyOutcomeTokens(program, trader, marketAddress, mint, betAmount, { higher: {} });
+      await buyOutcomeTokens(program, trader, marketAddress, mint, betAmount, { lower: {} });
+
+      let should_fail = "This Should Fail";
+      try {
+        await program.methods
+          .mergeCompleteSet(new anchor.BN(1000))
+          .accountsStrict({
+            market: marketAddress,
+            setVault,
+            higherMint,
+            lowerMint,
+            userHigherAta: token.getAssociatedTokenAddressSync(higherMint, trader.publicKey),
+            userLowerAta: token.getAssociatedTokenAddressSync(lowerMint, trader.publicKey),
+            userAta: traderAta,
+            user: trader.publicKey,
+            mint,
+            tokenProgram: token.TOKEN_PROGRAM_ID,
+          })
+          .signers([trader])
+          .rpc()
+          .then(confirmTx);
+      } catch (e) {
+        const anchorErr = anchor.AnchorError.parse(e.logs);
+        assert.strictEqual(
+          anchorErr.error.errorCode.code,
+          "InsufficientSetCollateral",
+          "Unexpected Error Code"
+        );
+        should_fail = "Failed";
+      }
+      assert.strictEqual(should_fail, "Failed");
+    });
+
+    it("Pays a winning set token out of the set vault once the pools run dry", async () => {
+      //more than both seeded pools hold together
+      const setAmount = SEED_LIQUIDITY_AMOUNT.muln(3);
+      const { marketAddress, higherPoolAddress, lowerPoolAddress } = await createShortMarket(
+        program,
+        tokenCreator,
+        mint,
+        tokenizedParams,
+        new anchor.BN(15)
+      );
+      const { setVault } = await initializeOutcomeMints(program, tokenCreator, marketAddress, mint);
+      await mintCompleteSet(program, trader, marketAddress, mint, setAmount);
+
+      await waitForExpiry(program, marketAddress);
+      await program.methods
+        .proposeOutcome({ higher: {} })
+        .accountsStrict({
+          market: marketAddress,
+          higherPool: higherPoolAddress,
+          lowerPool: lowerPoolAddress,
+          proposer: committee[0].publicKey,
+        })
+        .signers([committee[0]])
+        .rpc()
+        .then(confirmTx);
+      await program.methods
+        .approveOutcome({ higher: {} })
+        .accountsStrict({
+          market: marketAddress,
+          higherPool: higherPoolAddress,
+          lowerPool: lowerPoolAddress,
+          approver: committee[1].publicKey,
+        })
+        .signers([committee[1]])
+        .rpc()
+        .then(confirmTx);
+
+      const balanceBefore = await token.getAccount(provider.connection, traderAta);
+      await redeemOutcomeTokens(program, trader, marketAddress, mint, setAmount, { higher: {} });
+
+      const balanceAfter = await token.getAccount(provider.connection, traderAta);
+      assert.strictEqual(
+        (balanceAfter.amount - balanceBefore.amount).toString(),
+        setAmount.toString()
+      );
+      for (const pool of [higherPoolAddress, lowerPoolAddress]) {
+        const account = await token.getAccount(provider.connection, pool);
+        assert.strictEqual(account.amount.toString(), "0");
+      }
+      const vault = await token.getAccount(provider.connection, setVault);
+      assert.strictEqual(
+        vault.amount.toString(),
+        SEED_LIQUIDITY_AMOUNT.muln(2).toString()
+      );
+    });
+  });
+
+  describe("Selling Bets", () => {
+    const sellCreator = anchor.web3.Keypair.generate();
+    const seller = anchor.web3.Keypair.generate();
+    const counterparty = anchor.web3.Keypair.generate();
+    let mint: PublicKey;
+    let marketAddress: PublicKey;
+    let higherPoolAddress: PublicKey;
+    let lowerPoolAddress: PublicKey;
+
+    const placeBet = async (user: anchor.web3.Keypair, amount: anchor.BN, direction: any) => {
+      await program.methods
+        .placeBet(amount, direction)
+        .accountsStrict({
+          bet: getBetAddress(marketAddress, user.publicKey, new anchor.BN(0), program.programId)[0],
+          userPosition: getUserPositionAddress(marketAddress, user.publicKey, program.programId)[0],
+          position: null,
+          market: marketAddress,
+          user: user.publicKey,
+          higherPool: higherPoolAddress,
+          lowerPool: lowerPoolAddress,
+          userAta: token.getAssociatedTokenAddressSync(mint, user.publicKey),
+          mint,
+          systemProgram: anchor.web3.SystemProgram.programId,
+          tokenProgram: token.TOKEN_PROGRAM_ID,
+        })
+        .signers([user])
+        .rpc()
+        .then(confirmTx);
+    };
+
+    const sellBet = (user: anchor.web3.Keypair) =>
+      program.methods
+        .sellBet()
+        .accountsStrict({
+          market: marketAddress,
+          higherPool: higherPoolAddress,
+          lowerPool: lowerPoolAddress,
+          userAta: token.getAssociatedTokenAddressSync(mint, user.publicKey),
+          user: user.publicKey,
+          bet: getBetAddress(marketAddress, user.publicKey, new anchor.BN(0), program.programId)[0],
+          treasury: getTreasuryAddress(TREASURY_SEED, mint, program.programId)[0],
+          treasuryVault: getTreasuryAddress(TREASURY_VAULT_SEED, mint, program.programId)[0],
+          mint,
+          tokenProgram: token.TOKEN_PROGRAM_ID,
+        })
+        .signers([user])
+        .rpc()
+        .then(confirmTx);
+
+    before(async () => {
+      await setMinMarketDuration(program, configAddress, configParams, new anchor.BN(1));
+
+      await airdrop(provider.connection, sellCreator.publicKey);
+      await airdrop(provider.connection, seller.publicKey);
+      await airdrop(provider.connection, counterparty.publicKey);
+      await airdrop(provider.connection, mint_authority.publicKey);
+      mint = await token.createMint(
+        provider.connection,
+        mint_authority,
+        mint_authority.publicKey,
+        null,
+        6
+      );
+      await allowMint(program, mint);
+      await mintTokens(provider.connection, mint_authority, mint, sellCreator, to_mint);
+      await mintTokens(provider.connection, mint_authority, mint, seller, to_mint);
+      await mintTokens(provider.connection, mint_authority, mint, counterparty, to_mint);
+
+      ({ marketAddress, higherPoolAddress, lowerPoolAddress } = await createShortMarket(
+        program,
+        sellCreator,
+        mint,
+        resolutionParams,
+        new anchor.BN(25)
+      ));
+    });
+
+    it("Sells a bet back at the probability it lost since it was placed", async () => {
+      //bought at 1:1 against the seeded pools, p = 1/2
+      await placeBet(seller, new anchor.BN(100000), { higher: {} });
+      //leaves the seller at 1:2 without their own stake, p = 1/3
+      await placeBet(counterparty, new anchor.BN(1000000), { lower: {} });
+
+      const [treasuryVaultAddress] = getTreasuryAddress(TREASURY_VAULT_SEED, mint, program.programId);
+      const sellerAta = token.getAssociatedTokenAddressSync(mint, seller.publicKey);
+      const balanceBefore = await token.getAccount(provider.connection, sellerAta);
+      const vaultBefore = await token.getAccount(provider.connection, treasuryVaultAddress);
+
+      await sellBet(seller);
+
+      //100000 * (1/3) / (1/2), rounded down
+      const value = new anchor.BN(66666);
+      const market = await program.account.market.fetch(marketAddress);
+      const exitFee = value.muln(market.exitFeeBps).divn(10000);
+      const balanceAfter = await token.getAccount(provider.connection, sellerAta);
+      const vaultAfter = await token.getAccount(provider.connection, treasuryVaultAddress);
+      assert.strictEqual(
+        (balanceAfter.amount - balanceBefore.amount).toString(),
+        value.sub(exitFee).toString()
+      );
+      assert.strictEqual(
+        (vaultAfter.amount - vaultBefore.amount).toString(),
+        exitFee.toString()
+      );
+      assert.isNull(
+        await program.account.bet.fetchNullable(
+          getBetAddress(marketAddress, seller.publicKey, new anchor.BN(0), program.programId)[0]
+        )
+      );
+      assert.strictEqual(market.openBets.toString(), "1");
+    });
+
+    it("Can not sell a bet once the betting window is over", async () => {
+      await waitForExpiry(program, marketAddress);
+
+      let should_fail = "This Should Fail";
+      try {
+        await sellBet(counterparty);
+      } catch (e) {
+        const anchorErr = anchor.AnchorError.parse(e.logs);
+        assert.strictEqual(
+          anchorErr.error.errorCode.code,
+          "MarketDurationOver",
+          "Unexpected Error Code"
+        );
+        should_fail = "Failed";
+      }
+      assert.strictEqual(should_fail, "Failed");
+    });
+  });
+
+  describe("Token-2022 Markets", () => {
+    const token2022Creator = anchor.web3.Keypair.generate();
+    const winner = anchor.web3.Keypair.generate();
+    const loser = anchor.web3.Keypair.generate();
+    const member = anchor.web3.Keypair.generate();
+    //a single committee member resolves the market with its proposal, no oracle needed locally
+    const committeeParams = {
+      ...resolutionParams,
+      resolutionWindow: new anchor.BN(1),
+      resolutionMode: { committee: {} },
+      committee: [member.publicKey],
+      committeeThreshold: 1,
+    };
+
+    //creates a token-2022 mint carrying a single extension, initialized by `initExtension`
+    const createExtensionMint = async (
+      extension: token.ExtensionType,
+      initExtension: (mint: PublicKey) => anchor.web3.TransactionInstruction
+    ) => {
+      const mint = anchor.web3.Keypair.generate();
+      const space = token.getMintLen([extension]);
+      const lamports = await provider.connection.getMinimumBalanceForRentExemption(space);
+      const tx = new anchor.web3.Transaction().add(
+        anchor.web3.SystemProgram.createAccount({
+          fromPubkey: mint_authority.publicKey,
+          newAccountPubkey: mint.publicKey,
+          space,
+          lamports,
+          programId: token.TOKEN_2022_PROGRAM_ID,
+        }),
+        initExtension(mint.publicKey),
+        token.createInitializeMintInstruction(
+          mint.publicKey,
+          6,
+          mint_authority.publicKey,
+          null,
+          token.TOKEN_2022_PROGRAM_ID
+        )
+      );
+      await anchor.web3.sendAndConfirmTransaction(provider.connection, tx, [mint_authority, mint]);
+      return mint.publicKey;
+    };
+
+    const expectUnsupportedMint = async (mint: PublicKey) => {
+      let should_fail = "This Should Fail";
+      try {
+        await allowMint(program, mint, token.TOKEN_2022_PROGRAM_ID);
+      } catch (e) {
+        const anchorErr = anchor.AnchorError.parse(e.logs);
+        assert.strictEqual(
+          anchorErr.error.errorCode.code,
+          "UnsupportedMintExtension",
+          "Unexpected Error Code"
+        );
+        should_fail = "Failed";
+      }
+      assert.strictEqual(should_fail, "Failed");
+    };
+
+    before(async () => {
+      await airdrop(provider.connection, token2022Creator.publicKey);
+      await airdrop(provider.connection, winner.publicKey);
+      await airdrop(provider.connection, loser.publicKey);
+      await airdrop(provider.connection, member.publicKey);
+      await airdrop(provider.connection, mint_authority.publicKey);
+    });
+
+    it("Rejects a mint with a transfer fee", async () => {
+      const mint = await createExtensionMint(token.ExtensionType.TransferFeeConfig, (mint) =>
+        token.createInitializeTransferFeeConfigInstruction(
+          mint,
+          mint_authority.publicKey,
+          mint_authority.publicKey,
+          100,
+          BigInt(1000),
+          token.TOKEN_2022_PROGRAM_ID
+        )
+      );
+      await expectUnsupportedMint(mint);
+    });
+
+    it("Rejects a mint with a transfer hook", async () => {
+      const mint = await createExtensionMint(token.ExtensionType.TransferHook, (mint) =>
+        token.createInitializeTransferHookInstruction(
+          mint,
+          mint_authority.publicKey,
+          program.programId,
+          token.TOKEN_2022_PROGRAM_ID
+        )
+      );
+      await expectUnsupportedMint(mint);
+    });
+
+    it("Bets, resolves and claims on a market funded with a token-2022 mint", async () => {
+      const mint = await token.createMint(
+        provider.connection,
+        mint_authority,
+        mint_authority.publicKey,
+        null,
+        6,
+        anchor.web3.Keypair.generate(),
+        undefined,
+        token.TOKEN_2022_PROGRAM_ID
+      );
+      await allowMint(program, mint, token.TOKEN_2022_PROGRAM_ID);
+      for (const user of [token2022Creator, winner, loser]) {
+        await mintTokens(
+          provider.connection,
+          mint_authority,
+          mint,
+          user,
+          to_mint,
+          token.TOKEN_2022_PROGRAM_ID
+        );
+      }
+
+      const { marketAddress, higherPoolAddress, lowerPoolAddress } = await createShortMarket(
+        program,
+        token2022Creator,
+        mint,
+        committeeParams,
+        new anchor.BN(15),
+        token.TOKEN_2022_PROGRAM_ID
+      );
+      const userAta = (user: anchor.web3.Keypair) =>
+        token.getAssociatedTokenAddressSync(mint, user.publicKey, false, token.TOKEN_2022_PROGRAM_ID);
+      const betAddress = (user: anchor.web3.Keypair) =>
+        getBetAddress(marketAddress, user.publicKey, new anchor.BN(0), program.programId)[0];
+
+      const bets: [anchor.web3.Keypair, number, any][] = [
+        [winner, 100000, { higher: {} }],
+        [loser, 300000, { lower: {} }],
+      ];
+      for (const [user, amount, direction] of bets) {
+        await program.methods
+          .placeBet(new anchor.BN(amount), direction)
+          .accountsStrict({
+            bet: betAddress(user),
+            userPosition: getUserPositionAddress(marketAddress, user.publicKey, program.programId)[0],
+            position: null,
+            market: marketAddress,
+            user: user.publicKey,
+            higherPool: higherPoolAddress,
+            lowerPool: lowerPoolAddress,
+            userAta: userAta(user),
+            mint,
+            systemProgram: anchor.web3.SystemProgram.programId,
+            tokenProgram: token.TOKEN_2022_PROGRAM_ID,
+          })
+          .signers([user])
+          .rpc()
+          .then(confirmTx);
+      }
+      const pool = await token.getAccount(
+        provider.connection,
+        lowerPoolAddress,
+        undefined,
+        token.TOKEN_2022_PROGRAM_ID
+      );
+      assert.strictEqual(pool.amount.toString(), SEED_LIQUIDITY_AMOUNT.addn(300000).toString());
+
+      await waitForExpiry(program, marketAddress);
+      await program.methods
+        .proposeOutcome({ higher: {} })
+        .accountsStrict({
+          market: marketAddress,
+          higherPool: higherPoolAddress,
+          lowerPool: lowerPoolAddress,
+          proposer: member.publicKey,
+        })
+        .signers([member])
+        .rpc()
+        .then(confirmTx);
+
+      await program.methods
+        .claimBet()
+        .accountsStrict({
+          market: marketAddress,
+          higherPool: higherPoolAddress,
+          lowerPool: lowerPoolAddress,
+          userAta: userAta(winner),
+          user: winner.publicKey,
+          bet: betAddress(winner),
+          treasury: getTreasuryAddress(TREASURY_SEED, mint, program.programId)[0],
+          treasuryVault: getTreasuryAddress(TREASURY_VAULT_SEED, mint, program.programId)[0],
+          systemProgram: anchor.web3.SystemProgram.programId,
+          mint,
+          tokenProgram: token.TOKEN_2022_PROGRAM_ID,
+        })
+        .signers([winner])
+        .rpc()
+        .then(confirmTx);
+
+      //100000 * 1300000 / 1100000 won, less 1181 protocol and 590 creator fees
+      const balance = await token.getAccount(
+        provider.connection,
+        userAta(winner),
+        undefined,
+        token.TOKEN_2022_PROGRAM_ID
+      );
+      assert.strictEqual(
+        balance.amount.toString(),
+        to_mint.addn(118181 - 1181 - 590).toString()
+      );
+      const vault = await token.getAccount(
+        provider.connection,
+        getTreasuryAddress(TREASURY_VAULT_SEED, mint, program.programId)[0],
+        undefined,
+        token.TOKEN_2022_PROGRAM_ID
+      );
+      assert.strictEqual(vault.amount.toString(), (1181 + 590).toString());
+    });
+  });
+
+  describe("Committee Resolution", () => {
+    const committeeCreator = anchor.web3.Keypair.generate();
+    const committee = [
+      anchor.web3.Keypair.generate(),
+      anchor.web3.Keypair.generate(),
+      anchor.web3.Keypair.generate(),
+    ];
+    let mint: PublicKey;
+
+    type CommitteeMarket = {
+      marketAddress: PublicKey;
+      higherPoolAddress: PublicKey;
+      lowerPoolAddress: PublicKey;
+    };
+
+    const propose = (member: anchor.web3.Keypair, outcome: any, market: CommitteeMarket) =>
+      program.methods
+        .proposeOutcome(outcome)
+        .accountsStrict({
+          market: market.marketAddress,
+          higherPool: market.higherPoolAddress,
+          lowerPool: market.lowerPoolAddress,
+          proposer: member.publicKey,
+        })
+        .signers([member])
+        .rpc()
+        .then(confirmTx);
+
+    const approve = (member: anchor.web3.Keypair, outcome: any, market: CommitteeMarket) =>
+      program.methods
+        .approveOutcome(outcome)
+        .accountsStrict({
+          market: market.marketAddress,
+          higherPool: market.higherPoolAddress,
+          lowerPool: market.lowerPoolAddress,
+          approver: member.publicKey,
+        })
+        .signers([member])
+        .rpc()
+        .then(confirmTx);
+
+    before(async () => {
+      await airdrop(provider.connection, committeeCreator.publicKey);
+      for (const member of committee) {
+        await airdrop(provider.connection, member.publicKey);
+      }
+      await airdrop(provider.connection, mint_authority.publicKey);
+      mint = await token.createMint(
+        provider.connection,
+        mint_authority,
+        mint_authority.publicKey,
+        null,
+        6
+      );
+      await allowMint(program, mint);
+      await mintTokens(provider.connection, mint_authority, mint, committeeCreator, to_mint);
+    });
+
+    it("Resolves to the outcome the majority backs despite an earlier wrong proposal", async () => {
+      const market = await createShortMarket(
+        program,
+        committeeCreator,
+        mint,
+        {
+          ...resolutionParams,
+          resolutionWindow: new anchor.BN(1),
+          resolutionMode: { committee: {} },
+          committee: committee.map((member) => member.publicKey),
+          committeeThreshold: 2,
+        },
+        new anchor.BN(10)
+      );
+      await waitForExpiry(program, market.marketAddress);
+
+      await propose(committee[0], { lower: {} }, market);
+      await propose(committee[1], { higher: {} }, market);
+
+      //a member backs a single outcome
+      let should_fail = "This Should Fail";
+      try {
+        await approve(committee[0], { higher: {} }, market);
+      } catch (e) {
+        const anchorErr = anchor.AnchorError.parse(e.logs);
+        assert.strictEqual(
+          anchorErr.error.errorCode.code,
+          "AlreadyApproved",
+          "Unexpected Error Code"
+        );
+        should_fail = "Failed";
+      }
+      assert.strictEqual(should_fail, "Failed");
+
+      await approve(committee[2], { higher: {} }, market);
+      const resolved = await program.account.market.fetch(market.marketAddress);
+      assert.deepEqual(resolved.status, { resolved: {} });
+      assert.deepEqual(resolved.outcome, { higher: {} });
+    });
+  });
+});
+
+async function setMinMarketDuration(
+  program: anchor.Program<PredictionMarket>,
+  configAddress: PublicKey,
+  configParams: any,
+  minMarketDuration: anchor.BN
+) {
+  await program.methods
+    .updateConfig({ ...configParams, minMarketDuration })
+    .accountsStrict({
+      config: configAddress,
+      admin: program.provider.publicKey,
+    })
+    .rpc()
+    .then(confirmTx);
+}
+
+//creates and funds a market that stops taking bets a couple of seconds from now, the creator
+//needs the seed liquidity of both pools in their token account
+async function createShortMarket(
+  program: anchor.Program<PredictionMarket>,
+  creator: anchor.web3.Keypair,
+  mint: PublicKey,
+  params: any,
+  duration = new anchor.BN(5),
+  tokenProgram = token.TOKEN_PROGRAM_ID
+) {
+  const [configAddress] = PublicKey.findProgramAddressSync(
+    [anchor.utils.bytes.utf8.encode(CONFIG_SEED)],
+    program.programId
+  );
+  const [allowedMintAddress] = PublicKey.findProgramAddressSync(
+    [anchor.utils.bytes.utf8.encode(ALLOWED_MINT_SEED), mint.toBuffer()],
+    program.programId
+  );
+  //a fresh feed id gives every short market its own address
+  const feedId = "0x" + crypto.randomBytes(32).toString("hex");
+  const targetPrice = new anchor.BN(140);
+
+  const [marketAddress] = getMarketAddress(
+    creator.publicKey,
+    feedId,
+    targetPrice,
+    duration,
+    program.programId
+  );
+  const [higherPoolAddress] = getPoolAddress(
+    HIGHER_POOL_SEED,
+    marketAddress,
+    program.programId
+  );
+  const [lowerPoolAddress] = getPoolAddress(
+    LOWER_POOL_SEED,
+    marketAddress,
+    program.programId
+  );
+
+  await program.methods
+    .initializeMarket(targetPrice, feedId, duration, params)
+    .accountsStrict({
+      market: marketAddress,
+      config: configAddress,
+      marketCreator: creator.publicKey,
+      systemProgram: anchor.web3.SystemProgram.programId,
+    })
+    .signers([creator])
+    .rpc()
+    .then(confirmTx);
+
+  if (params.outcomeBounds.length == 0) {
+    await program.methods
+      .initializePools()
+      .accountsStrict({
+        market: marketAddress,
+        allowedMint: allowedMintAddress,
+        treasury: getTreasuryAddress(TREASURY_SEED, mint, program.programId)[0],
+        marketCreator: creator.publicKey,
+        poolTokenMint: mint,
+        higherPool: higherPoolAddress,
+        lowerPool: lowerPoolAddress,
+        userAta: token.getAssociatedTokenAddressSync(mint, creator.publicKey, false, tokenProgram),
+        systemProgram: anchor.web3.SystemProgram.programId,
+        tokenProgram,
+      })
+      .signers([creator])
+      .rpc()
+      .then(confirmTx);
+  }
+
+  return { marketAddress, higherPoolAddress, lowerPoolAddress };
+}
+
+async function waitForExpiry(
+  program: anchor.Program<PredictionMarket>,
+  marketAddress: PublicKey
+) {
+  const market = await program.account.market.fetch(marketAddress);
+  //a little margin for the validator clock trailing the wall clock
+  const waitMs = (market.expiryTimestamp.toNumber() + 3) * 1000 - Date.now();
+  if (waitMs > 0) {
+    await new Promise((resolve) => setTimeout(resolve, waitMs));
+  }
+}
+
+async function initializeOutcomeMints(
+  program: anchor.Program<PredictionMarket>,
+  creator: anchor.web3.Keypair,
+  marketAddress: PublicKey,
+  mint: PublicKey
+) {
+  const addresses = getOutcomeMintAddresses(marketAddress, program.programId);
+  await program.methods
+    .initializeOutcomeMints()
+    .accountsStrict({
+      market: marketAddress,
+      higherMint: addresses.higherMint,
+      lowerMint: addresses.lowerMint,
+      setVault: addresses.setVault,
+      mint,
+      marketCreator: creator.publicKey,
+      systemProgram: anchor.web3.SystemProgram.programId,
+      tokenProgram: token.TOKEN_PROGRAM_ID,
+    })
+    .signers([creator])
+    .rpc()
+    .then(confirmTx);
+  return addresses;
+}
+
+//buys outcome tokens from the market maker and returns how many were minted to the user
+async function buyOutcomeTokens(
+  program: anchor.Program<PredictionMarket>,
+  user: anchor.web3.Keypair,
+  marketAddress: PublicKey,
+  mint: PublicKey,
+  amount: anchor.BN,
+  direction: any
+) {
+  const { higherMint, lowerMint } = getOutcomeMintAddresses(marketAddress, program.programId);
+  const outcomeMint = direction.higher ? higherMint : lowerMint;
+  const userOutcomeAta = token.getAssociatedTokenAddressSync(outcomeMint, user.publicKey);
+  const before = await token
+    .getAccount(program.provider.connection, userOutcomeAta)
+    .then((account) => account.amount)
+    .catch(() => BigInt(0));
+
+  await program.methods
+    .buyOutcomeTokens(amount, direction, new anchor.BN(0))
+    .accountsStrict({
+      market: marketAddress,
+      higherPool: getPoolAddress(HIGHER_POOL_SEED, marketAddress, program.programId)[0],
+      lowerPool: getPoolAddress(LOWER_POOL_SEED, marketAddress, program.programId)[0],
+      outcomeMint,
+      userOutcomeAta,
+      userAta: token.getAssociatedTokenAddressSync(mint, user.publicKey),
+      user: user.publicKey,
+      treasury: getTreasuryAddress(TREASURY_SEED, mint, program.programId)[0],
+      treasuryVault: getTreasuryAddress(TREASURY_VAULT_SEED, mint, program.programId)[0],
+      mint,
+      systemProgram: anchor.web3.SystemProgram.programId,
+      tokenProgram: token.TOKEN_PROGRAM_ID,
+      associatedTokenProgram: token.ASSOCIATED_TOKEN_PROGRAM_ID,
+    })
+    .signers([user])
+    .rpc()
+    .then(confirmTx);
+
+  const after = await token.getAccount(program.provider.connection, userOutcomeAta);
+  return new anchor.BN((after.amount - before).toString());
+}
+
+async function mintCompleteSet(
+  program: anchor.Program<PredictionMarket>,
+  user: anchor.web3.Keypair,
+  marketAddress: PublicKey,
+  mint: PublicKey,
+  amount: anchor.BN
+) {
+  const { higherMint, lowerMint, setVault } = getOutcomeMintAddresses(marketAddress, program.programId);
+  await program.methods
+    .mintCompleteSet(amount)
+    .accountsStrict({
+      market: marketAddress,
+      setVault,
+      higherMint,
+      lowerMint,
+      userHigherAta: token.getAssociatedTokenAddressSync(higherMint, user.publicKey),
+      userLowerAta: token.getAssociatedTokenAddressSync(lowerMint, user.publicKey),
+      userAta: token.getAssociatedTokenAddressSync(mint, user.publicKey),
+      user: user.publicKey,
+      mint,
+      systemProgram: anchor.web3.SystemProgram.programId,
+      tokenProgram: token.TOKEN_PROGRAM_ID,
+      associatedTokenProgram: token.ASSOCIATED_TOKEN_PROGRAM_ID,
+    })
+    .signers([user])
+    .rpc()
+    .then(confirmTx);
+}
+
+async function redeemOutcomeTokens(
+  program: anchor.Program<PredictionMarket>,
+  user: anchor.web3.Keypair,
+  marketAddress: PublicKey,
+  mint: PublicKey,
+  amount: anchor.BN,
+  direction: any
+) {
+  const { higherMint, lowerMint, setVault } = getOutcomeMintAddresses(marketAddress, program.programId);
+  const outcomeMint = direction.higher ? higherMint : lowerMint;
+  await program.methods
+    .redeem(amount, direction)
+    .accountsStrict({
+      market: marketAddress,
+      higherPool: getPoolAddress(HIGHER_POOL_SEED, marketAddress, program.programId)[0],
+      lowerPool: getPoolAddress(LOWER_POOL_SEED, marketAddress, program.programId)[0],
+      setVault,
+      outcomeMint,
+      userOutcomeAta: token.getAssociatedTokenAddressSync(outcomeMint, user.publicKey),
+      userAta: token.getAssociatedTokenAddressSync(mint, user.publicKey),
+      user: user.publicKey,
+      mint,
+      tokenProgram: token.TOKEN_PROGRAM_ID,
+    })
+    .signers([user])
+    .rpc()
+    .then(confirmTx);
+}
+
+async function mintTokens(
+  connection: anchor.web3.Connection,
+  mintAuthority: anchor.web3.Keypair,
+  mint: PublicKey,
+  owner: anchor.web3.Keypair,
+  amount: anchor.BN,
+  tokenProgram = token.TOKEN_PROGRAM_ID
+) {
+  const ata = await token.getOrCreateAssociatedTokenAccount(
+    connection,
+    owner,
+    mint,
+    owner.publicKey,
+    false,
+    undefined,
+    undefined,
+    tokenProgram
+  );
+  await token.mintTo(
+    connection,
+    mintAuthority,
+    mint,
+    ata.address,
+    mintAuthority,
+    amount.toNumber(),
+    [],
+    undefined,
+    tokenProgram
+  );
+  return ata.address;
+}
+
+async function allowMint(
+  program: anchor.Program<PredictionMarket>,
+  mint: PublicKey,
+  tokenProgram = token.TOKEN_PROGRAM_ID
+) {
+  const [configAddress] = PublicKey.findProgramAddressSync(
+    [anchor.utils.bytes.utf8.encode(CONFIG_SEED)],
+    program.programId
+  );
+  const [allowedMintAddress] = PublicKey.findProgramAddressSync(
+    [anchor.utils.bytes.utf8.encode(ALLOWED_MINT_SEED), mint.toBuffer()],
+    program.programId
+  );
+
+  await program.methods
+    .addAllowedMint(new anchor.BN(1), new anchor.BN(1000000000), SEED_LIQUIDITY_AMOUNT)
+    .accountsStrict({
+      config: configAddress,
+      allowedMint: allowedMintAddress,
+      mint,
+      admin: program.provider.publicKey,
+      systemProgram: anchor.web3.SystemProgram.programId,
+    })
+    .rpc()
+    .then(confirmTx);
+
+  //pools can only be funded once the treasury collecting the fees of the mint exists
+  await program.methods
+    .initializeTreasury()
+    .accountsStrict({
+      config: configAddress,
+      treasury: getTreasuryAddress(TREASURY_SEED, mint, program.programId)[0],
+      treasuryVault: getTreasuryAddress(TREASURY_VAULT_SEED, mint, program.programId)[0],
+      mint,
+      admin: program.provider.publicKey,
+      systemProgram: anchor.web3.SystemProgram.programId,
+      tokenProgram,
+    })
+    .rpc()
+    .then(confirmTx);
+
+  return allowedMintAddress;
+}
+
+async function airdrop(
+  connection: anchor.web3.Connection,
+  address: PublicKey,
+  amount = 10 * LAMPORTS_PER_SOL
+) {
+  await connection.requestAirdrop(address, amount).then(confirmTx);
+}
+
+function getMarketAddress(
+  creator: PublicKey,
+  feedId: string,
+  targetPrice: anchor.BN,
+  marketDuration: anchor.BN,
+  programID: PublicKey
+) {
+  let hexString = crypto
+    .createHash("sha256")
+    .update(feedId, "utf-8")
+    .digest("hex");
+  let feed_seed = Uint8Array.from(Buffer.from(hexString, "hex"));
+
+  return PublicKey.findProgramAddressSync(
+    [
+      creator.toBuffer(),
+      feed_seed,
+      targetPrice.toArrayLike(Buffer, "le", 8),
+      marketDuration.toArrayLike(Buffer, "le", 8),
+    ],
+    programID
+  );
+}
+
+function getPoolAddress(
+  poolStringSeed: string,
+  marketAddress: PublicKey,
+  programId: PublicKey
+) {
+  return PublicKey.findProgramAddressSync(
+    [anchor.utils.bytes.utf8.encode(poolStringSeed), marketAddress.toBuffer()],
+    programId
+  );
+}
+
+function getTreasuryAddress(
+  treasuryStringSeed: string,
+  mint: PublicKey,
+  programId: PublicKey
+) {
+  return PublicKey.findProgramAddressSync(
+    [anchor.utils.bytes.utf8.encode(treasuryStringSeed), mint.toBuffer()],
+    programId
+  );
+}
+
+function getOutcomeMintAddresses(marketAddress: PublicKey, programId: PublicKey) {
+  const [higherMint, lowerMint, setVault] = [HIGHER_MINT_SEED, LOWER_MINT_SEED, SET_VAULT_SEED].map(
+    (seed) =>
+      PublicKey.findProgramAddressSync(
+        [anchor.utils.bytes.utf8.encode(seed), marketAddress.toBuffer()],
+        programId
+      )[0]
+  );
+  return { higherMint, lowerMint, setVault };
+}
+
+function getOutcomePoolAddress(
+  marketAddress: PublicKey,
+  outcomeIndex: number,
+  programId: PublicKey
+) {
+  return PublicKey.findProgramAddressSync(
+    [
+      anchor.utils.bytes.utf8.encode(OUTCOME_POOL_SEED),
+      marketAddress.toBuffer(),
+      Buffer.from([outcomeIndex]),
+    ],
+    programId
+  );
+}
+
+function getUserPositionAddress(
+  marketAddress: PublicKey,
+  userAddress: PublicKey,
+  programId: PublicKey
+) {
+  return PublicKey.findProgramAddressSync(
+    [
+      anchor.utils.bytes.utf8.encode(USER_POSITION_SEED),
+      userAddress.toBuffer(),
+      marketAddress.toBuffer(),
+    ],
+    programId
+  );
+}
+
+function getBetAddress(
+  marketAddress: PublicKey,
+  userAddress: PublicKey,
+  betId: anchor.BN,
+  programId: PublicKey
+) {
   return PublicKey.findProgramAddressSync(
     [
-      anchor.utils.bytes.utf8.encode(betStringSeed),
+      anchor.utils.bytes.utf8.encode(BET_SEED),
       userAddress.toBuffer(),
       marketAddress.toBuffer(),
-      betAmount.toArrayLike(Buffer, "le", 8),
-      Buffer.from(new Uint8Array([directionNum])),
+      betId.toArrayLike(Buffer, "le", 8),
     ],
     programId
   );
@@ -708,7 +2957,7 @@ async function checkMarket(
   targetPrice: anchor.BN,
   marketDuration: anchor.BN,
   bump: number,
-  initialization: Object,
+  status: Object,
   higherPoolBump?: number,
   lowerPoolBump?: number,
   mint?: PublicKey
@@ -734,8 +2983,8 @@ async function checkMarket(
   );
 
   assert.strictEqual(
-    Object.keys(marketData.initialization)[0],
-    Object.keys(initialization)[0]
+    Object.keys(marketData.status)[0],
+    Object.keys(status)[0]
   );
 
   if (higherPoolBump) {